@@ -13,7 +13,8 @@ criterion_group!(
     benches,
     replicate::<UsizeComponent>,
     replicate::<StringComponent>,
-    replicate::<StructComponent>
+    replicate::<StructComponent>,
+    shared_writes
 );
 
 const ENTITIES: usize = 1000;
@@ -21,7 +22,7 @@ const ENTITIES: usize = 1000;
 fn replicate<C: BenchmarkComponent>(c: &mut Criterion) {
     let mut g = c.benchmark_group(C::NAME);
 
-    for clients_count in [1, 20] {
+    for clients_count in [1, 10, 50] {
         g.bench_function(BenchmarkId::new("changes_send", clients_count), |b| {
             b.iter_custom(|iter| changes_send::<C>(iter, clients_count))
         });
@@ -38,6 +39,73 @@ fn replicate<C: BenchmarkComponent>(c: &mut Criterion) {
     });
 }
 
+/// Measures sending when all clients need the same data in the same tick.
+///
+/// Every client visits the same mutated components, so tasks that process clients
+/// in parallel race to serialize them first.
+fn shared_writes(c: &mut Criterion) {
+    let mut g = c.benchmark_group("shared_writes");
+
+    for clients_count in [1, 10, 50] {
+        g.bench_function(BenchmarkId::new("mutations_send", clients_count), |b| {
+            b.iter_custom(|iter| shared_mutations_send(iter, clients_count))
+        });
+    }
+}
+
+fn shared_mutations_send(iter: u64, clients_count: usize) -> Duration {
+    let mut server_app = create_shared_app();
+    let mut client_apps = Vec::new();
+    for _ in 0..clients_count {
+        client_apps.push(create_shared_app());
+    }
+
+    for client_app in &mut client_apps {
+        server_app.connect_client(client_app);
+    }
+
+    server_app.world_mut().spawn_batch(vec![
+        (
+            Replicated,
+            UsizeComponent::default(),
+            StringComponent::default(),
+            StructComponent::default(),
+        );
+        ENTITIES
+    ]);
+    let mut query = server_app.world_mut().query::<(
+        &mut UsizeComponent,
+        &mut StringComponent,
+        &mut StructComponent,
+    )>();
+
+    server_app.update();
+    for client_app in &mut client_apps {
+        server_app.exchange_with_client(client_app);
+        client_app.update();
+        server_app.exchange_with_client(client_app);
+    }
+
+    let mut elapsed = Duration::ZERO;
+    for _ in 0..iter {
+        for (mut a, mut b, mut c) in query.iter_mut(server_app.world_mut()) {
+            a.set_changed();
+            b.set_changed();
+            c.set_changed();
+        }
+
+        let instant = Instant::now();
+        server_app.update();
+        elapsed += instant.elapsed();
+
+        // Only sending is measured, so drop messages instead of receiving them on each client.
+        let mut messages = server_app.world_mut().resource_mut::<ServerMessages>();
+        for _ in messages.drain_sent() {}
+    }
+
+    elapsed
+}
+
 fn changes_send<C: BenchmarkComponent>(iter: u64, clients_count: usize) -> Duration {
     let mut elapsed = Duration::ZERO;
     for _ in 0..iter {
@@ -195,6 +263,21 @@ fn create_app<C: BenchmarkComponent>() -> App {
     app
 }
 
+fn create_shared_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        StatesPlugin,
+        RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+    ))
+    .replicate::<UsizeComponent>()
+    .replicate::<StringComponent>()
+    .replicate::<StructComponent>()
+    .finish();
+
+    app
+}
+
 trait BenchmarkComponent:
     Component<Mutability = Mutable> + Default + Serialize + DeserializeOwned + Clone
 {
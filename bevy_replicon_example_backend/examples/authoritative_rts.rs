@@ -144,7 +144,10 @@ fn apply_team_request(
             .client_id
             .entity()
             .expect("server can't request an invalid team");
-        disconnects.write(DisconnectRequest { client });
+        disconnects.write(DisconnectRequest {
+            client,
+            reason: DisconnectReason::Custom("team is already taken".into()),
+        });
         return;
     }
 
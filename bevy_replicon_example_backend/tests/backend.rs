@@ -55,7 +55,12 @@ fn disconnect_request() {
         app.add_plugins((
             MinimalPlugins,
             StatesPlugin,
-            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+            RepliconPlugins
+                .set(RepliconSharedPlugin {
+                    disconnect_reasons: true,
+                    ..Default::default()
+                })
+                .set(ServerPlugin::new(PostUpdate)),
             RepliconExampleBackendPlugins,
         ))
         .add_server_message::<Test>(Channel::Ordered)
@@ -74,9 +79,16 @@ fn disconnect_request() {
         .world_mut()
         .query_filtered::<Entity, With<ConnectedClient>>();
     let client = clients.single(server_app.world()).unwrap();
-    server_app
-        .world_mut()
-        .write_message(DisconnectRequest { client });
+    server_app.world_mut().write_message(DisconnectRequest {
+        client,
+        reason: DisconnectReason::Kicked,
+    });
+
+    client_app.init_resource::<ReceivedReason>().add_observer(
+        |disconnected: On<Disconnected>, mut received: ResMut<ReceivedReason>| {
+            received.0 = disconnected.reason.clone();
+        },
+    );
 
     server_app.update();
     client_app.update();
@@ -89,6 +101,9 @@ fn disconnect_request() {
     let messages = client_app.world().resource::<Messages<Test>>();
     assert_eq!(messages.len(), 1, "last message should be received");
 
+    let received = client_app.world().resource::<ReceivedReason>();
+    assert_eq!(received.0, Some(DisconnectReason::Kicked));
+
     let mut replicated = client_app.world_mut().query::<&Replicated>();
     assert_eq!(
         replicated.iter(client_app.world()).len(),
@@ -245,3 +260,6 @@ fn setup(server_app: &mut App, client_app: &mut App) -> io::Result<()> {
 
 #[derive(Message, Serialize, Deserialize)]
 struct Test;
+
+#[derive(Resource, Default)]
+struct ReceivedReason(Option<DisconnectReason>);
@@ -1,6 +1,7 @@
 pub mod confirm_history;
 #[cfg(feature = "client_diagnostics")]
 pub mod diagnostics;
+pub mod entity_pool;
 pub mod message;
 pub mod server_mutate_ticks;
 
@@ -13,13 +14,17 @@ use crate::{
     postcard_utils,
     prelude::*,
     shared::{
-        backend::channels::{ClientChannel, ServerChannel},
+        backend::{
+            DisconnectNotice,
+            channels::{ClientChannel, ServerChannel},
+        },
+        protocol::versioning::{ProtocolAccepted, ProtocolManifest, ServerFnsMap},
         replication::{
             command_markers::{CommandMarkers, EntityMarkers},
             deferred_entity::{DeferredChanges, DeferredEntity},
             mutate_index::MutateIndex,
             registry::{
-                ReplicationRegistry,
+                FnsId, ReplicationRegistry,
                 ctx::{DespawnCtx, RemoveCtx, WriteCtx},
             },
             signature::SignatureMap,
@@ -27,9 +32,11 @@ use crate::{
             update_message_flags::UpdateMessageFlags,
         },
         server_entity_map::{EntityEntry, ServerEntityMap},
+        token_auth::{AuthRejected, AuthToken, TokenHandshake},
     },
 };
 use confirm_history::{ConfirmHistory, EntityReplicated};
+use entity_pool::EntityPool;
 use server_mutate_ticks::{MutateTickReceived, ServerMutateTicks};
 
 /// Client functionality and replication receiving.
@@ -39,11 +46,14 @@ pub struct ClientPlugin;
 
 impl Plugin for ClientPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<ClientMessages>()
+        app.register_type::<ServerUpdateTick>()
+            .register_type::<ConfirmHistory>()
+            .init_resource::<ClientMessages>()
             .init_resource::<ClientStats>()
             .init_resource::<ServerEntityMap>()
             .init_resource::<ServerUpdateTick>()
             .init_resource::<BufferedMutations>()
+            .init_resource::<ReceivedDisconnectReason>()
             .add_message::<EntityReplicated>()
             .add_message::<MutateTickReceived>()
             .configure_sets(
@@ -78,18 +88,40 @@ impl Plugin for ClientPlugin {
                 OnEnter(ClientState::Connected),
                 receive_replication.in_set(ClientSystems::Receive),
             )
+            .add_systems(
+                PreUpdate,
+                receive_disconnect_reason
+                    .after(ClientSystems::Receive)
+                    .run_if(in_state(ClientState::Connected))
+                    .run_if(resource_exists::<Messages<DisconnectNotice>>),
+            )
             .add_systems(
                 OnExit(ClientState::Connected),
-                reset.in_set(ClientSystems::Reset),
+                (reset, trigger_disconnected).in_set(ClientSystems::Reset),
             );
 
         let auth_method = *app.world().resource::<AuthMethod>();
         debug!("using authorization method `{auth_method:?}`");
-        if auth_method == AuthMethod::ProtocolCheck {
-            app.add_observer(log_protocol_error).add_systems(
-                OnEnter(ClientState::Connected),
-                send_protocol_hash.in_set(ClientSystems::SendHash),
-            );
+        if let AuthMethod::Token { .. } = auth_method {
+            app.add_observer(log_protocol_error)
+                .add_observer(log_auth_rejection)
+                .add_systems(
+                    OnEnter(ClientState::Connected),
+                    send_token_handshake.in_set(ClientSystems::SendHash),
+                );
+        } else if auth_method == AuthMethod::ProtocolCheck {
+            app.add_observer(log_protocol_error);
+            if app.world().contains_resource::<ProtocolVersioning>() {
+                app.add_observer(receive_protocol_accepted).add_systems(
+                    OnEnter(ClientState::Connected),
+                    send_protocol_manifest.in_set(ClientSystems::SendHash),
+                );
+            } else {
+                app.add_systems(
+                    OnEnter(ClientState::Connected),
+                    send_protocol_hash.in_set(ClientSystems::SendHash),
+                );
+            }
         }
 
         if log_enabled!(Level::Debug) {
@@ -109,7 +141,7 @@ impl Plugin for ClientPlugin {
         app.world_mut()
             .resource_scope(|world, mut messages: Mut<ClientMessages>| {
                 let channels = world.resource::<RepliconChannels>();
-                messages.setup_server_channels(channels.server_channels().len());
+                messages.setup_channels(channels);
             });
     }
 }
@@ -135,6 +167,11 @@ pub(super) fn receive_replication(
     mut changes: Local<DeferredChanges>,
     mut entity_markers: Local<EntityMarkers>,
 ) {
+    if world.contains_resource::<ProtocolManifest>() && !world.contains_resource::<ServerFnsMap>() {
+        // Keep messages until the server sends the mapping for replication functions.
+        return;
+    }
+
     world.resource_scope(|world, mut messages: Mut<ClientMessages>| {
         world.resource_scope(|world, mut entity_map: Mut<ServerEntityMap>| {
             world.resource_scope(|world, mut signature_map: Mut<SignatureMap>| {
@@ -148,6 +185,8 @@ pub(super) fn receive_replication(
                                         world.remove_resource::<ClientReplicationStats>();
                                     let mut mutate_ticks =
                                         world.remove_resource::<ServerMutateTicks>();
+                                    let mut entity_pool = world.remove_resource::<EntityPool>();
+                                    let fns_map = world.remove_resource::<ServerFnsMap>();
                                     let mut params = ReceiveParams {
                                         changes: &mut changes,
                                         entity_markers: &mut entity_markers,
@@ -156,8 +195,10 @@ pub(super) fn receive_replication(
                                         replicated: &mut replicated,
                                         mutate_ticks: mutate_ticks.as_mut(),
                                         stats: stats.as_mut(),
+                                        entity_pool: entity_pool.as_mut(),
                                         command_markers: &command_markers,
                                         registry: &registry,
+                                        fns_map: fns_map.as_ref(),
                                         type_registry: &type_registry,
                                     };
 
@@ -174,6 +215,12 @@ pub(super) fn receive_replication(
                                     if let Some(mutate_ticks) = mutate_ticks {
                                         world.insert_resource(mutate_ticks);
                                     }
+                                    if let Some(entity_pool) = entity_pool {
+                                        world.insert_resource(entity_pool);
+                                    }
+                                    if let Some(fns_map) = fns_map {
+                                        world.insert_resource(fns_map);
+                                    }
                                 },
                             )
                         })
@@ -185,6 +232,7 @@ pub(super) fn receive_replication(
 }
 
 fn reset(
+    mut commands: Commands,
     mut messages: ResMut<ClientMessages>,
     mut stats: ResMut<ClientStats>,
     mut update_tick: ResMut<ServerUpdateTick>,
@@ -204,6 +252,8 @@ fn reset(
     if let Some(mut replication_stats) = replication_stats {
         *replication_stats = Default::default();
     }
+    commands.remove_resource::<InitialSyncProgress>();
+    commands.remove_resource::<ServerFnsMap>();
 }
 
 fn send_protocol_hash(mut commands: Commands, protocol: Res<ProtocolHash>) {
@@ -211,6 +261,55 @@ fn send_protocol_hash(mut commands: Commands, protocol: Res<ProtocolHash>) {
     commands.client_trigger(*protocol);
 }
 
+fn send_protocol_manifest(mut commands: Commands, manifest: Res<ProtocolManifest>) {
+    debug!(
+        "sending manifest for version {} to the server",
+        manifest.version()
+    );
+    commands.client_trigger(manifest.clone());
+}
+
+fn receive_protocol_accepted(
+    accepted: On<ProtocolAccepted>,
+    mut commands: Commands,
+    registry: Res<ReplicationRegistry>,
+) {
+    debug!("received mapping for replication functions from the server");
+    commands.insert_resource(ServerFnsMap::new(&registry, &accepted));
+}
+
+fn send_token_handshake(
+    mut commands: Commands,
+    protocol: Res<ProtocolHash>,
+    token: Option<Res<AuthToken>>,
+) {
+    debug!("sending `{:?}` with auth token to the server", *protocol);
+    commands.client_trigger(TokenHandshake {
+        protocol: *protocol,
+        payload: token.map(|token| token.0.clone()).unwrap_or_default(),
+    });
+}
+
+fn receive_disconnect_reason(
+    mut notices: ResMut<Messages<DisconnectNotice>>,
+    mut received: ResMut<ReceivedDisconnectReason>,
+) {
+    if let Some(notice) = notices.drain().last() {
+        debug!("received disconnect reason `{:?}`", notice.reason);
+        **received = Some(notice.reason);
+    }
+}
+
+fn trigger_disconnected(mut commands: Commands, mut received: ResMut<ReceivedDisconnectReason>) {
+    let reason = received.take();
+    debug!("triggering disconnect with reason `{reason:?}`");
+    commands.trigger(Disconnected { reason });
+}
+
+fn log_auth_rejection(rejected: On<AuthRejected>) {
+    error!("server rejected authorization: {}", rejected.reason);
+}
+
 fn log_protocol_error(_on: On<ProtocolMismatch>) {
     error!(
         "server reported protocol mismatch; make sure replication rules and events registration order match with the server"
@@ -273,6 +372,7 @@ fn apply_update_message(
     trace!("applying update message with `{flags:?}` for {message_tick:?}");
     world.resource_mut::<ServerUpdateTick>().0 = message_tick;
 
+    let mut sync = None;
     let last_flag = flags.last();
     for (_, flag) in flags.iter_names() {
         let array_kind = if flag != last_flag {
@@ -300,6 +400,12 @@ fn apply_update_message(
                     stats.despawns += len;
                 }
             }
+            UpdateMessageFlags::HIDDEN => {
+                apply_array(array_kind, message, |message| {
+                    apply_hidden(world, params, message, message_tick)
+                })
+                .map_err(|e| format!("unable to apply visibility losses: {e}"))?;
+            }
             UpdateMessageFlags::REMOVALS => {
                 let len = apply_array(array_kind, message, |message| {
                     apply_removals(world, params, message, message_tick)
@@ -309,6 +415,11 @@ fn apply_update_message(
                     stats.entities_changed += len;
                 }
             }
+            UpdateMessageFlags::SYNC => {
+                let synced = postcard_utils::from_buf(message)?;
+                let total = postcard_utils::from_buf(message)?;
+                sync = Some(InitialSyncProgress { synced, total });
+            }
             UpdateMessageFlags::CHANGES => {
                 debug_assert_eq!(array_kind, ArrayKind::Dynamic);
                 let len = apply_array(array_kind, message, |message| {
@@ -323,6 +434,16 @@ fn apply_update_message(
         }
     }
 
+    // Apply the progress after all entities from the message.
+    if let Some(sync) = sync {
+        trace!("received initial sync {}/{}", sync.synced, sync.total);
+        world.insert_resource(sync);
+        if sync.is_finished() {
+            debug!("finished initial sync");
+            world.trigger(InitialSyncFinished);
+        }
+    }
+
     Ok(())
 }
 
@@ -450,8 +571,62 @@ fn apply_despawn(
     {
         trace!("applying despawn for `{}`", client_entity.id());
         params.signature_map.remove(client_entity.id()); // // Requires manual removal since the map is removed from the world and inaccessible to triggers.
-        let ctx = DespawnCtx { message_tick };
-        (params.registry.despawn)(&ctx, client_entity);
+        let client_entity = match &mut params.entity_pool {
+            Some(entity_pool) => match entity_pool.put(client_entity) {
+                Some(client_entity) => client_entity,
+                None => return Ok(()),
+            },
+            None => client_entity,
+        };
+
+        params
+            .entity_markers
+            .read(params.command_markers, &client_entity);
+        let despawn = params.registry.despawn_fn(params.entity_markers);
+        let ctx = DespawnCtx {
+            message_tick,
+            visibility_lost: false,
+        };
+        (despawn)(&ctx, client_entity);
+    }
+
+    Ok(())
+}
+
+/// Deserializes and applies visibility loss for an entity from update message.
+///
+/// Triggers [`EntityLeftInterest`] before calling [`ReplicationRegistry::visibility_lost`].
+fn apply_hidden(
+    world: &mut World,
+    params: &mut ReceiveParams,
+    message: &mut Bytes,
+    message_tick: RepliconTick,
+) -> Result<()> {
+    // Like with despawns, the entity might have already been despawned on the client.
+    let server_entity = postcard_utils::entity_from_buf(message)?;
+    let Some(client_entity) = params
+        .entity_map
+        .server_entry(server_entity)
+        .remove()
+        .filter(|&entity| world.entities().contains(entity))
+    else {
+        return Ok(());
+    };
+
+    trace!("applying visibility loss for `{client_entity}`");
+    params.signature_map.remove(client_entity); // Requires manual removal since the map is removed from the world and inaccessible to triggers.
+    world.trigger(EntityLeftInterest {
+        entity: client_entity,
+        tick: message_tick,
+    });
+
+    // Observers could despawn the entity.
+    if let Ok(client_entity) = world.get_entity_mut(client_entity) {
+        let ctx = DespawnCtx {
+            message_tick,
+            visibility_lost: true,
+        };
+        (params.registry.visibility_lost)(&ctx, client_entity);
     }
 
     Ok(())
@@ -489,6 +664,13 @@ fn apply_removals(
 
     let len = apply_array(ArrayKind::Sized, message, |message| {
         let fns_id = postcard_utils::from_buf(message)?;
+        let Some(fns_id) = resolve_fns_id(params.fns_map, fns_id) else {
+            trace!(
+                "skipping removal for `{}` with unknown `{fns_id:?}`",
+                client_entity.id()
+            );
+            return Ok(());
+        };
         let (component_id, component_fns, _) = params.registry.get(fns_id);
         let mut ctx = RemoveCtx {
             message_tick,
@@ -528,15 +710,20 @@ fn apply_changes(
     // The latter won't apply any structural changes until `flush`, and `Entities` won't be used afterward.
     let world = unsafe { world_cell.world_mut() };
 
-    let mut client_entity = match params.entity_map.server_entry(server_entity) {
-        EntityEntry::Occupied(entry) => {
-            DeferredEntity::new(world.get_entity_mut(entry.get())?, params.changes)
-        }
+    let (mut client_entity, spawned) = match params.entity_map.server_entry(server_entity) {
+        EntityEntry::Occupied(entry) => (
+            DeferredEntity::new(world.get_entity_mut(entry.get())?, params.changes),
+            false,
+        ),
         EntityEntry::Vacant(entry) => {
-            let mut client_entity = DeferredEntity::new(world.spawn_empty(), params.changes);
+            let entity = match &mut params.entity_pool {
+                Some(entity_pool) => entity_pool.reserve(world),
+                None => world.spawn_empty(),
+            };
+            let mut client_entity = DeferredEntity::new(entity, params.changes);
             client_entity.insert(Replicated);
             entry.insert(client_entity.id());
-            client_entity
+            (client_entity, true)
         }
     };
 
@@ -544,10 +731,12 @@ fn apply_changes(
         .entity_markers
         .read(params.command_markers, &*client_entity);
 
-    confirm_tick(&mut client_entity, params.replicated, message_tick);
+    if !spawned {
+        confirm_tick(&mut client_entity, params.replicated, message_tick);
+    }
 
     let len = apply_array(ArrayKind::Sized, message, |message| {
-        let fns_id = postcard_utils::from_buf(message)?;
+        let fns_id = read_fns_id(params.fns_map, message)?;
         let (component_id, component_fns, rule_fns) = params.registry.get(fns_id);
         let mut ctx = WriteCtx {
             entity_map: params.entity_map,
@@ -580,11 +769,45 @@ fn apply_changes(
         stats.components_changed += len;
     }
 
+    if spawned {
+        if let Some(entity_pool) = &mut params.entity_pool {
+            // Pools are matched by written components, so the staging entity can be replaced only after writing them.
+            let spawned_entity = client_entity.id();
+            client_entity = entity_pool.take(client_entity);
+            if client_entity.id() != spawned_entity {
+                params.entity_map.server_entry(server_entity).remove();
+                params.entity_map.insert(server_entity, client_entity.id());
+            }
+        }
+
+        // Confirm after the possible replacement to report the right entity.
+        confirm_tick(&mut client_entity, params.replicated, message_tick);
+    }
+
     client_entity.flush();
 
     Ok(())
 }
 
+/// Reads the server's [`FnsId`] and resolves it into the client's.
+fn read_fns_id(fns_map: Option<&ServerFnsMap>, message: &mut Bytes) -> Result<FnsId> {
+    let fns_id = postcard_utils::from_buf(message)?;
+    let fns_id =
+        resolve_fns_id(fns_map, fns_id).ok_or_else(|| format!("received unknown `{fns_id:?}`"))?;
+
+    Ok(fns_id)
+}
+
+/// Maps the server's [`FnsId`] to the client's in the versioned protocol mode.
+///
+/// Returns the ID as is otherwise.
+fn resolve_fns_id(fns_map: Option<&ServerFnsMap>, fns_id: FnsId) -> Option<FnsId> {
+    match fns_map {
+        Some(fns_map) => fns_map.get(fns_id),
+        None => Some(fns_id),
+    }
+}
+
 fn apply_array(
     kind: ArrayKind,
     message: &mut Bytes,
@@ -703,7 +926,7 @@ fn apply_mutations(
     let mut data = message.split_to(data_size);
     let mut components_count = 0;
     while data.has_remaining() {
-        let fns_id = postcard_utils::from_buf(&mut data)?;
+        let fns_id = read_fns_id(params.fns_map, &mut data)?;
         let (component_id, component_fns, rule_fns) = params.registry.get(fns_id);
         let mut ctx = WriteCtx {
             entity_map: params.entity_map,
@@ -763,8 +986,10 @@ struct ReceiveParams<'a> {
     replicated: &'a mut Messages<EntityReplicated>,
     mutate_ticks: Option<&'a mut ServerMutateTicks>,
     stats: Option<&'a mut ClientReplicationStats>,
+    entity_pool: Option<&'a mut EntityPool>,
     command_markers: &'a CommandMarkers,
     registry: &'a ReplicationRegistry,
+    fns_map: Option<&'a ServerFnsMap>,
     type_registry: &'a AppTypeRegistry,
 }
 
@@ -817,9 +1042,14 @@ pub enum ClientSystems {
 /// This value is not updated when mutation messages are received from the server.
 ///
 /// See also [`ServerMutateTicks`].
-#[derive(Clone, Copy, Debug, Default, Deref, Resource)]
+#[derive(Clone, Copy, Debug, Default, Deref, Resource, Reflect)]
+#[reflect(Resource)]
 pub struct ServerUpdateTick(RepliconTick);
 
+/// Reason of the upcoming disconnection received from the server.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct ReceivedDisconnectReason(Option<DisconnectReason>);
+
 /// Cached buffered mutate messages, used to synchronize mutations with update messages.
 #[derive(Default, Resource)]
 pub(crate) struct BufferedMutations(Vec<BufferedMutate>);
@@ -857,6 +1087,57 @@ pub(super) struct BufferedMutate {
     message: Bytes,
 }
 
+/// An event that indicates that a replicated entity is no longer visible to the client.
+///
+/// Triggered before calling [`ReplicationRegistry::visibility_lost`], so the entity
+/// still has all its components. The entity is no longer mapped in [`ServerEntityMap`]
+/// and won't receive any updates.
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct EntityLeftInterest {
+    /// Entity that became hidden.
+    pub entity: Entity,
+
+    /// Message tick.
+    pub tick: RepliconTick,
+}
+
+/// Progress of the streamed initial sync.
+///
+/// Inserted on the first received chunk if the server uses
+/// [`InitialSync::Streamed`] and removed on disconnect.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct InitialSyncProgress {
+    /// Number of entities received so far.
+    pub synced: usize,
+
+    /// Total number of entities to sync.
+    ///
+    /// May grow while syncing if new entities become visible for the client.
+    pub total: usize,
+}
+
+impl InitialSyncProgress {
+    /// Returns the fraction of synced entities in range `0.0..=1.0`.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        self.synced as f32 / self.total as f32
+    }
+
+    /// Returns `true` if all entities were received.
+    pub fn is_finished(&self) -> bool {
+        self.synced == self.total
+    }
+}
+
+/// An event that indicates that the streamed initial sync is finished.
+///
+/// Triggered after applying the last chunk.
+/// See also [`InitialSyncProgress`].
+#[derive(Event, Debug, Clone, Copy)]
+pub struct InitialSyncFinished;
+
 /// Replication stats during message processing.
 ///
 /// Statistic will be collected only if the resource is present.
@@ -10,7 +10,8 @@ use crate::prelude::*;
 /// a bitmask indicating whether the most recent 64 ticks were received.
 ///
 /// See also [`EntityReplicated`].
-#[derive(Component)]
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
 pub struct ConfirmHistory {
     /// Previously confirmed ticks, including the last tick at position 0.
     mask: u64,
@@ -0,0 +1,237 @@
+use alloc::vec::Vec;
+
+use bevy::{ecs::component::ComponentId, prelude::*};
+use log::{debug, trace};
+
+use super::confirm_history::ConfirmHistory;
+use crate::{prelude::*, shared::replication::deferred_entity::DeferredEntity};
+
+/// Entity pooling for replicated entities on client.
+pub trait AppPoolExt {
+    /// Enables pooling for replicated entities with component `C` using default [`PoolConfig`].
+    ///
+    /// See also [`Self::pool_replicated_with`].
+    fn pool_replicated<C: Component>(&mut self) -> &mut Self {
+        self.pool_replicated_with::<C>(PoolConfig::default())
+    }
+
+    /**
+    Same as [`Self::pool_replicated`], but also accepts pool configuration.
+
+    When the server despawns an entity with component `C`, the client calls [`PoolConfig::reset`],
+    removes [`Replicated`] and [`ConfirmHistory`], inserts [`Pooled`] and keeps the entity
+    instead of calling [`ReplicationRegistry::despawn`](crate::shared::replication::registry::ReplicationRegistry::despawn).
+
+    When the server spawns an entity with `C`, the client takes an entity from the pool instead of spawning a new one.
+    All received components are inserted into it at once and [`Pooled`] is removed. Mapping in
+    [`ServerEntityMap`](crate::shared::server_entity_map::ServerEntityMap) is updated accordingly.
+
+    If an entity matches multiple pools, the first added pool is used.
+
+    Pools are checked before marker despawn functions: entities returned to a pool skip
+    [`AppMarkerExt::set_marker_despawn_fn`] functions. If the pool is full, the entity is despawned as usual,
+    including marker despawn functions.
+
+    While the pool is not empty, components of newly spawned server entities are written into a reserved
+    staging entity marked with [`Pooled`], so no entity is spawned on the client if a pooled one is reused.
+    Pooling applies only to despawns and spawns from the server. Entities that became hidden for the client
+    are handled by [`ReplicationRegistry::visibility_lost`](crate::shared::replication::registry::ReplicationRegistry::visibility_lost).
+
+    # Examples
+
+    Keep rendering components of bullets to avoid archetype moves, but reset the transform.
+
+    ```
+    # use bevy::state::app::StatesPlugin;
+    use bevy::prelude::*;
+    use bevy_replicon::{client::entity_pool::PoolConfig, prelude::*};
+    use serde::{Deserialize, Serialize};
+
+    # let mut app = App::new();
+    # app.add_plugins((StatesPlugin, RepliconPlugins));
+    app.replicate::<Bullet>().pool_replicated_with::<Bullet>(PoolConfig {
+        capacity: 256,
+        reset: reset_bullet,
+    });
+
+    fn reset_bullet(entity: &mut EntityWorldMut) {
+        entity.retain::<(Bullet, Transform, Visibility)>();
+        entity.insert((Transform::default(), Visibility::Hidden));
+    }
+
+    #[derive(Component, Serialize, Deserialize)]
+    struct Bullet;
+    ```
+    **/
+    fn pool_replicated_with<C: Component>(&mut self, config: PoolConfig) -> &mut Self;
+}
+
+impl AppPoolExt for App {
+    fn pool_replicated_with<C: Component>(&mut self, config: PoolConfig) -> &mut Self {
+        debug!("adding pool for `{}`", ShortName::of::<C>());
+        let component_id = self.world_mut().register_component::<C>();
+        let mut entity_pool = self.world_mut().get_resource_or_init::<EntityPool>();
+        entity_pool.pools.push(ComponentPool {
+            component_id,
+            config,
+            entities: Default::default(),
+        });
+
+        self
+    }
+}
+
+/// Pooled entities for each component registered via [`AppPoolExt`].
+///
+/// Inserted on the first call of [`AppPoolExt::pool_replicated_with`].
+/// Pooling is performed only if the resource is present.
+#[derive(Resource, Default)]
+pub struct EntityPool {
+    pools: Vec<ComponentPool>,
+
+    /// Entity into which components of spawned entities are written until a pool is matched.
+    ///
+    /// Spawned when the first entity is returned to a pool, so apps that never
+    /// receive pooled entities don't have it.
+    /// Never receives any components, all of them are buffered by [`DeferredEntity`].
+    staging: Option<Entity>,
+}
+
+impl EntityPool {
+    /// Returns the number of pooled entities for all components.
+    pub fn len(&self) -> usize {
+        self.pools.iter().map(|pool| pool.entities.len()).sum()
+    }
+
+    /// Returns `true` if there are no pooled entities.
+    pub fn is_empty(&self) -> bool {
+        self.pools.iter().all(|pool| pool.entities.is_empty())
+    }
+
+    /// Returns a matching entity to its pool.
+    ///
+    /// Returns the entity back if there is no matching pool or it's full.
+    pub(crate) fn put<'w>(&mut self, mut entity: EntityWorldMut<'w>) -> Option<EntityWorldMut<'w>> {
+        let Some(pool) = self.pools.iter_mut().find(|pool| {
+            entity.contains_id(pool.component_id) && pool.entities.len() < pool.config.capacity
+        }) else {
+            return Some(entity);
+        };
+
+        trace!("returning `{}` to the pool", entity.id());
+        (pool.config.reset)(&mut entity);
+        entity
+            .remove::<(Replicated, ConfirmHistory)>()
+            .insert(Pooled);
+        pool.entities.push(entity.id());
+
+        // Spawn in advance to avoid spawning on reuse.
+        if self.staging.is_none() {
+            let staging = entity.world_scope(|world| world.spawn(Pooled).id());
+            debug!("spawning staging `{staging}`");
+            self.staging = Some(staging);
+        }
+
+        None
+    }
+
+    /// Returns an entity for writing components of a new server entity.
+    ///
+    /// Returns the staging entity if any pool has entities. It should be replaced using
+    /// [`Self::take`] after writing all components.
+    /// Spawns a new entity otherwise.
+    pub(crate) fn reserve<'w>(&mut self, world: &'w mut World) -> EntityWorldMut<'w> {
+        if self.is_empty() {
+            return world.spawn_empty();
+        }
+
+        // The staging entity could be despawned by user.
+        match self
+            .staging
+            .filter(|&entity| world.get::<Pooled>(entity).is_some())
+        {
+            Some(entity) => world.entity_mut(entity),
+            None => {
+                let staging = world.spawn(Pooled);
+                self.staging = Some(staging.id());
+                staging
+            }
+        }
+    }
+
+    /// Replaces the staging entity from [`Self::reserve`] with an entity from a matching pool.
+    ///
+    /// The pool is matched by the buffered insertions, so this should be called after
+    /// writing all components. If no pool matches, the entity is spawned.
+    pub(crate) fn take<'w>(&mut self, client_entity: DeferredEntity<'w>) -> DeferredEntity<'w> {
+        if Some(client_entity.id()) != self.staging {
+            return client_entity;
+        }
+
+        if let Some(pool) = self.pools.iter_mut().find(|pool| {
+            !pool.entities.is_empty() && client_entity.buffered_insertion(pool.component_id)
+        }) {
+            // Pooled entities could be despawned by user.
+            while let Some(entity) = pool.entities.pop() {
+                if client_entity.world().get::<Pooled>(entity).is_some() {
+                    trace!("taking pooled `{entity}`");
+                    let mut client_entity = client_entity.replace(Some(entity));
+                    client_entity.remove::<Pooled>();
+                    return client_entity;
+                }
+            }
+        }
+
+        client_entity.replace(None)
+    }
+}
+
+/// Configuration for a pool.
+///
+/// See [`AppPoolExt::pool_replicated_with`].
+#[derive(Clone, Copy)]
+pub struct PoolConfig {
+    /// Maximum number of pooled entities.
+    ///
+    /// Entities are despawned as usual if the pool is full.
+    ///
+    /// By default set to `64`.
+    pub capacity: usize,
+
+    /// Function that called on entity before returning it to the pool.
+    ///
+    /// By default uses [`reset`].
+    pub reset: ResetFn,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 64,
+            reset,
+        }
+    }
+}
+
+/// Signature of the pooled entity reset function.
+pub type ResetFn = fn(&mut EntityWorldMut);
+
+/// Default reset function.
+///
+/// Removes all components, so the entity will be reused only as an ID.
+/// Override it to keep components and avoid archetype moves.
+pub fn reset(entity: &mut EntityWorldMut) {
+    entity.clear();
+}
+
+/// Marker for entities in [`EntityPool`].
+///
+/// Can be used to exclude pooled entities from queries if [`PoolConfig::reset`] keeps components.
+#[derive(Component)]
+pub struct Pooled;
+
+struct ComponentPool {
+    component_id: ComponentId,
+    config: PoolConfig,
+    entities: Vec<Entity>,
+}
@@ -46,8 +46,11 @@ These entities are automatically spawned and despawned by the messaging backend.
 also despawn them yourself to trigger a disconnect or use the [`DisconnectRequest`] message
 to disconnect after sending messages.
 
-You can use [`On<Add, ConnectedClient>`] to react to new connections,
-or use backend-provided events if you need the disconnect reason.
+The [`Disconnected`] event is triggered on the client when it leaves [`ClientState::Connected`].
+If [`RepliconSharedPlugin::disconnect_reasons`] is enabled, the [`DisconnectReason`] from the request
+is sent to the client before the disconnect and available in this event.
+
+You can use [`On<Add, ConnectedClient>`] to react to new connections.
 
 ## States
 
@@ -219,7 +222,8 @@ struct NotReplicatedComponent;
 ```
 
 This pairs nicely with server state serialization and keeps saves clean.
-You can use [`scene::replicate_into`] to fill [`DynamicScene`] with replicated entities and their components.
+You can use [`scene::replicate_into`] to fill [`DynamicScene`] with replicated entities and their components
+and [`scene::restore_replicated`] to spawn them back with [`Replicated`].
 On deserialization all missing required components will be inserted, and initialization
 systems will restore the correct game state.
 
@@ -252,6 +256,9 @@ replication only for [`ChildOf`] and [`Children`] will be updated automatically
 This will emit a [`B0004`](https://bevy.org/learn/errors/b0004) warning which can be safely ignored.
 See [#19776](https://github.com/bevyengine/bevy/issues/19776) for more details.
 
+Since [`Children`] is rebuilt on the client, the order of children may differ from the server.
+Use [`RelationshipOrderAppExt::replicate_order`] to replicate it.
+
 You can also ensure that their mutations arrive in sync by using [`SyncRelatedAppExt::sync_related_entities`].
 
 #### Deterministic replication
@@ -280,7 +287,8 @@ To send a message from client to server, you need to register the message
 with [`ClientMessageAppExt::add_client_message`] instead of [`App::add_message`].
 
 Messages include [`Channel`] to configure delivery guarantees (reliability and
-ordering).
+ordering). Each message uses a separate channel by default. If your backend limits the number
+of channels, you can register multiple messages with the same [`SharedChannel`].
 
 These messages will appear on server as [`FromClient`] wrapper message that
 contains sender ID and the message.
@@ -351,8 +359,13 @@ fn receive(ping: On<FromClient<Ping>>) {
 ```
 
 For events with entities inside use [`ClientEventAppExt::add_mapped_client_event`].
+To trigger an [`EntityEvent`] on the mapped server entity, use [`ClientEventAppExt::add_client_entity_event`].
 Similar to messages, serialization can also be customized with [`ClientEventAppExt::add_client_event_with`].
 
+To protect the server from spam, you can limit the rate and size of received client messages and events
+with [`ClientMessageAppExt::limit_client_message`] and [`ClientEventAppExt::limit_client_event`],
+or all of them combined with [`ClientMessageAppExt::limit_all_client_messages`].
+
 ### From server to client
 
 A similar technique is used to send messages from server to clients. To do this,
@@ -427,11 +440,25 @@ fn receive(_on: On<Pong>) {
 
 And just like for client events, we provide [`ServerEventAppExt::add_mapped_server_event`]
 and [`ServerEventAppExt::add_server_event_with`].
+Entity events can be registered with [`ServerEventAppExt::add_server_entity_event`].
+
+For interactions that need a reply, like buying an item, there are client requests. Register them
+with [`RequestAppExt::add_client_request`]
+and respond on the server with [`ServerRespondExt::server_respond`].
+The response is routed only to the requesting client.
 
 We guarantee that clients will never receive events or messages that point to an entity or require specific
 component to be presentt which client haven't received yet. For more details see the documentation on
 [`ServerMessageAppExt::make_message_independent`].
 
+To keep low-priority messages from competing with important ones, you can limit the number of bytes sent to
+each client per update via [`ServerPlugin::message_budget`] and prioritize messages with
+[`ServerMessageAppExt::set_message_priority`] or [`ServerEventAppExt::set_event_priority`].
+
+For messages that represent the latest state, like cursor positions, use
+[`ServerMessageAppExt::coalesce_server_message`] or [`ClientMessageAppExt::coalesce_client_message`]
+to send only the newest message per update instead of all of them.
+
 ## Abstracting over configurations
 
 Depending on the game, you may need to support some of these configurations:
@@ -534,7 +561,14 @@ You can control which parts of the world are visible for each client by setting
 in [`ServerPlugin`] to [`VisibilityPolicy::Whitelist`] or [`VisibilityPolicy::Blacklist`].
 
 To set which entity is visible, you need to use the [`ClientVisibility`] component
-on authorized clients.
+on authorized clients. To make related entities inherit visibility of their root,
+use [`SyncRelatedAppExt::propagate_visibility`].
+To make entities referenced by visible entities visible, enable
+[`ServerPlugin::visibility_dependencies`].
+
+When an entity becomes hidden for a client, the client triggers [`EntityLeftInterest`] and calls
+[`ReplicationRegistry::visibility_lost`](shared::replication::registry::ReplicationRegistry::visibility_lost),
+which despawns the entity by default. Override it to fade out the entity, keep it as a ghost or pool it instead.
 
 Check also the [corresponding section](https://github.com/simgine/bevy_replicon#visibility)
 in our README for more high-level abstractions.
@@ -600,6 +634,7 @@ others might need to be interpolated.
 
 This is why writing functions are marker-based. First, you register a marker using [`AppMarkerExt::register_marker<M>`].
 Then you can override how specific component is written and removed using [`AppMarkerExt::set_marker_fns<M, C>`].
+Entity despawns can be overridden in the same way using [`AppMarkerExt::set_marker_despawn_fn<M>`].
 
 You can control marker priority or enable processing of old values using [`AppMarkerExt::register_marker_with<M>`].
 
@@ -625,6 +660,13 @@ A tick for an entity is confirmed if one of the following is true:
 - [`ServerMutateTicks`](client::server_mutate_ticks::ServerMutateTicks) reports that for at least one of the next ticks, all update
   messages have been received.
 
+### Desync detection
+
+To verify that the client state matches the server, register components with
+[`ChecksumAppExt::add_checksum`]. The server will periodically send hashes of these components
+and the client will trigger [`ReplicationDesync`](shared::replication::checksum::ReplicationDesync)
+for mismatching entities.
+
 ### Optimizing entity serialization
 
 Serialization of [`Entity`] is optimized for use in scenes, but it’s not very efficient for networking. Because of this, we use our own implementation.
@@ -696,8 +738,9 @@ pub mod prelude {
         shared::{
             AuthMethod, RepliconSharedPlugin,
             backend::{
-                ClientState, ClientStats, DisconnectRequest, ServerState,
-                channels::{Channel, RepliconChannels},
+                ClientState, ClientStats, DisconnectReason, DisconnectRequest, Disconnected,
+                ServerState,
+                channels::{Channel, RepliconChannels, SharedChannel},
                 client_messages::ClientMessages,
                 connected_client::ConnectedClient,
                 server_messages::ServerMessages,
@@ -706,14 +749,19 @@ pub mod prelude {
             message::{
                 client_event::{ClientEventAppExt, ClientTriggerExt},
                 client_message::{ClientMessageAppExt, FromClient},
+                request::{ClientRequestExt, RequestAppExt, ServerRespondExt},
                 server_event::{ServerEventAppExt, ServerTriggerExt},
                 server_message::{SendMode, ServerMessageAppExt, ToClients},
             },
-            protocol::{ProtocolHash, ProtocolHasher, ProtocolMismatch},
+            protocol::{
+                ProtocolHash, ProtocolHasher, ProtocolMismatch, versioning::ProtocolVersioning,
+            },
             replication::{
                 Replicated,
+                checksum::ChecksumAppExt,
                 command_markers::AppMarkerExt,
                 registry::rule_fns::RuleFns,
+                relationship_order::RelationshipOrderAppExt,
                 rules::{AppRuleExt, component::ReplicationMode},
                 signature::Signature,
             },
@@ -723,19 +771,26 @@ pub mod prelude {
 
     #[cfg(feature = "client")]
     pub use super::client::{
-        ClientPlugin, ClientReplicationStats, ClientSystems, message::ClientMessagePlugin,
+        ClientPlugin, ClientReplicationStats, ClientSystems, EntityLeftInterest,
+        InitialSyncFinished, InitialSyncProgress, entity_pool::AppPoolExt,
+        message::ClientMessagePlugin,
     };
 
     #[cfg(feature = "server")]
     pub use super::server::{
-        AuthorizedClient, PriorityMap, ServerPlugin, ServerSystems,
+        AuthorizedClient, PriorityMap, ServerPlugin, ServerReplicationStats, ServerSystems,
         client_visibility::{ClientVisibility, VisibilityPolicy},
+        dirty_entities::ChangeCollection,
+        initial_sync::InitialSync,
         message::ServerMessagePlugin,
         related_entities::SyncRelatedAppExt,
     };
 
     #[cfg(feature = "client_diagnostics")]
     pub use super::client::diagnostics::ClientDiagnosticsPlugin;
+
+    #[cfg(feature = "server_diagnostics")]
+    pub use super::server::diagnostics::ServerDiagnosticsPlugin;
 }
 
 pub use bytes;
@@ -753,6 +808,7 @@ use prelude::*;
 /// * [`ClientPlugin`] - with feature `client`.
 /// * [`ClientMessagePlugin`] - with feature `client`.
 /// * [`ClientDiagnosticsPlugin`] - with feature `client_diagnostics`.
+/// * [`ServerDiagnosticsPlugin`] - with feature `server_diagnostics`.
 pub struct RepliconPlugins;
 
 impl PluginGroup for RepliconPlugins {
@@ -775,6 +831,11 @@ impl PluginGroup for RepliconPlugins {
             group = group.add(ClientDiagnosticsPlugin);
         }
 
+        #[cfg(feature = "server_diagnostics")]
+        {
+            group = group.add(ServerDiagnosticsPlugin);
+        }
+
         group
     }
 }
@@ -1,4 +1,8 @@
-use bevy::{ecs::entity::hash_map::EntityHashMap, prelude::*, scene::DynamicEntity};
+use bevy::{
+    ecs::entity::hash_map::EntityHashMap,
+    prelude::*,
+    scene::{DynamicEntity, SceneSpawnError},
+};
 use log::debug;
 
 use crate::{prelude::*, shared::replication::rules::ReplicationRules};
@@ -11,6 +15,10 @@ or do not have `#[reflect(Component)]` will be skipped.
 
 Entities won't have the [`Replicated`] component.
 So on deserialization you need to insert it back if you want entities to continue to replicate.
+See [`restore_replicated`] for this.
+
+Hashes of [`Signature`] are stored as [`SignatureSnapshot`], except for signatures
+created with [`Signature::for_client`] since clients don't persist.
 
 # Examples
 
@@ -51,6 +59,8 @@ pub fn replicate_into(scene: &mut DynamicScene, world: &World) {
         return;
     };
 
+    let signature_id = world.component_id::<Signature>();
+
     let mut entities: EntityHashMap<_> = scene
         .entities
         .drain(..)
@@ -67,7 +77,16 @@ pub fn replicate_into(scene: &mut DynamicScene, world: &World) {
     {
         // Populate entities ahead of time in order to extract entities without components too.
         for entity in archetype.entities() {
-            entities.entry(entity.id()).or_default();
+            let components = entities.entry(entity.id()).or_default();
+            if signature_id.is_some_and(|id| archetype.contains(id)) {
+                let signature = world.get::<Signature>(entity.id()).unwrap();
+                if signature.client().is_none() {
+                    debug!("adding signature snapshot to `{}`", entity.id());
+                    components.push(Box::new(SignatureSnapshot {
+                        hash: signature.hash(),
+                    }));
+                }
+            }
         }
 
         for rule in rules.iter().filter(|rule| rule.matches(archetype)) {
@@ -119,3 +138,127 @@ pub fn replicate_into(scene: &mut DynamicScene, world: &World) {
         .map(|(entity, components)| DynamicEntity { entity, components });
     scene.entities.extend(dyn_entities_iter);
 }
+
+/**
+Like [`replicate_into`], but also includes client-side replication state.
+
+Intended to be called on the client to dump what the client knows about the replicated world.
+In addition to the replicated components, each entity will have:
+
+- [`ServerEntitySnapshot`] with the server entity from
+  [`ServerEntityMap`](crate::shared::server_entity_map::ServerEntityMap) if the entity is mapped.
+- [`ConfirmHistory`](crate::client::confirm_history::ConfirmHistory).
+
+The scene will also contain [`ServerUpdateTick`](crate::client::ServerUpdateTick) resource.
+
+Server entities are stored as-is, so they can be matched against a scene filled
+with [`replicate_into`] on the server to spot desyncs.
+
+# Examples
+
+```
+use bevy::{prelude::*, state::app::StatesPlugin};
+use bevy_replicon::{prelude::*, scene};
+# let mut app = App::new();
+# app.add_plugins((StatesPlugin, RepliconPlugins));
+
+let mut scene = DynamicScene::default();
+scene::replicate_client_into(&mut scene, app.world());
+
+let registry = app.world().resource::<AppTypeRegistry>();
+let serialized = scene
+    .serialize(&registry.read())
+    .expect("scene should be serialized");
+```
+*/
+#[cfg(feature = "client")]
+pub fn replicate_client_into(scene: &mut DynamicScene, world: &World) {
+    use crate::{
+        client::{ServerUpdateTick, confirm_history::ConfirmHistory},
+        shared::server_entity_map::ServerEntityMap,
+    };
+
+    replicate_into(scene, world);
+
+    let entity_map = world.resource::<ServerEntityMap>();
+    for dyn_entity in &mut scene.entities {
+        if let Some(&server_entity) = entity_map.to_server().get(&dyn_entity.entity) {
+            dyn_entity
+                .components
+                .push(Box::new(ServerEntitySnapshot(server_entity)));
+        }
+        if let Some(&history) = world.get::<ConfirmHistory>(dyn_entity.entity) {
+            dyn_entity.components.push(Box::new(history));
+        }
+    }
+
+    scene
+        .resources
+        .push(Box::new(*world.resource::<ServerUpdateTick>()));
+}
+
+/**
+Spawns entities from a scene and makes them replicated.
+
+Writes all entities and resources from the scene into the world, remapping entity references
+via [`DynamicScene::write_to_world`], and inserts [`Replicated`] into each spawned entity.
+[`SignatureSnapshot`] is replaced with [`Signature`] that has the same hash, so clients
+can still match their entities.
+
+Intended for scenes filled with [`replicate_into`] to resume a persistent world after restart.
+
+Returns the mapping from scene entities to spawned entities.
+
+# Examples
+
+```
+use bevy::{prelude::*, state::app::StatesPlugin};
+use bevy_replicon::{prelude::*, scene};
+# let mut app = App::new();
+# app.add_plugins((StatesPlugin, RepliconPlugins));
+
+let mut scene = DynamicScene::default();
+scene::replicate_into(&mut scene, app.world());
+
+// Serialize the scene, restart the server and load it back...
+
+let entity_map = scene::restore_replicated(&scene, app.world_mut())
+    .expect("all scene types should be registered");
+```
+*/
+pub fn restore_replicated(
+    scene: &DynamicScene,
+    world: &mut World,
+) -> Result<EntityHashMap<Entity>, SceneSpawnError> {
+    let mut entity_map = EntityHashMap::default();
+    scene.write_to_world(world, &mut entity_map)?;
+
+    for &entity in entity_map.values() {
+        let mut entity = world.entity_mut(entity);
+        if let Some(snapshot) = entity.take::<SignatureSnapshot>() {
+            debug!("restoring signature for `{}`", entity.id());
+            entity.insert(Signature::restored(snapshot.hash));
+        }
+        entity.insert(Replicated);
+    }
+
+    Ok(entity_map)
+}
+
+/// Hash of [`Signature`] stored in scenes.
+///
+/// See [`replicate_into`] and [`restore_replicated`].
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct SignatureSnapshot {
+    hash: u64,
+}
+
+/// Server entity for a client entity stored in scenes.
+///
+/// Not mapped on scene loading, since it refers to an entity on the server.
+///
+/// See [`replicate_client_into`].
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct ServerEntitySnapshot(pub Entity);
@@ -1,24 +1,30 @@
+pub mod bandwidth_profiler;
 pub mod client_visibility;
+#[cfg(feature = "server_diagnostics")]
+pub mod diagnostics;
+pub mod dirty_entities;
+pub mod initial_sync;
 pub mod message;
 pub mod related_entities;
 pub(super) mod removal_buffer;
 pub(super) mod replication_messages;
 pub mod server_tick;
 mod server_world;
+mod visibility_dependencies;
 
 use core::{ops::Range, time::Duration};
 
 use bevy::{
     ecs::{
-        archetype::Archetypes,
+        archetype::{Archetype, ArchetypeEntity, Archetypes},
         component::{CheckChangeTicks, Tick},
-        entity::{Entities, EntityHashMap},
+        entity::{Entities, EntityHashMap, hash_set::EntityHashSet},
         intern::Interned,
         schedule::ScheduleLabel,
         system::SystemChangeTick,
     },
     prelude::*,
-    ptr::Ptr,
+    tasks::{ComputeTaskPool, TaskPool},
     time::common_conditions::on_timer,
 };
 use bytes::Buf;
@@ -28,26 +34,33 @@ use crate::{
     postcard_utils,
     prelude::*,
     shared::{
-        backend::channels::ClientChannel,
-        message::server_message::message_buffer::MessageBuffer,
+        backend::{DisconnectNotice, channels::ClientChannel},
+        message::{
+            registry::RemoteMessageRegistry,
+            server_message::{message_buffer::MessageBuffer, send_queue::SendQueue},
+        },
+        protocol::versioning::{NegotiatedProtocol, ProtocolManifest},
         replication::{
             client_ticks::{ClientTicks, EntityBuffer},
-            registry::{
-                ReplicationRegistry, component_fns::ComponentFns, ctx::SerializeCtx,
-                rule_fns::UntypedRuleFns,
-            },
-            rules::{ReplicationRules, component::ComponentRule},
+            registry::{FnsId, ReplicationRegistry, ctx::SerializeCtx},
+            rules::ReplicationRules,
             track_mutate_messages::TrackMutateMessages,
         },
+        token_auth::{AuthRejected, AuthRequested, PendingAuth, ResolveAuth, TokenHandshake},
     },
 };
+use bandwidth_profiler::BandwidthProfiler;
+use dirty_entities::{ChangeCollection, DirtyEntities};
+use initial_sync::InitialSync;
 use related_entities::RelatedEntities;
 use removal_buffer::{RemovalBuffer, RemovalReader};
 use replication_messages::{
-    mutations::Mutations, serialized_data::SerializedData, updates::Updates,
+    mutations::Mutations, range_cache::RangeCache, serialized_data::SerializedData,
+    updates::Updates,
 };
 use server_tick::ServerTick;
-use server_world::ServerWorld;
+use server_world::{ReplicatedArchetype, ServerWorld};
+use visibility_dependencies::EntityReferences;
 
 pub struct ServerPlugin {
     /// Schedule in which [`ServerTick`] is incremented.
@@ -84,6 +97,45 @@ pub struct ServerPlugin {
     ///
     /// In practice mutations will live at least `mutations_timeout`, and at most `2*mutations_timeout`.
     pub mutations_timeout: Duration,
+
+    /// How changes are collected for replication.
+    ///
+    /// By default it's set to [`ChangeCollection::Scan`].
+    pub change_collection: ChangeCollection,
+
+    /// How the world is sent to newly authorized clients.
+    ///
+    /// By default it's set to [`InitialSync::Full`].
+    pub initial_sync: InitialSync,
+
+    /// Maximum number of bytes of server messages sent to each client per update.
+    ///
+    /// Messages are sent in order of their priority (see [`ServerMessageAppExt::set_message_priority`]).
+    /// Messages that don't fit are dropped on [`Channel::Unreliable`] and deferred to the next update
+    /// on other channels. At least one message is sent to each client per update, even if it exceeds
+    /// the budget. Replication is not affected.
+    ///
+    /// By default it's [`None`], which means no limit.
+    pub message_budget: Option<usize>,
+
+    /// Makes entities referenced by visible entities visible too.
+    ///
+    /// When a visible entity references a hidden entity, the client maps the reference to an empty entity
+    /// that stays empty until the referenced entity becomes visible. With this option enabled, the server
+    /// gathers references from [`Component::map_entities`] of all replicated components and makes referenced
+    /// replicated entities visible for each client that can see the referencing entity in the same tick.
+    /// Visibility is revoked once no visible entity references them anymore, unless it was set via
+    /// [`ClientVisibility::set_visibility`].
+    ///
+    /// Dependencies are not transitive: only entities visible via [`ClientVisibility::set_visibility`]
+    /// make their references visible.
+    ///
+    /// Like [`ChangeCollection::Dirty`], all replicated components are checked for changes on each
+    /// server tick. References are gathered only from changed entities by serializing their replicated
+    /// components and mapping entities of the deserialized copies, so only replicated references count.
+    ///
+    /// By default it's `false`.
+    pub visibility_dependencies: bool,
 }
 
 impl ServerPlugin {
@@ -93,6 +145,10 @@ impl ServerPlugin {
             tick_schedule: tick_schedule.intern(),
             visibility_policy: Default::default(),
             mutations_timeout: Duration::from_secs(10),
+            change_collection: Default::default(),
+            initial_sync: Default::default(),
+            message_budget: None,
+            visibility_dependencies: false,
         }
     }
 }
@@ -125,6 +181,7 @@ impl Plugin for ServerPlugin {
                     ServerSystems::IncrementTick,
                     ServerSystems::Send,
                     ServerSystems::SendPackets,
+                    ServerSystems::Diagnostics,
                 )
                     .chain(),
             )
@@ -147,11 +204,33 @@ impl Plugin for ServerPlugin {
                 PostUpdate,
                 (
                     buffer_removals,
+                    related_entities::propagate_visibility.run_if(resource_changed::<ServerTick>),
+                    (
+                        visibility_dependencies::collect,
+                        visibility_dependencies::resolve,
+                    )
+                        .chain()
+                        .run_if(resource_exists::<EntityReferences>)
+                        .run_if(resource_changed::<ServerTick>),
                     send_replication.run_if(resource_changed::<ServerTick>),
                 )
                     .chain()
                     .in_set(ServerSystems::Send)
                     .run_if(in_state(ServerState::Running)),
+            )
+            .add_systems(
+                PostUpdate,
+                send_disconnect_reasons
+                    .before(ServerSystems::Send)
+                    .run_if(in_state(ServerState::Running))
+                    .run_if(resource_exists::<Messages<ToClients<DisconnectNotice>>>),
+            )
+            .add_systems(
+                PostUpdate,
+                bandwidth_profiler::update_report
+                    .in_set(ServerSystems::Diagnostics)
+                    .run_if(resource_exists::<BandwidthProfiler>)
+                    .run_if(in_state(ServerState::Running)),
             );
 
         debug!("using tick schedule `{:?}`", self.tick_schedule);
@@ -162,6 +241,22 @@ impl Plugin for ServerPlugin {
                 .run_if(in_state(ServerState::Running)),
         );
 
+        debug!("using change collection `{:?}`", self.change_collection);
+        if self.change_collection == ChangeCollection::Dirty {
+            app.init_resource::<DirtyEntities>();
+        }
+
+        if self.visibility_dependencies {
+            debug!("using visibility dependencies");
+            app.init_resource::<EntityReferences>();
+        }
+
+        debug!("using initial sync `{:?}`", self.initial_sync);
+        app.insert_resource(self.initial_sync);
+
+        debug!("using message budget `{:?}`", self.message_budget);
+        app.insert_resource(SendQueue::new(self.message_budget));
+
         debug!("using visibility policy `{:?}`", self.visibility_policy);
         match self.visibility_policy {
             VisibilityPolicy::Blacklist => {
@@ -176,16 +271,31 @@ impl Plugin for ServerPlugin {
             }
         }
 
-        let auth_method = app.world().resource::<AuthMethod>();
+        let auth_method = *app.world().resource::<AuthMethod>();
         debug!("using authorization method `{auth_method:?}`");
         match auth_method {
             AuthMethod::ProtocolCheck => {
-                app.add_observer(check_protocol);
+                if app.world().contains_resource::<ProtocolVersioning>() {
+                    app.add_observer(negotiate_protocol);
+                } else {
+                    app.add_observer(check_protocol);
+                }
             }
             AuthMethod::None => {
                 app.register_required_components::<ConnectedClient, AuthorizedClient>();
             }
             AuthMethod::Custom => (),
+            AuthMethod::Token { timeout } => {
+                app.add_observer(start_token_auth)
+                    .add_observer(verify_token_handshake)
+                    .add_observer(resolve_auth)
+                    .add_systems(
+                        PreUpdate,
+                        reject_expired_auth(timeout)
+                            .after(ServerSystems::Receive)
+                            .run_if(in_state(ServerState::Running)),
+                    );
+            }
         }
 
         if log_enabled!(Level::Debug) {
@@ -195,10 +305,22 @@ impl Plugin for ServerPlugin {
     }
 
     fn finish(&self, app: &mut App) {
+        if self.change_collection == ChangeCollection::Dirty {
+            dirty_entities::observe_insertions(app.world_mut());
+        }
+
         app.world_mut()
             .resource_scope(|world, mut messages: Mut<ServerMessages>| {
                 let channels = world.resource::<RepliconChannels>();
-                messages.setup_client_channels(channels.client_channels().len());
+                messages.setup_channels(channels);
+
+                if world.contains_resource::<ProtocolManifest>() {
+                    let channel_id = world
+                        .resource::<RemoteMessageRegistry>()
+                        .client_event_channel::<ProtocolManifest>()
+                        .expect("manifest should be registered with versioned protocol");
+                    messages.set_negotiation_channel(channel_id);
+                }
             });
     }
 }
@@ -214,9 +336,14 @@ fn handle_connects(add: On<Add, ConnectedClient>, mut message_buffer: ResMut<Mes
     message_buffer.exclude_client(add.entity);
 }
 
-fn handle_disconnects(remove: On<Remove, ConnectedClient>, mut messages: ResMut<ServerMessages>) {
+fn handle_disconnects(
+    remove: On<Remove, ConnectedClient>,
+    mut messages: ResMut<ServerMessages>,
+    mut send_queue: ResMut<SendQueue>,
+) {
     debug!("client `{}` disconnected", remove.entity);
     messages.remove_client(remove.entity);
+    send_queue.remove_client(remove.entity);
 }
 
 fn check_protocol(
@@ -242,19 +369,195 @@ fn check_protocol(
             mode: SendMode::Direct(client_protocol.client_id),
             message: ProtocolMismatch,
         });
-        disconnects.write(DisconnectRequest { client });
+        disconnects.write(DisconnectRequest {
+            client,
+            reason: DisconnectReason::ProtocolMismatch,
+        });
+    }
+}
+
+fn negotiate_protocol(
+    client_manifest: On<FromClient<ProtocolManifest>>,
+    mut commands: Commands,
+    mut disconnects: MessageWriter<DisconnectRequest>,
+    mut messages: ResMut<ServerMessages>,
+    manifest: Res<ProtocolManifest>,
+) {
+    let client = client_manifest
+        .client_id
+        .entity()
+        .expect("protocol manifest sent only from clients");
+
+    if let Some((negotiated, accepted, channel_map)) = manifest.negotiate(&client_manifest) {
+        debug!(
+            "marking client `{client}` as authorized with protocol version {} (server: {})",
+            client_manifest.version(),
+            manifest.version()
+        );
+        messages.insert_channel_map(client, channel_map);
+        commands.server_trigger(ToClients {
+            mode: SendMode::Direct(client_manifest.client_id),
+            message: accepted,
+        });
+        commands
+            .entity(client)
+            .insert((negotiated, AuthorizedClient));
+    } else {
+        debug!(
+            "disconnecting client `{client}` due to incompatible protocol version {} (server: {})",
+            client_manifest.version(),
+            manifest.version()
+        );
+        commands.server_trigger(ToClients {
+            mode: SendMode::Direct(client_manifest.client_id),
+            message: ProtocolMismatch,
+        });
+        disconnects.write(DisconnectRequest {
+            client,
+            reason: DisconnectReason::ProtocolMismatch,
+        });
+    }
+}
+
+fn send_disconnect_reasons(
+    mut disconnects: MessageReader<DisconnectRequest>,
+    mut notices: MessageWriter<ToClients<DisconnectNotice>>,
+) {
+    for disconnect in disconnects.read() {
+        debug!(
+            "sending `{:?}` to client `{}`",
+            disconnect.reason, disconnect.client
+        );
+        notices.write(ToClients {
+            mode: SendMode::Direct(ClientId::Client(disconnect.client)),
+            message: DisconnectNotice {
+                reason: disconnect.reason.clone(),
+            },
+        });
+    }
+}
+
+fn start_token_auth(add: On<Add, ConnectedClient>, mut commands: Commands, time: Res<Time>) {
+    debug!("waiting for token authorization of client `{}`", add.entity);
+    commands.entity(add.entity).insert(PendingAuth {
+        since: time.elapsed(),
+    });
+}
+
+fn verify_token_handshake(
+    handshake: On<FromClient<TokenHandshake>>,
+    mut commands: Commands,
+    mut disconnects: MessageWriter<DisconnectRequest>,
+    protocol: Res<ProtocolHash>,
+    pending: Query<(), With<PendingAuth>>,
+) {
+    let client = handshake
+        .client_id
+        .entity()
+        .expect("token handshake sent only from clients");
+
+    if !pending.contains(client) {
+        debug!(
+            "ignoring token handshake from client `{client}` that isn't waiting for authorization"
+        );
+        return;
+    }
+
+    if handshake.protocol == *protocol {
+        debug!("requesting token verification for client `{client}`");
+        commands.trigger(AuthRequested {
+            client,
+            payload: handshake.payload.clone(),
+        });
+    } else {
+        debug!(
+            "disconnecting client `{client}` due to protocol mismatch (client: `{:?}`, server: `{:?}`)",
+            handshake.protocol, *protocol
+        );
+        commands.entity(client).remove::<PendingAuth>();
+        commands.server_trigger(ToClients {
+            mode: SendMode::Direct(handshake.client_id),
+            message: ProtocolMismatch,
+        });
+        disconnects.write(DisconnectRequest {
+            client,
+            reason: DisconnectReason::ProtocolMismatch,
+        });
+    }
+}
+
+fn resolve_auth(
+    resolve: On<ResolveAuth>,
+    mut commands: Commands,
+    mut disconnects: MessageWriter<DisconnectRequest>,
+    pending: Query<(), With<PendingAuth>>,
+) {
+    if !pending.contains(resolve.client) {
+        debug!(
+            "ignoring authorization result for client `{}` that isn't waiting for it",
+            resolve.client
+        );
+        return;
+    }
+
+    commands.entity(resolve.client).remove::<PendingAuth>();
+    match &resolve.result {
+        Ok(()) => {
+            debug!("marking client `{}` as authorized", resolve.client);
+            commands.entity(resolve.client).insert(AuthorizedClient);
+        }
+        Err(reason) => {
+            debug!(
+                "disconnecting client `{}` due to rejected authorization: {reason}",
+                resolve.client
+            );
+            commands.server_trigger(ToClients {
+                mode: SendMode::Direct(ClientId::Client(resolve.client)),
+                message: AuthRejected {
+                    reason: reason.clone(),
+                },
+            });
+            disconnects.write(DisconnectRequest {
+                client: resolve.client,
+                reason: DisconnectReason::AuthRejected(reason.clone()),
+            });
+        }
+    }
+}
+
+fn reject_expired_auth(
+    timeout: Duration,
+) -> impl FnMut(Commands, Query<(Entity, &PendingAuth)>, Res<Time>) {
+    move |mut commands: Commands, clients: Query<(Entity, &PendingAuth)>, time: Res<Time>| {
+        for (client, pending) in &clients {
+            if time.elapsed().saturating_sub(pending.since) >= timeout {
+                commands.trigger(ResolveAuth::reject(client, "authorization timed out"));
+            }
+        }
     }
 }
 
 fn cleanup_acks(
     mutations_timeout: Duration,
-) -> impl FnMut(Query<&mut ClientTicks>, ResMut<EntityBuffer>, Res<Time>) {
-    move |mut clients: Query<&mut ClientTicks>,
+) -> impl FnMut(
+    Query<(&mut ClientTicks, Option<&mut ServerReplicationStats>)>,
+    Option<ResMut<ServerReplicationStats>>,
+    ResMut<EntityBuffer>,
+    Res<Time>,
+) {
+    move |mut clients: Query<(&mut ClientTicks, Option<&mut ServerReplicationStats>)>,
+          mut stats: Option<ResMut<ServerReplicationStats>>,
           mut entity_buffer: ResMut<EntityBuffer>,
           time: Res<Time>| {
         let min_timestamp = time.elapsed().saturating_sub(mutations_timeout);
-        for mut ticks in &mut clients {
-            ticks.cleanup_older_mutations(&mut entity_buffer, min_timestamp);
+        for (mut ticks, client_stats) in &mut clients {
+            let resends = ticks.cleanup_older_mutations(&mut entity_buffer, min_timestamp);
+            if let Some(stats) = &mut stats {
+                stats.resends += resends;
+            }
+            if let Some(mut client_stats) = client_stats {
+                client_stats.resends += resends;
+            }
         }
     }
 }
@@ -327,7 +630,11 @@ fn check_mutation_ticks(check: On<CheckChangeTicks>, mut clients: Query<&mut Cli
 
 /// Collects [`ReplicationMessages`] and sends them.
 fn send_replication(
-    mut serialized: Local<SerializedData>,
+    (mut serialized, mut segments, mut range_cache): (
+        Local<SerializedData>,
+        Local<Vec<SerializedData>>,
+        Local<RangeCache>,
+    ),
     change_tick: SystemChangeTick,
     world: ServerWorld,
     mut clients: Query<(
@@ -335,7 +642,8 @@ fn send_replication(
         &mut Updates,
         &mut Mutations,
         &ConnectedClient,
-        &mut EntityCache,
+        Option<&NegotiatedProtocol>,
+        Option<&mut ServerReplicationStats>,
         &mut ClientTicks,
         &mut PriorityMap,
         &mut ClientVisibility,
@@ -346,9 +654,22 @@ fn send_replication(
     mut entity_buffer: ResMut<EntityBuffer>,
     mut despawn_buffer: ResMut<DespawnBuffer>,
     mut messages: ResMut<ServerMessages>,
-    track_mutate_messages: Res<TrackMutateMessages>,
-    registry: Res<ReplicationRegistry>,
-    type_registry: Res<AppTypeRegistry>,
+    mut stats: Option<ResMut<ServerReplicationStats>>,
+    (
+        mut dirty_entities,
+        mut profiler,
+        initial_sync,
+        track_mutate_messages,
+        registry,
+        type_registry,
+    ): (
+        Option<ResMut<DirtyEntities>>,
+        Option<ResMut<BandwidthProfiler>>,
+        Res<InitialSync>,
+        Res<TrackMutateMessages>,
+        Res<ReplicationRegistry>,
+        Res<AppTypeRegistry>,
+    ),
     server_tick: Res<ServerTick>,
     time: Res<Time>,
 ) -> Result<()> {
@@ -361,11 +682,26 @@ fn send_replication(
     }
 
     collect_mappings(&mut serialized, &mut clients, &entities)?;
-    collect_despawns(&mut serialized, &mut clients, &mut despawn_buffer)?;
-    collect_removals(&mut serialized, &mut clients, &removal_buffer)?;
+    collect_despawns(
+        &mut serialized,
+        &mut clients,
+        &mut despawn_buffer,
+        *initial_sync,
+    )?;
+    collect_removals(
+        &mut serialized,
+        &mut clients,
+        &removal_buffer,
+        *initial_sync,
+    )?;
     collect_changes(
         &mut serialized,
+        &mut segments,
+        &mut range_cache,
         &mut clients,
+        dirty_entities.as_deref_mut(),
+        profiler.as_deref_mut(),
+        *initial_sync,
         &registry,
         &type_registry,
         &related_entities,
@@ -379,6 +715,7 @@ fn send_replication(
     send_messages(
         &mut clients,
         &mut messages,
+        stats.as_deref_mut(),
         **server_tick,
         **track_mutate_messages,
         &mut serialized,
@@ -398,11 +735,21 @@ fn reset(
     mut related_entities: ResMut<RelatedEntities>,
     clients: Query<Entity, With<ConnectedClient>>,
     mut message_buffer: ResMut<MessageBuffer>,
+    mut send_queue: ResMut<SendQueue>,
+    dirty_entities: Option<ResMut<DirtyEntities>>,
+    entity_references: Option<ResMut<EntityReferences>>,
 ) {
     messages.clear();
     *server_tick = Default::default();
     message_buffer.clear();
+    send_queue.clear();
     related_entities.clear();
+    if let Some(mut dirty_entities) = dirty_entities {
+        dirty_entities.clear();
+    }
+    if let Some(mut entity_references) = entity_references {
+        entity_references.clear();
+    }
     for entity in &clients {
         commands.entity(entity).despawn();
     }
@@ -414,12 +761,14 @@ fn send_messages(
         &mut Updates,
         &mut Mutations,
         &ConnectedClient,
-        &mut EntityCache,
+        Option<&NegotiatedProtocol>,
+        Option<&mut ServerReplicationStats>,
         &mut ClientTicks,
         &mut PriorityMap,
         &mut ClientVisibility,
     )>,
     messages: &mut ServerMessages,
+    mut stats: Option<&mut ServerReplicationStats>,
     server_tick: RepliconTick,
     track_mutate_messages: bool,
     serialized: &mut SerializedData,
@@ -427,39 +776,106 @@ fn send_messages(
     change_tick: SystemChangeTick,
     time: &Time,
 ) -> Result<()> {
-    let mut server_tick_range = None;
-    for (client_entity, updates, mut mutations, client, .., mut ticks, _, _) in clients {
-        if !updates.is_empty() {
-            ticks.set_update_tick(server_tick);
-            let server_tick_range =
-                write_tick_cached(&mut server_tick_range, serialized, server_tick)?;
-
-            updates.send(messages, client_entity, serialized, server_tick_range)?;
+    let server_tick_range = serialized.write_tick(server_tick)?;
+    let serialized = &*serialized;
+    if let Some(stats) = &mut stats {
+        stats.pending_acks = 0;
+    }
+
+    let mut clients: Vec<_> = clients.iter_mut().collect();
+    let task_pool = ComputeTaskPool::get_or_init(TaskPool::default);
+    let chunk_size = chunk_size(clients.len(), task_pool);
+    let chunks = clients.chunks_mut(chunk_size);
+
+    // Distribute buffered vectors between tasks to reuse their memory without locking.
+    let buffers_per_task = entity_buffer.len() / chunks.len().max(1);
+    let collect_total = stats.is_some();
+    let results = task_pool.scope(|scope| {
+        for chunk in chunks {
+            let split_at = entity_buffer.len() - buffers_per_task;
+            let mut task_buffer = EntityBuffer::default();
+            task_buffer.extend(entity_buffer.drain(split_at..));
+            let server_tick_range = server_tick_range.clone();
+            scope.spawn(async move {
+                let mut sent = Vec::new();
+                let mut total_stats = ServerReplicationStats::default();
+                for (client_entity, updates, mutations, client, _, client_stats, ticks, _, _) in
+                    chunk
+                {
+                    let collect_stats = collect_total || client_stats.is_some();
+                    let mut sent_stats = ServerReplicationStats::default();
+                    if !updates.is_empty() {
+                        ticks.set_update_tick(server_tick);
+                        let bytes = updates.send(
+                            &mut sent,
+                            *client_entity,
+                            serialized,
+                            server_tick_range.clone(),
+                        )?;
+                        if collect_stats {
+                            sent_stats.update_messages = 1;
+                            sent_stats.update_bytes = bytes;
+                            sent_stats.update_entities = updates.entities_len();
+                            sent_stats.update_components = updates.components_len();
+                        }
+                    }
+
+                    if !mutations.is_empty() || track_mutate_messages {
+                        let (messages_count, bytes) = mutations.send(
+                            &mut sent,
+                            *client_entity,
+                            ticks,
+                            &mut task_buffer,
+                            serialized,
+                            track_mutate_messages,
+                            server_tick_range.clone(),
+                            server_tick,
+                            change_tick.this_run(),
+                            time.elapsed(),
+                            client.max_size,
+                        )?;
+                        if collect_stats {
+                            sent_stats.mutate_messages = messages_count;
+                            sent_stats.mutate_bytes = bytes;
+                            sent_stats.mutate_entities = mutations.entities_len();
+                            sent_stats.mutate_components = mutations.components_len();
+                        }
+                    }
+
+                    if collect_stats {
+                        sent_stats.pending_acks = ticks.pending_mutate_messages();
+                    }
+                    total_stats.add(&sent_stats);
+                    if let Some(client_stats) = client_stats {
+                        client_stats.add(&sent_stats);
+                        client_stats.pending_acks = sent_stats.pending_acks;
+                    }
+                }
+
+                Ok::<_, BevyError>((sent, total_stats, task_buffer))
+            });
         }
+    });
 
-        if !mutations.is_empty() || track_mutate_messages {
-            let server_tick_range =
-                write_tick_cached(&mut server_tick_range, serialized, server_tick)?;
-
-            mutations.send(
-                messages,
-                client_entity,
-                &mut ticks,
-                entity_buffer,
-                serialized,
-                track_mutate_messages,
-                server_tick_range,
-                server_tick,
-                change_tick.this_run(),
-                time.elapsed(),
-                client.max_size,
-            )?;
+    for result in results {
+        let (sent, total_stats, mut task_buffer) = result?;
+        for (client, channel, message) in sent {
+            messages.send(client, channel, message);
         }
+        if let Some(stats) = &mut stats {
+            stats.add(&total_stats);
+        }
+        entity_buffer.append(&mut task_buffer);
     }
 
     Ok(())
 }
 
+/// Returns the number of clients processed by a single task.
+fn chunk_size(clients_len: usize, task_pool: &TaskPool) -> usize {
+    clients_len.div_ceil(task_pool.thread_num()).max(1)
+}
+
 /// Collects and writes any new entity mappings that happened in this tick.
 fn collect_mappings(
     serialized: &mut SerializedData,
@@ -468,7 +884,8 @@ fn collect_mappings(
         &mut Updates,
         &mut Mutations,
         &ConnectedClient,
-        &mut EntityCache,
+        Option<&NegotiatedProtocol>,
+        Option<&mut ServerReplicationStats>,
         &mut ClientTicks,
         &mut PriorityMap,
         &mut ClientVisibility,
@@ -517,19 +934,21 @@ fn collect_despawns(
         &mut Updates,
         &mut Mutations,
         &ConnectedClient,
-        &mut EntityCache,
+        Option<&NegotiatedProtocol>,
+        Option<&mut ServerReplicationStats>,
         &mut ClientTicks,
         &mut PriorityMap,
         &mut ClientVisibility,
     )>,
     despawn_buffer: &mut DespawnBuffer,
+    initial_sync: InitialSync,
 ) -> Result<()> {
     for entity in despawn_buffer.drain(..) {
         let entity_range = serialized.write_entity(entity)?;
         for (client_entity, mut message, .., mut ticks, mut priority, mut visibility) in
             &mut *clients
         {
-            if visibility.is_visible(entity) {
+            if visibility.is_visible(entity) && !is_deferred(initial_sync, &ticks, entity) {
                 trace!("writing despawn for `{entity}` for client `{client_entity}`");
                 message.add_despawn(entity_range.clone());
             }
@@ -543,7 +962,7 @@ fn collect_despawns(
         for entity in visibility.drain_lost() {
             trace!("writing visibility lost for `{entity}` for client `{client_entity}`");
             let entity_range = serialized.write_entity(entity)?;
-            message.add_despawn(entity_range);
+            message.add_hidden(entity_range);
             ticks.remove_entity(entity);
             priority.remove(&entity);
         }
@@ -560,23 +979,45 @@ fn collect_removals(
         &mut Updates,
         &mut Mutations,
         &ConnectedClient,
-        &mut EntityCache,
+        Option<&NegotiatedProtocol>,
+        Option<&mut ServerReplicationStats>,
         &mut ClientTicks,
         &mut PriorityMap,
         &mut ClientVisibility,
     )>,
     removal_buffer: &RemovalBuffer,
+    initial_sync: InitialSync,
 ) -> Result<()> {
     for (&entity, remove_ids) in removal_buffer.iter() {
         let entity_range = serialized.write_entity(entity)?;
         let ids_len = remove_ids.len();
         let fn_ids = serialized.write_fn_ids(remove_ids.iter().map(|&(_, fns_id)| fns_id))?;
-        for (client_entity, mut message, .., visibility) in &mut *clients {
-            if visibility.is_visible(entity) {
-                trace!(
-                    "writing removals for `{entity}` with `{remove_ids:?}` for client `{client_entity}`"
-                );
+        for (client_entity, mut message, _, _, protocol, _, ticks, _, visibility) in &mut *clients {
+            if !visibility.is_visible(entity) || is_deferred(initial_sync, &ticks, entity) {
+                continue;
+            }
+
+            let supported =
+                |&&(_, fns_id): &&_| protocol.is_none_or(|p| p.supports_component(fns_id));
+            let supported_len = remove_ids.iter().filter(supported).count();
+            if supported_len == 0 {
+                continue;
+            }
+
+            trace!(
+                "writing removals for `{entity}` with `{remove_ids:?}` for client `{client_entity}`"
+            );
+            if supported_len == ids_len {
                 message.add_removals(entity_range.clone(), ids_len, fn_ids.clone());
+            } else {
+                // Write supported IDs separately for clients with a different protocol.
+                let supported_ids = serialized.write_fn_ids(
+                    remove_ids
+                        .iter()
+                        .filter(supported)
+                        .map(|&(_, fns_id)| fns_id),
+                )?;
+                message.add_removals(entity_range.clone(), supported_len, supported_ids);
             }
         }
     }
@@ -584,19 +1025,34 @@ fn collect_removals(
     Ok(())
 }
 
+/// Returns `true` if the entity wasn't sent to the client yet because the streamed initial sync deferred it.
+///
+/// Such entities will be sent with their current components, so despawns and removals for them are skipped.
+fn is_deferred(initial_sync: InitialSync, ticks: &ClientTicks, entity: Entity) -> bool {
+    matches!(initial_sync, InitialSync::Streamed { .. })
+        && !ticks.is_sync_finished()
+        && ticks.mutation_tick(entity).is_none()
+}
+
 /// Collects component changes from this tick into update and mutate messages since the last entity tick.
 fn collect_changes(
     serialized: &mut SerializedData,
+    segments: &mut Vec<SerializedData>,
+    range_cache: &mut RangeCache,
     clients: &mut Query<(
         Entity,
         &mut Updates,
         &mut Mutations,
         &ConnectedClient,
-        &mut EntityCache,
+        Option<&NegotiatedProtocol>,
+        Option<&mut ServerReplicationStats>,
         &mut ClientTicks,
         &mut PriorityMap,
         &mut ClientVisibility,
     )>,
+    mut dirty_entities: Option<&mut DirtyEntities>,
+    mut profiler: Option<&mut BandwidthProfiler>,
+    initial_sync: InitialSync,
     registry: &ReplicationRegistry,
     type_registry: &AppTypeRegistry,
     related_entities: &RelatedEntities,
@@ -605,154 +1061,421 @@ fn collect_changes(
     change_tick: &SystemChangeTick,
     server_tick: RepliconTick,
 ) -> Result<()> {
-    for (archetype, replicated_archetype) in world.iter_archetypes() {
-        for entity in archetype.entities() {
-            let mut entity_range = None;
-            for (
-                _,
-                mut updates,
-                mut mutations,
-                ..,
-                mut entity_cache,
-                ticks,
-                priority,
-                visibility,
-            ) in &mut *clients
+    range_cache.clear();
+    match &mut dirty_entities {
+        Some(dirty_entities) if clients.iter().all(|(.., ticks, _, _)| ticks.is_scanned()) => {
+            dirty_entities.collect_mutated(world, change_tick);
+
+            let mut visited = EntityHashSet::default();
+            let pending = clients.iter().flat_map(|(.., ticks, _, _)| ticks.pending());
+            let gained = clients
+                .iter()
+                .flat_map(|(.., visibility)| visibility.gained());
+            for &entity in dirty_entities
+                .iter()
+                .chain(removal_buffer.keys())
+                .chain(pending)
+                .chain(gained)
             {
-                *entity_cache = EntityCache {
-                    mutation_tick: ticks.mutation_tick(entity.id()),
-                    visible: visibility.is_visible(entity.id()),
-                    base_priority: priority.get(&entity.id()).copied().unwrap_or(1.0),
+                if !visited.insert(entity) {
+                    continue;
+                }
+                if let Some((archetype_index, row)) = world.locate(entity) {
+                    let (_, replicated_archetype) = world.get_archetype(archetype_index);
+                    range_cache.push(archetype_index, row, replicated_archetype.components.len());
+                }
+            }
+            trace!("visiting {} dirty entities", visited.len());
+        }
+        _ => {
+            // Visit all entities if the mode is not dirty or if there are clients that need a full scan.
+            for (archetype_index, (archetype, replicated_archetype)) in
+                world.iter_archetypes().enumerate()
+            {
+                for row in 0..archetype.len() as usize {
+                    range_cache.push(archetype_index, row, replicated_archetype.components.len());
+                }
+            }
+        }
+    }
+
+    let ctx = CollectCtx {
+        range_cache,
+        dirty_entities: dirty_entities.as_deref(),
+        profile: profiler.is_some(),
+        initial_sync,
+        registry,
+        type_registry,
+        related_entities,
+        removal_buffer,
+        world,
+        change_tick,
+        server_tick,
+    };
+
+    let mut clients: Vec<_> = clients.iter_mut().collect();
+    let task_pool = ComputeTaskPool::get_or_init(TaskPool::default);
+    let chunk_size = chunk_size(clients.len(), task_pool);
+    let chunks = clients.chunks_mut(chunk_size);
+    let tasks_count = chunks.len();
+    if segments.len() < tasks_count {
+        segments.resize_with(tasks_count, Default::default);
+    }
+    let results = task_pool.scope(|scope| {
+        for (index, (chunk, segment)) in chunks.zip(&mut *segments).enumerate() {
+            segment.reset_segment(index);
+            let ctx = &ctx;
+            scope.spawn(async move {
+                let mut task = CollectTask {
+                    serialized: segment,
+                    profiled: Vec::new(),
                 };
-                updates.start_entity_changes();
-                mutations.start_entity();
+                for (
+                    client_entity,
+                    updates,
+                    mutations,
+                    _,
+                    protocol,
+                    _,
+                    ticks,
+                    priority,
+                    visibility,
+                ) in chunk
+                {
+                    let client = ClientChanges {
+                        entity: *client_entity,
+                        protocol: *protocol,
+                        priority,
+                        visibility,
+                    };
+                    client.collect(ctx, &mut task, updates, mutations, ticks)?;
+                    visibility.clear_gained();
+                }
+
+                Ok::<_, BevyError>(task.profiled)
+            });
+        }
+    });
+
+    for segment in &mut segments[..tasks_count] {
+        serialized.append(segment)?;
+    }
+
+    if let Some(dirty_entities) = dirty_entities {
+        dirty_entities.clear();
+    }
+
+    for result in results {
+        let profiled = result?;
+        if let Some(profiler) = &mut profiler {
+            for (client_entity, fns_id, bytes) in profiled {
+                profiler.add(client_entity, fns_id, bytes);
             }
+        }
+    }
 
-            for &(component_rule, storage) in &replicated_archetype.components {
-                let (component_id, component_fns, rule_fns) = registry.get(component_rule.fns_id);
+    Ok(())
+}
 
-                // SAFETY: component and storage were obtained from this archetype.
-                let (component, ticks) = unsafe {
-                    world.get_component_unchecked(
-                        entity,
-                        archetype.table_id(),
-                        storage,
-                        component_id,
-                    )
-                };
+/// Data shared between clients in [`collect_changes`].
+struct CollectCtx<'a, 'w, 's> {
+    range_cache: &'a RangeCache,
+    dirty_entities: Option<&'a DirtyEntities>,
+    profile: bool,
+    initial_sync: InitialSync,
+    registry: &'a ReplicationRegistry,
+    type_registry: &'a AppTypeRegistry,
+    related_entities: &'a RelatedEntities,
+    removal_buffer: &'a RemovalBuffer,
+    world: &'a ServerWorld<'w, 's>,
+    change_tick: &'a SystemChangeTick,
+    server_tick: RepliconTick,
+}
+
+/// Data written by a single task in [`collect_changes`].
+///
+/// Merged after all tasks finish.
+struct CollectTask<'a> {
+    /// Segment for data that wasn't written by other clients yet.
+    serialized: &'a mut SerializedData,
+
+    /// Written component bytes for [`BandwidthProfiler`].
+    profiled: Vec<(Entity, FnsId, usize)>,
+}
+
+/// Progress of the streamed initial sync for a client in the current tick.
+///
+/// See [`InitialSync::Streamed`].
+struct SyncChunk {
+    /// Remaining size for entity data in this tick.
+    bytes_left: usize,
+
+    /// Number of entities written in this tick.
+    synced: usize,
+
+    /// Number of entities that didn't fit and were deferred to the next ticks.
+    deferred: usize,
+}
 
-                let ctx = SerializeCtx {
-                    server_tick,
+/// Per-client data for [`collect_changes`].
+struct ClientChanges<'a> {
+    entity: Entity,
+    protocol: Option<&'a NegotiatedProtocol>,
+    priority: &'a PriorityMap,
+    visibility: &'a ClientVisibility,
+}
+
+impl ClientChanges<'_> {
+    /// Writes changed components of visited entities for this client.
+    ///
+    /// Serialized data is shared with other clients via [`CollectCtx::range_cache`].
+    fn collect(
+        &self,
+        ctx: &CollectCtx,
+        task: &mut CollectTask,
+        updates: &mut Updates,
+        mutations: &mut Mutations,
+        ticks: &mut ClientTicks,
+    ) -> Result<()> {
+        let mut sync = match ctx.initial_sync {
+            InitialSync::Streamed { max_bytes } if !ticks.is_sync_finished() => Some(SyncChunk {
+                bytes_left: max_bytes,
+                synced: 0,
+                deferred: 0,
+            }),
+            _ => None,
+        };
+
+        for (entity_slot, archetype_index, row) in ctx.range_cache.iter() {
+            let (archetype, replicated_archetype) = ctx.world.get_archetype(archetype_index);
+            let entity = &archetype.entities()[row];
+            let pending = self.collect_entity(
+                ctx,
+                task,
+                updates,
+                mutations,
+                ticks,
+                sync.as_mut(),
+                entity_slot,
+                archetype,
+                replicated_archetype,
+                entity,
+            )?;
+            if ctx.dirty_entities.is_some() {
+                ticks.set_pending(entity.id(), pending);
+            }
+        }
+
+        let mut deferred = 0;
+        if let Some(sync) = sync {
+            let synced = ticks.add_synced(sync.synced);
+            let total = synced + sync.deferred;
+            trace!(
+                "sending initial sync {synced}/{total} for client `{}`",
+                self.entity
+            );
+            updates.set_sync(synced, total);
+            if sync.deferred == 0 {
+                debug!("finishing initial sync for client `{}`", self.entity);
+                ticks.finish_sync();
+            }
+            deferred = sync.deferred;
+        }
+
+        // Deferred entities need to be visited again.
+        if ctx.dirty_entities.is_some() && deferred == 0 {
+            ticks.mark_scanned();
+        }
+
+        Ok(())
+    }
+
+    /// Writes changed components of an entity for this client.
+    ///
+    /// Returns `true` if the entity has mutations that weren't acknowledged by the client.
+    fn collect_entity(
+        &self,
+        ctx: &CollectCtx,
+        task: &mut CollectTask,
+        updates: &mut Updates,
+        mutations: &mut Mutations,
+        ticks: &mut ClientTicks,
+        mut sync: Option<&mut SyncChunk>,
+        entity_slot: usize,
+        archetype: &Archetype,
+        replicated_archetype: &ReplicatedArchetype,
+        entity: &ArchetypeEntity,
+    ) -> Result<bool> {
+        let client_entity = self.entity;
+        let entity_cache = EntityCache {
+            mutation_tick: ticks.mutation_tick(entity.id()),
+            visible: self.visibility.is_visible(entity.id()),
+            base_priority: self.priority.get(&entity.id()).copied().unwrap_or(1.0),
+        };
+        if !entity_cache.visible {
+            return Ok(false);
+        }
+
+        let new_for_client = entity_cache.is_new_for_client();
+        if new_for_client && let Some(sync) = &mut sync {
+            if sync.bytes_left == 0 {
+                sync.deferred += 1;
+                return Ok(false);
+            }
+            sync.synced += 1;
+        }
+
+        updates.start_entity_changes();
+        mutations.start_entity();
+
+        let mut pending = false;
+        for (component_index, &(component_rule, storage)) in
+            replicated_archetype.components.iter().enumerate()
+        {
+            if self
+                .protocol
+                .is_some_and(|p| !p.supports_component(component_rule.fns_id))
+            {
+                continue;
+            }
+
+            let (component_id, component_fns, rule_fns) = ctx.registry.get(component_rule.fns_id);
+
+            // SAFETY: component and storage were obtained from this archetype.
+            let (component, component_ticks) = unsafe {
+                ctx.world.get_component_unchecked(
+                    entity,
+                    archetype.table_id(),
+                    storage,
                     component_id,
-                    type_registry,
+                )
+            };
+
+            let write_component = |serialized: &mut SerializedData| {
+                let serialize_ctx = SerializeCtx {
+                    server_tick: ctx.server_tick,
+                    component_id,
+                    type_registry: ctx.type_registry,
                 };
-                let mut component_range = None;
-                for (client_entity, mut updates, mut mutations, .., entity_cache, _, _, _) in
-                    &mut *clients
-                {
-                    if !entity_cache.visible {
-                        continue;
-                    }
+                serialized.write_component(
+                    rule_fns,
+                    component_fns,
+                    &serialize_ctx,
+                    component_rule.fns_id,
+                    component,
+                )
+            };
 
-                    if let Some((last_system_tick, last_server_tick)) = entity_cache.mutation_tick
-                        && !ticks.is_added(change_tick.last_run(), change_tick.this_run())
-                    {
-                        let tick_diff = server_tick - last_server_tick;
-                        if component_rule.mode != ReplicationMode::Once
-                            && entity_cache.base_priority * tick_diff as f32 >= 1.0
-                            && ticks.is_changed(last_system_tick, change_tick.this_run())
-                        {
-                            if !mutations.entity_added() {
-                                let graph_index = related_entities.graph_index(entity.id());
-                                let entity_range = write_entity_cached(
-                                    &mut entity_range,
-                                    serialized,
-                                    entity.id(),
-                                )?;
-                                mutations.add_entity(entity.id(), graph_index, entity_range);
-                            }
-                            let component_range = write_component_cached(
-                                &mut component_range,
-                                serialized,
-                                rule_fns,
-                                component_fns,
-                                &ctx,
-                                component_rule,
-                                component,
+            let this_run = ctx.change_tick.this_run();
+            if let Some((last_system_tick, last_server_tick)) = entity_cache.mutation_tick
+                && !component_ticks.is_added(ctx.change_tick.last_run(), this_run)
+            {
+                if component_rule.mode != ReplicationMode::Once
+                    && component_ticks.is_changed(last_system_tick, this_run)
+                {
+                    pending = true;
+                    let tick_diff = ctx.server_tick - last_server_tick;
+                    if entity_cache.base_priority * tick_diff as f32 >= 1.0 {
+                        if !mutations.entity_added() {
+                            let graph_index = ctx.related_entities.graph_index(entity.id());
+                            let entity_range = ctx.range_cache.entity(
+                                task.serialized,
+                                entity_slot,
+                                |serialized| serialized.write_entity(entity.id()),
                             )?;
-
-                            trace!(
-                                "writing mutation for `{}` with `{:?}` for client `{client_entity}`",
-                                entity.id(),
-                                component_rule.fns_id,
-                            );
-                            mutations.add_component(component_range);
-                        }
-                    } else {
-                        if !updates.changed_entity_added() {
-                            let entity_range =
-                                write_entity_cached(&mut entity_range, serialized, entity.id())?;
-                            updates.add_changed_entity(entity_range);
+                            mutations.add_entity(entity.id(), graph_index, entity_range);
                         }
-                        let component_range = write_component_cached(
-                            &mut component_range,
-                            serialized,
-                            rule_fns,
-                            component_fns,
-                            &ctx,
-                            component_rule,
-                            component,
+                        let component_range = ctx.range_cache.component(
+                            task.serialized,
+                            entity_slot,
+                            component_index,
+                            write_component,
                         )?;
+                        if ctx.profile {
+                            task.profiled.push((
+                                client_entity,
+                                component_rule.fns_id,
+                                component_range.len(),
+                            ));
+                        }
 
                         trace!(
-                            "writing insertion for `{}` with `{:?}` for client `{client_entity}`",
+                            "writing mutation for `{}` with `{:?}` for client `{client_entity}`",
                             entity.id(),
                             component_rule.fns_id,
                         );
-                        updates.add_inserted_component(component_range);
+                        mutations.add_component(component_range);
                     }
                 }
-            }
-
-            for (client_entity, mut updates, mut mutations, .., entity_cache, mut ticks, _, _) in
-                &mut *clients
-            {
-                if !entity_cache.visible {
-                    continue;
+            } else {
+                if !updates.changed_entity_added() {
+                    let entity_range =
+                        ctx.range_cache
+                            .entity(task.serialized, entity_slot, |serialized| {
+                                serialized.write_entity(entity.id())
+                            })?;
+                    updates.add_changed_entity(entity_range);
                 }
-
-                if entity_cache.is_new_for_client()
-                    || updates.changed_entity_added()
-                    || removal_buffer.contains_key(&entity.id())
-                {
-                    // If there is any insertion, removal, or it's a new entity for a client, include all mutations
-                    // into update message and bump the last acknowledged tick to keep entity updates atomic.
-                    if mutations.entity_added() {
-                        trace!(
-                            "merging mutations for `{}` with updates for client `{client_entity}`",
-                            entity.id()
-                        );
-                        updates.take_added_entity(&mut mutations);
-                    }
-                    ticks.set_mutation_tick(entity.id(), change_tick.this_run(), server_tick);
+                let component_range = ctx.range_cache.component(
+                    task.serialized,
+                    entity_slot,
+                    component_index,
+                    write_component,
+                )?;
+                if ctx.profile {
+                    task.profiled.push((
+                        client_entity,
+                        component_rule.fns_id,
+                        component_range.len(),
+                    ));
                 }
 
-                if entity_cache.is_new_for_client() && !updates.changed_entity_added() {
-                    trace!(
-                        "writing empty `{}` for client `{client_entity}`",
-                        entity.id()
-                    );
-
-                    // Force-write new entity even if it doesn't have any components.
-                    let entity_range =
-                        write_entity_cached(&mut entity_range, serialized, entity.id())?;
-                    updates.add_changed_entity(entity_range);
+                trace!(
+                    "writing insertion for `{}` with `{:?}` for client `{client_entity}`",
+                    entity.id(),
+                    component_rule.fns_id,
+                );
+                if new_for_client && let Some(sync) = &mut sync {
+                    sync.bytes_left = sync.bytes_left.saturating_sub(component_range.len());
                 }
+                updates.add_inserted_component(component_range);
             }
         }
-    }
 
-    Ok(())
+        if entity_cache.is_new_for_client()
+            || updates.changed_entity_added()
+            || ctx.removal_buffer.contains_key(&entity.id())
+        {
+            // If there is any insertion, removal, or it's a new entity for a client, include all mutations
+            // into update message and bump the last acknowledged tick to keep entity updates atomic.
+            if mutations.entity_added() {
+                trace!(
+                    "merging mutations for `{}` with updates for client `{client_entity}`",
+                    entity.id()
+                );
+                updates.take_added_entity(mutations);
+            }
+            ticks.set_mutation_tick(entity.id(), ctx.change_tick.this_run(), ctx.server_tick);
+            pending = false;
+        }
+
+        if entity_cache.is_new_for_client() && !updates.changed_entity_added() {
+            trace!(
+                "writing empty `{}` for client `{client_entity}`",
+                entity.id()
+            );
+
+            // Force-write new entity even if it doesn't have any components.
+            let entity_range =
+                ctx.range_cache
+                    .entity(task.serialized, entity_slot, |serialized| {
+                        serialized.write_entity(entity.id())
+                    })?;
+            updates.add_changed_entity(entity_range);
+        }
+
+        Ok(pending)
+    }
 }
 
 fn should_send_mapping(
@@ -785,64 +1508,6 @@ fn write_mapping_cached(
     Ok(range)
 }
 
-/// Writes an entity or re-uses previously written range if exists.
-fn write_entity_cached(
-    entity_range: &mut Option<Range<usize>>,
-    serialized: &mut SerializedData,
-    entity: Entity,
-) -> Result<Range<usize>> {
-    if let Some(range) = entity_range.clone() {
-        return Ok(range);
-    }
-
-    let range = serialized.write_entity(entity)?;
-    *entity_range = Some(range.clone());
-
-    Ok(range)
-}
-
-/// Writes a component or re-uses previously written range if exists.
-fn write_component_cached(
-    component_range: &mut Option<Range<usize>>,
-    serialized: &mut SerializedData,
-    rule_fns: &UntypedRuleFns,
-    component_fns: &ComponentFns,
-    ctx: &SerializeCtx,
-    component_rule: ComponentRule,
-    component: Ptr<'_>,
-) -> Result<Range<usize>> {
-    if let Some(component_range) = component_range.clone() {
-        return Ok(component_range);
-    }
-
-    let range = serialized.write_component(
-        rule_fns,
-        component_fns,
-        ctx,
-        component_rule.fns_id,
-        component,
-    )?;
-    *component_range = Some(range.clone());
-
-    Ok(range)
-}
-
-/// Writes an entity or re-uses previously written range if exists.
-fn write_tick_cached(
-    tick_range: &mut Option<Range<usize>>,
-    serialized: &mut SerializedData,
-    tick: RepliconTick,
-) -> Result<Range<usize>> {
-    if let Some(range) = tick_range.clone() {
-        return Ok(range);
-    }
-
-    let range = serialized.write_tick(tick)?;
-    *tick_range = Some(range.clone());
-
-    Ok(range)
-}
-
 /// Set with replication and event systems related to server.
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
 pub enum ServerSystems {
@@ -877,6 +1542,11 @@ pub enum ServerSystems {
     ///
     /// Runs in [`PostUpdate`] if [`ServerTick`] changes.
     SendPackets,
+    /// Systems that write diagnostics from [`ServerReplicationStats`] and
+    /// reports from [`BandwidthProfiler`].
+    ///
+    /// Runs in [`PostUpdate`].
+    Diagnostics,
 }
 
 /// Buffer with all despawned entities.
@@ -887,6 +1557,60 @@ pub enum ServerSystems {
 #[derive(Default, Resource, Deref, DerefMut)]
 struct DespawnBuffer(Vec<Entity>);
 
+/// Replication stats during message sending.
+///
+/// Statistic will be collected only if the resource is present.
+/// The resource is not added by default.
+///
+/// Values for individual clients are also collected into this component
+/// if it's present on a client entity.
+///
+/// See also `ServerDiagnosticsPlugin` (with feature `server_diagnostics`)
+/// for automatic integration with Bevy diagnostics.
+#[derive(Clone, Copy, Default, Resource, Component, Debug)]
+pub struct ServerReplicationStats {
+    /// Incremented per entity with changes in update messages.
+    pub update_entities: usize,
+    /// Incremented for every component in update messages.
+    pub update_components: usize,
+    /// Update messages sent.
+    pub update_messages: usize,
+    /// Update bytes sent in message payloads (without internal messaging plugin data).
+    pub update_bytes: usize,
+    /// Incremented per entity in mutate messages.
+    pub mutate_entities: usize,
+    /// Incremented for every component in mutate messages.
+    pub mutate_components: usize,
+    /// Mutate messages sent.
+    pub mutate_messages: usize,
+    /// Mutate bytes sent in message payloads (without internal messaging plugin data).
+    pub mutate_bytes: usize,
+    /// Incremented per mutate message that wasn't acknowledged within [`ServerPlugin::mutations_timeout`].
+    ///
+    /// Mutations from such messages will be resent.
+    pub resends: usize,
+    /// Number of sent mutate messages that are waiting for acknowledgment.
+    ///
+    /// Updated on each replication send.
+    pub pending_acks: usize,
+}
+
+impl ServerReplicationStats {
+    /// Adds all values from `other`.
+    fn add(&mut self, other: &Self) {
+        self.update_entities += other.update_entities;
+        self.update_components += other.update_components;
+        self.update_messages += other.update_messages;
+        self.update_bytes += other.update_bytes;
+        self.mutate_entities += other.mutate_entities;
+        self.mutate_components += other.mutate_components;
+        self.mutate_messages += other.mutate_messages;
+        self.mutate_bytes += other.mutate_bytes;
+        self.resends += other.resends;
+        self.pending_acks += other.pending_acks;
+    }
+}
+
 /// Marker that enables replication and all events for a client.
 ///
 /// Until authorization happened, the client and server can still exchange network events that are marked as
@@ -895,7 +1619,7 @@ struct DespawnBuffer(Vec<Entity>);
 ///
 /// See also [`ConnectedClient`] and [`RepliconSharedPlugin::auth_method`].
 #[derive(Component, Default)]
-#[require(ClientTicks, PriorityMap, EntityCache, Updates, Mutations)]
+#[require(ClientTicks, PriorityMap, Updates, Mutations)]
 pub struct AuthorizedClient;
 
 /// Controls how often mutations are sent for an authorized client.
@@ -924,9 +1648,9 @@ pub struct PriorityMap(EntityHashMap<f32>);
 /// Cached data from [`ClientTicks`] and [`ClientVisibility`] about the entity
 /// currently being processed during [`collect_changes`].
 ///
-/// Because we iterate over clients for each component, this information is
+/// Because we iterate over all components of an entity, this information is
 /// cached to avoid redundant lookups.
-#[derive(Component, Default, Clone, Copy)]
+#[derive(Default, Clone, Copy)]
 struct EntityCache {
     mutation_tick: Option<(Tick, RepliconTick)>,
     visible: bool,
@@ -0,0 +1,210 @@
+use core::{cmp::Reverse, time::Duration};
+
+use alloc::vec::Vec;
+use bevy::{ecs::entity::EntityHashMap, prelude::*};
+use log::info;
+
+use crate::shared::replication::registry::{FnsId, ReplicationRegistry};
+
+/// Attributes serialized component bytes to each [`FnsId`] and client.
+///
+/// Bytes are collected only if the resource is present.
+/// The resource is not added by default.
+///
+/// Every [`Self::window`], the accumulated data is turned into
+/// [`BandwidthReport`] resource and reset.
+///
+/// # Examples
+///
+/// ```
+/// use core::time::Duration;
+///
+/// use bevy::{prelude::*, state::app::StatesPlugin};
+/// use bevy_replicon::{prelude::*, server::bandwidth_profiler::BandwidthProfiler};
+///
+/// let mut profiler = BandwidthProfiler::new(Duration::from_secs(5));
+/// profiler.log_report = true;
+///
+/// let mut app = App::new();
+/// app.add_plugins((MinimalPlugins, StatesPlugin, RepliconPlugins))
+///     .insert_resource(profiler);
+/// ```
+#[derive(Resource)]
+pub struct BandwidthProfiler {
+    /// Time over which bytes are accumulated before producing a report.
+    ///
+    /// By default it's set to 1 second.
+    pub window: Duration,
+
+    /// Log each report at info level, ordered by cost.
+    ///
+    /// By default it's set to `false`.
+    pub log_report: bool,
+
+    /// Elapsed time when the current window started.
+    window_start: Option<Duration>,
+
+    /// Accumulated data for all clients, indexed by [`FnsId`].
+    components: Vec<ComponentBytes>,
+
+    /// Accumulated data for each client, indexed by [`FnsId`].
+    clients: EntityHashMap<Vec<ComponentBytes>>,
+}
+
+impl BandwidthProfiler {
+    /// Creates a profiler with the given [`Self::window`].
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            ..Default::default()
+        }
+    }
+
+    /// Records `bytes` written for `client` using replication functions from `fns_id`.
+    pub(crate) fn add(&mut self, client: Entity, fns_id: FnsId, bytes: usize) {
+        add_bytes(&mut self.components, fns_id, bytes);
+        add_bytes(self.clients.entry(client).or_default(), fns_id, bytes);
+    }
+
+    /// Builds a report from the accumulated data and resets it.
+    fn take_report(&mut self, registry: &ReplicationRegistry, window: Duration) -> BandwidthReport {
+        let mut components: Vec<_> = self
+            .components
+            .iter()
+            .enumerate()
+            .filter(|(_, component)| component.count != 0)
+            .map(|(index, component)| {
+                let fns_id = registry.fns_id(index);
+                let mut clients: Vec<_> = self
+                    .clients
+                    .iter()
+                    .filter_map(|(&client, components)| {
+                        components
+                            .get(index)
+                            .filter(|component| component.count != 0)
+                            .map(|component| (client, component.bytes))
+                    })
+                    .collect();
+                clients.sort_unstable_by_key(|&(_, bytes)| Reverse(bytes));
+
+                ComponentCost {
+                    fns_id,
+                    name: registry.type_name(fns_id),
+                    bytes: component.bytes,
+                    count: component.count,
+                    clients,
+                }
+            })
+            .collect();
+        components.sort_unstable_by_key(|cost| Reverse(cost.bytes));
+
+        self.components.clear();
+        self.clients.clear();
+
+        BandwidthReport { window, components }
+    }
+}
+
+impl Default for BandwidthProfiler {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(1),
+            log_report: false,
+            window_start: None,
+            components: Default::default(),
+            clients: Default::default(),
+        }
+    }
+}
+
+fn add_bytes(components: &mut Vec<ComponentBytes>, fns_id: FnsId, bytes: usize) {
+    let index = fns_id.index();
+    if components.len() <= index {
+        components.resize(index + 1, Default::default());
+    }
+    let component = &mut components[index];
+    component.bytes += bytes;
+    component.count += 1;
+}
+
+#[derive(Clone, Copy, Default)]
+struct ComponentBytes {
+    bytes: usize,
+    count: usize,
+}
+
+/// Bandwidth used by replicated components during the last [`BandwidthProfiler::window`].
+///
+/// Inserted by the server when the window ends if [`BandwidthProfiler`] is present.
+#[derive(Resource, Clone, Default, Debug)]
+pub struct BandwidthReport {
+    /// Actual time over which the data was collected.
+    pub window: Duration,
+
+    /// Costs for each written component, ordered by bytes from highest to lowest.
+    pub components: Vec<ComponentCost>,
+}
+
+impl BandwidthReport {
+    /// Returns the total number of component bytes in the report.
+    pub fn total_bytes(&self) -> usize {
+        self.components.iter().map(|cost| cost.bytes).sum()
+    }
+}
+
+/// Bandwidth used by a component with specific replication functions.
+///
+/// Includes only serialized component data with its ID, without entity and message headers.
+/// Bytes are counted once per client the data was written for.
+#[derive(Clone, Debug)]
+pub struct ComponentCost {
+    /// Replication functions used for serialization.
+    pub fns_id: FnsId,
+
+    /// Type name of the component.
+    pub name: &'static str,
+
+    /// Written bytes for all clients.
+    pub bytes: usize,
+
+    /// How many times the component was written for all clients.
+    pub count: usize,
+
+    /// Written bytes for each client, ordered from highest to lowest.
+    pub clients: Vec<(Entity, usize)>,
+}
+
+pub(super) fn update_report(
+    mut commands: Commands,
+    mut profiler: ResMut<BandwidthProfiler>,
+    registry: Res<ReplicationRegistry>,
+    time: Res<Time>,
+) {
+    let elapsed = time.elapsed();
+    let window_start = *profiler.window_start.get_or_insert(elapsed);
+    let window = elapsed - window_start;
+    if window < profiler.window {
+        return;
+    }
+
+    let report = profiler.take_report(&registry, window);
+    profiler.window_start = Some(elapsed);
+
+    if profiler.log_report {
+        info!(
+            "replicated {} component bytes in {window:?}",
+            report.total_bytes()
+        );
+        for cost in &report.components {
+            info!(
+                "`{}` ({:?}): {} bytes in {} writes",
+                ShortName(cost.name),
+                cost.fns_id,
+                cost.bytes,
+                cost.count,
+            );
+        }
+    }
+
+    commands.insert_resource(report);
+}
@@ -1,4 +1,9 @@
-use bevy::{ecs::entity::hash_set::EntityHashSet, prelude::*};
+use alloc::vec::Vec;
+
+use bevy::{
+    ecs::entity::{hash_map::EntityHashMap, hash_set::EntityHashSet},
+    prelude::*,
+};
 
 /// Entity visibility settings for a client.
 ///
@@ -58,6 +63,15 @@ pub struct ClientVisibility {
 
     /// All entities that lost visibility in this tick.
     lost: EntityHashSet,
+
+    /// All entities that gained visibility in this tick.
+    gained: EntityHashSet,
+
+    /// Entities that are visible because visible entities reference them.
+    ///
+    /// Initialized on the first resolution if
+    /// [`ServerPlugin::visibility_dependencies`](super::ServerPlugin::visibility_dependencies) is enabled.
+    dependencies: Option<Dependencies>,
 }
 
 impl ClientVisibility {
@@ -66,6 +80,8 @@ impl ClientVisibility {
             policy: VisibilityPolicy::Blacklist,
             entities: Default::default(),
             lost: Default::default(),
+            gained: Default::default(),
+            dependencies: None,
         }
     }
 
@@ -74,6 +90,8 @@ impl ClientVisibility {
             policy: VisibilityPolicy::Whitelist,
             entities: Default::default(),
             lost: Default::default(),
+            gained: Default::default(),
+            dependencies: None,
         }
     }
 
@@ -82,6 +100,90 @@ impl ClientVisibility {
         if self.entities.remove(&entity) {
             self.lost.remove(&entity);
         }
+        self.gained.remove(&entity);
+
+        if let Some(dependencies) = &mut self.dependencies {
+            dependencies.counts.remove(&entity);
+            dependencies.changed.remove(&entity);
+        }
+        self.set_references(entity, &[]);
+    }
+
+    /// Starts tracking dependencies if it's not started yet.
+    ///
+    /// Returns `true` if tracking was started, which means that references of all entities need to be set.
+    pub(super) fn init_dependencies(&mut self) -> bool {
+        if self.dependencies.is_some() {
+            return false;
+        }
+
+        self.dependencies = Some(Default::default());
+        true
+    }
+
+    /// Drains entities whose visibility was changed via [`Self::set_visibility`] since the last call.
+    pub(super) fn drain_set(&mut self) -> impl Iterator<Item = Entity> + '_ {
+        self.dependencies
+            .iter_mut()
+            .flat_map(|dependencies| dependencies.changed.drain())
+    }
+
+    /// Replaces entities referenced by `entity` that should be visible because of it.
+    ///
+    /// Entities gain visibility when the first visible entity references them and lose
+    /// it when the last one stops, unless they are visible via [`Self::set_visibility`].
+    pub(super) fn set_references(&mut self, entity: Entity, references: &[Entity]) {
+        let Some(dependencies) = &mut self.dependencies else {
+            return;
+        };
+
+        let old_references = if references.is_empty() {
+            let Some(old_references) = dependencies.references.remove(&entity) else {
+                return;
+            };
+            old_references
+        } else {
+            dependencies
+                .references
+                .insert(entity, references.to_vec())
+                .unwrap_or_default()
+        };
+
+        // Add new references first to avoid losing visibility for entities that are referenced in both.
+        for &reference in references {
+            let was_visible = self.is_visible(reference);
+            let dependencies = self.dependencies.as_mut().unwrap();
+            *dependencies.counts.entry(reference).or_default() += 1;
+            self.update_changes(reference, was_visible);
+        }
+
+        for reference in old_references {
+            let dependencies = self.dependencies.as_mut().unwrap();
+            let Some(count) = dependencies.counts.get_mut(&reference) else {
+                // Was despawned.
+                continue;
+            };
+            *count -= 1;
+            if *count == 0 {
+                dependencies.counts.remove(&reference);
+                self.update_changes(reference, true);
+            }
+        }
+    }
+
+    /// Updates gained and lost entities if the entity visibility differs from `was_visible`.
+    fn update_changes(&mut self, entity: Entity, was_visible: bool) {
+        match (was_visible, self.is_visible(entity)) {
+            (false, true) => {
+                self.lost.remove(&entity);
+                self.gained.insert(entity);
+            }
+            (true, false) => {
+                self.lost.insert(entity);
+                self.gained.remove(&entity);
+            }
+            _ => (),
+        }
     }
 
     /// Drains all entities for which visibility was lost during this tick.
@@ -89,34 +191,64 @@ impl ClientVisibility {
         self.lost.drain()
     }
 
+    /// Returns entities for which visibility was gained during this tick.
+    pub(super) fn gained(&self) -> &EntityHashSet {
+        &self.gained
+    }
+
+    /// Returns entities for which visibility was gained or lost during this tick.
+    pub(super) fn changed(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.gained.iter().chain(&self.lost).copied()
+    }
+
+    /// Clears entities for which visibility was gained during this tick.
+    pub(super) fn clear_gained(&mut self) {
+        self.gained.clear();
+    }
+
     /// Sets visibility for a specific entity.
+    ///
+    /// Entities referenced by visible entities stay visible regardless of this setting if
+    /// [`ServerPlugin::visibility_dependencies`](super::ServerPlugin::visibility_dependencies) is enabled.
     pub fn set_visibility(&mut self, entity: Entity, visible: bool) {
-        match (self.policy, visible) {
-            (VisibilityPolicy::Blacklist, true) => {
-                if self.entities.remove(&entity) {
-                    self.lost.remove(&entity);
-                }
-            }
-            (VisibilityPolicy::Blacklist, false) => {
-                if self.entities.insert(entity) {
-                    self.lost.insert(entity);
-                }
+        let was_visible = self.is_visible(entity);
+        let changed = match (self.policy, visible) {
+            (VisibilityPolicy::Blacklist, true) | (VisibilityPolicy::Whitelist, false) => {
+                self.entities.remove(&entity)
             }
-            (VisibilityPolicy::Whitelist, true) => {
-                if self.entities.insert(entity) {
-                    self.lost.remove(&entity);
-                }
+            (VisibilityPolicy::Blacklist, false) | (VisibilityPolicy::Whitelist, true) => {
+                self.entities.insert(entity)
             }
-            (VisibilityPolicy::Whitelist, false) => {
-                if self.entities.remove(&entity) {
-                    self.lost.insert(entity);
-                }
+        };
+
+        if changed {
+            if let Some(dependencies) = &mut self.dependencies {
+                dependencies.changed.insert(entity);
             }
+            self.update_changes(entity, was_visible);
         }
     }
 
+    /// Resets visibility for a specific entity to the default of the policy.
+    pub(super) fn reset_visibility(&mut self, entity: Entity) {
+        let visible = match self.policy {
+            VisibilityPolicy::Blacklist => true,
+            VisibilityPolicy::Whitelist => false,
+        };
+        self.set_visibility(entity, visible);
+    }
+
     /// Checks if a specific entity is visible.
     pub fn is_visible(&self, entity: Entity) -> bool {
+        self.is_set_visible(entity)
+            || self
+                .dependencies
+                .as_ref()
+                .is_some_and(|dependencies| dependencies.counts.contains_key(&entity))
+    }
+
+    /// Checks if a specific entity is visible without considering dependencies.
+    pub(super) fn is_set_visible(&self, entity: Entity) -> bool {
         match self.policy {
             VisibilityPolicy::Blacklist => !self.entities.contains(&entity),
             VisibilityPolicy::Whitelist => self.entities.contains(&entity),
@@ -124,6 +256,19 @@ impl ClientVisibility {
     }
 }
 
+/// Visibility dependencies for [`ClientVisibility`].
+#[derive(Default)]
+struct Dependencies {
+    /// Entities referenced by each visible entity.
+    references: EntityHashMap<Vec<Entity>>,
+
+    /// Number of visible entities that reference each entity.
+    counts: EntityHashMap<usize>,
+
+    /// Entities whose visibility was changed via [`ClientVisibility::set_visibility`] since the last resolution.
+    changed: EntityHashSet,
+}
+
 /// Controls how visibility will be managed via [`ClientVisibility`].
 #[derive(Default, Debug, Clone, Copy)]
 pub enum VisibilityPolicy {
@@ -201,6 +346,16 @@ mod tests {
         assert!(visibility.lost.contains(&Entity::PLACEHOLDER));
     }
 
+    #[test]
+    fn whitelist_gained() {
+        let mut visibility = ClientVisibility::whitelist();
+        visibility.set_visibility(Entity::PLACEHOLDER, true);
+        assert!(visibility.gained.contains(&Entity::PLACEHOLDER));
+
+        visibility.set_visibility(Entity::PLACEHOLDER, false);
+        assert!(!visibility.gained.contains(&Entity::PLACEHOLDER));
+    }
+
     #[test]
     fn whitelist_duplicate_insertion() {
         let mut visibility = ClientVisibility::whitelist();
@@ -209,4 +364,25 @@ mod tests {
         assert!(visibility.is_visible(Entity::PLACEHOLDER));
         assert!(!visibility.lost.contains(&Entity::PLACEHOLDER));
     }
+
+    #[test]
+    fn whitelist_dependencies() {
+        let mut visibility = ClientVisibility::whitelist();
+        assert!(visibility.init_dependencies());
+        let entity = Entity::from_raw_u32(0).unwrap();
+        let dependency = Entity::from_raw_u32(1).unwrap();
+
+        visibility.set_references(entity, &[dependency]);
+        assert!(visibility.is_visible(dependency));
+        assert!(!visibility.is_set_visible(dependency));
+        assert!(visibility.gained.contains(&dependency));
+
+        visibility.set_visibility(dependency, true);
+        visibility.set_references(entity, &[]);
+        assert!(visibility.is_visible(dependency));
+
+        visibility.set_visibility(dependency, false);
+        assert!(!visibility.is_visible(dependency));
+        assert!(visibility.lost.contains(&dependency));
+    }
 }
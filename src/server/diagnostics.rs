@@ -0,0 +1,159 @@
+use bevy::diagnostic::DiagnosticPath;
+use bevy::{
+    diagnostic::{Diagnostic, Diagnostics, RegisterDiagnostic},
+    prelude::*,
+};
+
+use crate::prelude::*;
+
+/// Plugin to write [`Diagnostics`] based on [`ServerReplicationStats`].
+///
+/// Adds [`ServerReplicationStats`] resource for totals and requires
+/// it as a component on [`AuthorizedClient`] to collect per-client values.
+pub struct ServerDiagnosticsPlugin;
+
+impl Plugin for ServerDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ServerReplicationStats>()
+            .register_required_components::<AuthorizedClient, ServerReplicationStats>()
+            .add_systems(
+                PostUpdate,
+                add_measurements
+                    .in_set(ServerSystems::Diagnostics)
+                    .run_if(in_state(ServerState::Running)),
+            )
+            .register_diagnostic(
+                Diagnostic::new(UPDATE_ENTITIES)
+                    .with_suffix(" entities")
+                    .with_max_history_length(DIAGNOSTIC_HISTORY_LEN),
+            )
+            .register_diagnostic(
+                Diagnostic::new(UPDATE_COMPONENTS)
+                    .with_suffix(" components")
+                    .with_max_history_length(DIAGNOSTIC_HISTORY_LEN),
+            )
+            .register_diagnostic(
+                Diagnostic::new(UPDATE_MESSAGES)
+                    .with_suffix(" update messages")
+                    .with_max_history_length(DIAGNOSTIC_HISTORY_LEN),
+            )
+            .register_diagnostic(
+                Diagnostic::new(UPDATE_BYTES)
+                    .with_suffix(" update bytes")
+                    .with_max_history_length(DIAGNOSTIC_HISTORY_LEN),
+            )
+            .register_diagnostic(
+                Diagnostic::new(MUTATE_ENTITIES)
+                    .with_suffix(" entities")
+                    .with_max_history_length(DIAGNOSTIC_HISTORY_LEN),
+            )
+            .register_diagnostic(
+                Diagnostic::new(MUTATE_COMPONENTS)
+                    .with_suffix(" components")
+                    .with_max_history_length(DIAGNOSTIC_HISTORY_LEN),
+            )
+            .register_diagnostic(
+                Diagnostic::new(MUTATE_MESSAGES)
+                    .with_suffix(" mutate messages")
+                    .with_max_history_length(DIAGNOSTIC_HISTORY_LEN),
+            )
+            .register_diagnostic(
+                Diagnostic::new(MUTATE_BYTES)
+                    .with_suffix(" mutate bytes")
+                    .with_max_history_length(DIAGNOSTIC_HISTORY_LEN),
+            )
+            .register_diagnostic(
+                Diagnostic::new(RESENDS)
+                    .with_suffix(" resends")
+                    .with_max_history_length(DIAGNOSTIC_HISTORY_LEN),
+            )
+            .register_diagnostic(
+                Diagnostic::new(PENDING_ACKS)
+                    .with_suffix(" pending acks")
+                    .with_max_history_length(DIAGNOSTIC_HISTORY_LEN),
+            );
+    }
+}
+
+/// How many entities written into update messages.
+pub const UPDATE_ENTITIES: DiagnosticPath =
+    DiagnosticPath::const_new("server/replication/update_entities");
+/// How many components written into update messages.
+pub const UPDATE_COMPONENTS: DiagnosticPath =
+    DiagnosticPath::const_new("server/replication/update_components");
+/// How many update messages sent.
+pub const UPDATE_MESSAGES: DiagnosticPath =
+    DiagnosticPath::const_new("server/replication/update_messages");
+/// How many update bytes sent.
+pub const UPDATE_BYTES: DiagnosticPath =
+    DiagnosticPath::const_new("server/replication/update_bytes");
+
+/// How many entities written into mutate messages.
+pub const MUTATE_ENTITIES: DiagnosticPath =
+    DiagnosticPath::const_new("server/replication/mutate_entities");
+/// How many components written into mutate messages.
+pub const MUTATE_COMPONENTS: DiagnosticPath =
+    DiagnosticPath::const_new("server/replication/mutate_components");
+/// How many mutate messages sent.
+pub const MUTATE_MESSAGES: DiagnosticPath =
+    DiagnosticPath::const_new("server/replication/mutate_messages");
+/// How many mutate bytes sent.
+pub const MUTATE_BYTES: DiagnosticPath =
+    DiagnosticPath::const_new("server/replication/mutate_bytes");
+
+/// How many mutate messages weren't acknowledged in time and will be resent.
+pub const RESENDS: DiagnosticPath = DiagnosticPath::const_new("server/replication/resends");
+/// How many mutate messages are waiting for acknowledgment across all clients.
+pub const PENDING_ACKS: DiagnosticPath =
+    DiagnosticPath::const_new("server/replication/pending_acks");
+
+/// Max diagnostic history length.
+pub const DIAGNOSTIC_HISTORY_LEN: usize = 60;
+
+fn add_measurements(
+    mut diagnostics: Diagnostics,
+    mut last_stats: Local<ServerReplicationStats>,
+    stats: Res<ServerReplicationStats>,
+) {
+    diagnostics.add_measurement(&UPDATE_ENTITIES, || {
+        stats
+            .update_entities
+            .saturating_sub(last_stats.update_entities) as f64
+    });
+    diagnostics.add_measurement(&UPDATE_COMPONENTS, || {
+        stats
+            .update_components
+            .saturating_sub(last_stats.update_components) as f64
+    });
+    diagnostics.add_measurement(&UPDATE_MESSAGES, || {
+        stats
+            .update_messages
+            .saturating_sub(last_stats.update_messages) as f64
+    });
+    diagnostics.add_measurement(&UPDATE_BYTES, || {
+        stats.update_bytes.saturating_sub(last_stats.update_bytes) as f64
+    });
+    diagnostics.add_measurement(&MUTATE_ENTITIES, || {
+        stats
+            .mutate_entities
+            .saturating_sub(last_stats.mutate_entities) as f64
+    });
+    diagnostics.add_measurement(&MUTATE_COMPONENTS, || {
+        stats
+            .mutate_components
+            .saturating_sub(last_stats.mutate_components) as f64
+    });
+    diagnostics.add_measurement(&MUTATE_MESSAGES, || {
+        stats
+            .mutate_messages
+            .saturating_sub(last_stats.mutate_messages) as f64
+    });
+    diagnostics.add_measurement(&MUTATE_BYTES, || {
+        stats.mutate_bytes.saturating_sub(last_stats.mutate_bytes) as f64
+    });
+    diagnostics.add_measurement(&RESENDS, || {
+        stats.resends.saturating_sub(last_stats.resends) as f64
+    });
+    diagnostics.add_measurement(&PENDING_ACKS, || stats.pending_acks as f64);
+    *last_stats = *stats;
+}
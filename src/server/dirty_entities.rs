@@ -0,0 +1,103 @@
+use bevy::{
+    ecs::{entity::hash_set::EntityHashSet, system::SystemChangeTick},
+    prelude::*,
+};
+use log::debug;
+
+use super::server_world::ServerWorld;
+use crate::{prelude::*, shared::replication::rules::ReplicationRules};
+
+/// Controls how changes are collected for replication.
+///
+/// See [`ServerPlugin::change_collection`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeCollection {
+    /// Visit all replicated entities for each client on every server tick.
+    ///
+    /// Has no tracking overhead, but the cost scales with the number of replicated entities
+    /// multiplied by the number of clients.
+    #[default]
+    Scan,
+    /// Visit only dirty entities for each client.
+    ///
+    /// An entity is dirty if it had a replicated component inserted or removed, gained visibility
+    /// or has unacknowledged mutations. Insertions are recorded by observers.
+    ///
+    /// Bevy doesn't provide hooks for mutations, so this mode doesn't avoid a world scan entirely:
+    /// mutable components (except the ones replicated with [`ReplicationMode::Once`]) are still
+    /// checked for changes on every server tick. This makes the cost O(n) in the number of
+    /// replicated entities with such components. But the pass is done once instead of once
+    /// per client, so the per-client cost scales with the number of dirty entities.
+    ///
+    /// Prefer [`Self::Scan`] if most replicated entities have mutable components or if there
+    /// are only a few clients.
+    ///
+    /// All replicated entities are still visited once for each newly authorized client.
+    Dirty,
+}
+
+/// Entities with replicated components that were inserted or mutated since the last replication.
+///
+/// Present only with [`ChangeCollection::Dirty`].
+#[derive(Resource, Default, Deref, DerefMut)]
+pub(crate) struct DirtyEntities(EntityHashSet);
+
+impl DirtyEntities {
+    /// Records all entities with mutable components changed since the last run of the system.
+    ///
+    /// Unlike other sources of dirty entities, this walks all replicated entities
+    /// with mutable components because Bevy doesn't provide mutation hooks.
+    pub(super) fn collect_mutated(&mut self, world: &ServerWorld, change_tick: &SystemChangeTick) {
+        for (archetype, replicated_archetype) in world.iter_archetypes() {
+            for &(component_rule, storage) in &replicated_archetype.components {
+                if component_rule.mode == ReplicationMode::Once
+                    || !world.is_mutable(component_rule.id)
+                {
+                    continue;
+                }
+
+                for entity in archetype.entities() {
+                    // SAFETY: component and storage were obtained from this archetype.
+                    let (_, ticks) = unsafe {
+                        world.get_component_unchecked(
+                            entity,
+                            archetype.table_id(),
+                            storage,
+                            component_rule.id,
+                        )
+                    };
+
+                    if ticks.is_changed(change_tick.last_run(), change_tick.this_run()) {
+                        self.insert(entity.id());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Spawns an observer that marks entities as dirty on insertion of any replicated component or [`Replicated`].
+pub(super) fn observe_insertions(world: &mut World) {
+    let mut components: Vec<_> = world
+        .resource::<ReplicationRules>()
+        .iter()
+        .flat_map(|rule| rule.components.iter().map(|component| component.id))
+        .collect();
+    components.push(world.register_component::<Replicated>());
+    components.sort_unstable();
+    components.dedup();
+
+    debug!(
+        "observing insertions of {} components for dirty tracking",
+        components.len()
+    );
+    let mut observer = Observer::new(mark_inserted);
+    for component in components {
+        observer = observer.with_component(component);
+    }
+    world.spawn(observer);
+}
+
+fn mark_inserted(insert: On<Insert>, mut dirty_entities: ResMut<DirtyEntities>) {
+    dirty_entities.insert(insert.entity);
+}
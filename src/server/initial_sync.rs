@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+
+/// Controls how the world is sent to newly authorized clients.
+///
+/// See [`ServerPlugin::initial_sync`](super::ServerPlugin::initial_sync).
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitialSync {
+    /// Send all visible entities in a single update message.
+    #[default]
+    Full,
+    /// Send visible entities in size-bounded chunks over multiple ticks.
+    ///
+    /// On each tick, entities that are new for the client are written until the total size of their
+    /// components reaches `max_bytes`. At least one entity is written per tick, even if it exceeds the limit.
+    /// The remaining entities are deferred to the next ticks. Replication for already sent entities
+    /// continues as usual.
+    ///
+    /// Each chunk includes the progress, which is available on the client as
+    /// [`InitialSyncProgress`](crate::client::InitialSyncProgress). After the last chunk is applied,
+    /// the client triggers [`InitialSyncFinished`](crate::client::InitialSyncFinished).
+    Streamed {
+        /// Maximum size of entity data in bytes sent to a syncing client per tick.
+        max_bytes: usize,
+    },
+}
@@ -2,17 +2,15 @@ use bevy::{
     ecs::system::{FilteredResourcesMutParamBuilder, FilteredResourcesParamBuilder, ParamBuilder},
     prelude::*,
 };
+use log::debug;
 
 use super::server_tick::ServerTick;
 use crate::{
     prelude::*,
-    shared::{
-        message::{
-            ctx::{ServerReceiveCtx, ServerSendCtx},
-            registry::RemoteMessageRegistry,
-            server_message::message_buffer::MessageBuffer,
-        },
-        replication::client_ticks::ClientTicks,
+    shared::message::{
+        ctx::{ServerReceiveCtx, ServerSendCtx},
+        registry::RemoteMessageRegistry,
+        server_message::{message_buffer::MessageBuffer, send_queue::SendQueue},
     },
 };
 
@@ -57,6 +55,8 @@ impl Plugin for ServerMessagePlugin {
             ParamBuilder,
             ParamBuilder,
             ParamBuilder,
+            ParamBuilder,
+            ParamBuilder,
         )
             .build_state(app.world_mut())
             .build_system(receive);
@@ -106,6 +106,7 @@ impl Plugin for ServerMessagePlugin {
                     send_buffered
                         .run_if(in_state(ServerState::Running))
                         .run_if(resource_changed::<ServerTick>),
+                    flush_queue.run_if(in_state(ServerState::Running)),
                     send_locally_fn.run_if(in_state(ClientState::Disconnected)),
                 )
                     .chain()
@@ -117,11 +118,11 @@ impl Plugin for ServerMessagePlugin {
 
 fn send_or_buffer(
     to_messages: FilteredResources,
-    mut server_messages: ResMut<ServerMessages>,
+    mut send_queue: ResMut<SendQueue>,
     mut message_buffer: ResMut<MessageBuffer>,
     type_registry: Res<AppTypeRegistry>,
     message_registry: Res<RemoteMessageRegistry>,
-    clients: Query<Entity, With<ConnectedClient>>,
+    clients: Query<EntityRef, With<ConnectedClient>>,
 ) {
     message_buffer.start_tick();
     let mut ctx = ServerSendCtx {
@@ -138,7 +139,7 @@ fn send_or_buffer(
             message.send_or_buffer(
                 &mut ctx,
                 &to_messages,
-                &mut server_messages,
+                &mut send_queue,
                 &clients,
                 &mut message_buffer,
             );
@@ -147,23 +148,36 @@ fn send_or_buffer(
 }
 
 fn send_buffered(
-    mut messages: ResMut<ServerMessages>,
+    mut send_queue: ResMut<SendQueue>,
     mut message_buffer: ResMut<MessageBuffer>,
-    clients: Query<(Entity, Option<&ClientTicks>), With<ConnectedClient>>,
+    clients: Query<EntityRef, With<ConnectedClient>>,
 ) {
     message_buffer
-        .send_all(&mut messages, &clients)
+        .send_all(&mut send_queue, &clients)
         .expect("buffered server events should send");
 }
 
+fn flush_queue(
+    mut messages: ResMut<ServerMessages>,
+    mut send_queue: ResMut<SendQueue>,
+    channels: Res<RepliconChannels>,
+) {
+    send_queue.flush(&mut messages, &channels);
+}
+
 fn receive(
     mut from_messages: FilteredResourcesMut,
+    mut commands: Commands,
     mut server_messages: ResMut<ServerMessages>,
     type_registry: Res<AppTypeRegistry>,
     message_registry: Res<RemoteMessageRegistry>,
+    clients: Query<EntityRef<'static>, With<ConnectedClient>>,
 ) {
     let mut ctx = ServerReceiveCtx {
         type_registry: &type_registry,
+        client_id: ClientId::Server,
+        clients: &clients,
+        flagged: Default::default(),
     };
 
     for message in message_registry.iter_all_client() {
@@ -174,6 +188,11 @@ fn receive(
         // SAFETY: passed pointer was obtained using this message data.
         unsafe { message.receive(&mut ctx, from_messages.into_inner(), &mut server_messages) };
     }
+
+    for flagged in ctx.flagged.drain(..) {
+        debug!("`{}` flagged: {}", flagged.client, flagged.reason);
+        commands.trigger(flagged);
+    }
 }
 
 fn trigger(
@@ -1,11 +1,11 @@
 use core::any::TypeId;
 
 use bevy::{
-    ecs::{component::Immutable, relationship::Relationship},
+    ecs::{component::Immutable, entity::hash_set::EntityHashSet, relationship::Relationship},
     platform::collections::HashMap,
     prelude::*,
 };
-use log::{debug, trace};
+use log::{debug, trace, warn};
 use petgraph::{
     Direction,
     algo::TarjanScc,
@@ -51,6 +51,36 @@ pub trait SyncRelatedAppExt {
     fn sync_related_entities<C>(&mut self) -> &mut Self
     where
         C: Relationship + Component<Mutability = Immutable>;
+
+    /// Propagates visibility from [`ClientVisibility`] through relationship `C`.
+    ///
+    /// Each entity related by `C` inherits the visibility of its root for every client,
+    /// so it's enough to call [`ClientVisibility::set_visibility`] only for the root.
+    /// Visibility set for non-root entities is overridden. When an entity is detached and
+    /// becomes a root, its visibility is reset to the default of [`VisibilityPolicy`].
+    ///
+    /// Reuses the graph from [`Self::sync_related_entities`] and implicitly enables it for `C`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use bevy::state::app::StatesPlugin;
+    /// use bevy::prelude::*;
+    /// use bevy_replicon::prelude::*;
+    ///
+    /// # let mut app = App::new();
+    /// # app.add_plugins((StatesPlugin, RepliconPlugins));
+    /// app.propagate_visibility::<ChildOf>();
+    ///
+    /// // Hiding the parent for a client will also hide the child.
+    /// app.world_mut().spawn((
+    ///     Replicated,
+    ///     Transform::default(),
+    ///     children![(Replicated, Transform::default())],
+    /// ));
+    /// ```
+    fn propagate_visibility<C>(&mut self) -> &mut Self
+    where
+        C: Relationship + Component<Mutability = Immutable>;
 }
 
 impl SyncRelatedAppExt for App {
@@ -58,6 +88,14 @@ impl SyncRelatedAppExt for App {
     where
         C: Relationship + Component<Mutability = Immutable>,
     {
+        let type_id = TypeId::of::<C>();
+        let mut related_entities = self.world_mut().get_resource_or_init::<RelatedEntities>();
+        if related_entities.sync_types.contains(&type_id) {
+            return self;
+        }
+        related_entities.sync_types.push(type_id);
+
+        debug!("syncing entities related by `{}`", ShortName::of::<C>());
         self.add_systems(
             OnEnter(ServerState::Running),
             read_relations::<C>.in_set(ServerSystems::ReadRelations),
@@ -67,6 +105,19 @@ impl SyncRelatedAppExt for App {
         .add_observer(start_replication::<C>)
         .add_observer(stop_replication::<C>)
     }
+
+    fn propagate_visibility<C>(&mut self) -> &mut Self
+    where
+        C: Relationship + Component<Mutability = Immutable>,
+    {
+        debug!("propagating visibility via `{}`", ShortName::of::<C>());
+        self.world_mut()
+            .get_resource_or_init::<RelatedEntities>()
+            .visibility_types
+            .push(TypeId::of::<C>());
+
+        self.sync_related_entities::<C>()
+    }
 }
 
 /// Disjoined graphs of related entities.
@@ -97,6 +148,18 @@ pub(super) struct RelatedEntities {
     /// Maps each entity to its disconnected graph's index.
     entity_graphs: HashMap<Entity, usize>,
     graphs_count: usize,
+
+    /// Relationship types registered via [`SyncRelatedAppExt::sync_related_entities`].
+    sync_types: Vec<TypeId>,
+
+    /// Relationship types registered via [`SyncRelatedAppExt::propagate_visibility`].
+    visibility_types: Vec<TypeId>,
+
+    /// Sources connected via [`Self::visibility_types`] since the last [`propagate_visibility`].
+    reparented: Vec<Entity>,
+
+    /// Sources disconnected via [`Self::visibility_types`] since the last [`propagate_visibility`].
+    detached: Vec<Entity>,
 }
 
 impl RelatedEntities {
@@ -111,6 +174,10 @@ impl RelatedEntities {
 
         self.graph.add_edge(source_node, target_node, type_id);
         self.rebuild_needed = true;
+
+        if self.visibility_types.contains(&type_id) {
+            self.reparented.push(source);
+        }
     }
 
     fn remove_relation<C: Relationship>(&mut self, source: Entity, target: Entity) {
@@ -148,6 +215,10 @@ impl RelatedEntities {
         }
 
         self.rebuild_needed = true;
+
+        if self.visibility_types.contains(&type_id) {
+            self.detached.push(source);
+        }
     }
 
     fn register_entity(&mut self, entity: Entity) -> NodeIndex {
@@ -223,6 +294,50 @@ impl RelatedEntities {
         self.graphs_count
     }
 
+    /// Returns the root of an entity by following relationships from [`Self::visibility_types`].
+    ///
+    /// If relationships form a cycle, returns the last entity before the cycle repeats.
+    fn visibility_root(&self, mut entity: Entity, visited: &mut EntityHashSet) -> Entity {
+        visited.clear();
+        visited.insert(entity);
+        while let Some(&node) = self.entity_to_node.get(&entity)
+            && let Some(target) = self.graph.edges(node).find_map(|edge| {
+                let (source, target) = self.graph.edge_endpoints(edge.id())?;
+                (source == node && self.visibility_types.contains(edge.weight()))
+                    .then(|| self.node_to_entity[&target])
+            })
+        {
+            if !visited.insert(target) {
+                warn!("visibility relationships of `{entity}` form a cycle");
+                break;
+            }
+            entity = target;
+        }
+
+        entity
+    }
+
+    /// Collects the entity and all its sources via [`Self::visibility_types`],
+    /// including transitively related ones.
+    fn visibility_descendants(&self, entity: Entity, descendants: &mut Vec<Entity>) {
+        let mut index = descendants.len();
+        descendants.push(entity);
+        while let Some(&entity) = descendants.get(index) {
+            index += 1;
+            let Some(&node) = self.entity_to_node.get(&entity) else {
+                continue;
+            };
+            for edge in self.graph.edges(node) {
+                if let Some((source, target)) = self.graph.edge_endpoints(edge.id())
+                    && target == node
+                    && self.visibility_types.contains(edge.weight())
+                {
+                    descendants.push(self.node_to_entity[&source]);
+                }
+            }
+        }
+    }
+
     pub(super) fn clear(&mut self) {
         self.graph.clear();
         self.entity_to_node.clear();
@@ -230,9 +345,56 @@ impl RelatedEntities {
         self.rebuild_needed = false;
         self.entity_graphs.clear();
         self.graphs_count = 0;
+        self.reparented.clear();
+        self.detached.clear();
     }
 }
 
+/// Updates [`ClientVisibility`] of entities related via [`RelatedEntities::visibility_types`]
+/// to match their roots.
+///
+/// Processes only entities whose visibility or relationships changed since the last run.
+pub(super) fn propagate_visibility(
+    mut changed: Local<Vec<Entity>>,
+    mut descendants: Local<Vec<Entity>>,
+    mut visited: Local<EntityHashSet>,
+    mut related_entities: ResMut<RelatedEntities>,
+    mut clients: Query<&mut ClientVisibility>,
+) {
+    if related_entities.visibility_types.is_empty() {
+        return;
+    }
+
+    for mut visibility in &mut clients {
+        for &entity in &related_entities.detached {
+            if related_entities.visibility_root(entity, &mut visited) == entity {
+                trace!("resetting visibility of detached `{entity}`");
+                visibility.reset_visibility(entity);
+            }
+        }
+
+        changed.extend(
+            visibility
+                .changed()
+                .chain(related_entities.reparented.iter().copied())
+                .filter(|entity| related_entities.entity_to_node.contains_key(entity)),
+        );
+
+        for entity in changed.drain(..) {
+            let root = related_entities.visibility_root(entity, &mut visited);
+            let visible = visibility.is_visible(root);
+            related_entities.visibility_descendants(entity, &mut descendants);
+            for entity in descendants.drain(..) {
+                trace!("propagating visibility `{visible}` from `{root}` to `{entity}`");
+                visibility.set_visibility(entity, visible);
+            }
+        }
+    }
+
+    related_entities.reparented.clear();
+    related_entities.detached.clear();
+}
+
 /// Collects all existing relations.
 ///
 /// Used to gather previously spawned entities when the server starts,
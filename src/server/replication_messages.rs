@@ -1,4 +1,5 @@
 mod change_ranges;
 pub(super) mod mutations;
+pub(super) mod range_cache;
 pub(super) mod serialized_data;
 pub(super) mod updates;
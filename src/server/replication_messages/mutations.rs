@@ -152,9 +152,11 @@ impl Mutations {
     ///
     /// Sent over the [`ServerChannel::Mutations`] channel. If the message gets lost, we try to resend it manually,
     /// using the last up-to-date mutations to avoid re-sending old values.
+    ///
+    /// Returns the number of sent messages and their total size.
     pub(crate) fn send(
         &mut self,
-        messages: &mut ServerMessages,
+        messages: &mut Vec<(Entity, ServerChannel, Vec<u8>)>,
         client: Entity,
         ticks: &mut ClientTicks,
         entity_buffer: &mut EntityBuffer,
@@ -165,7 +167,7 @@ impl Mutations {
         system_tick: Tick,
         timestamp: Duration,
         max_size: usize,
-    ) -> Result<usize> {
+    ) -> Result<(usize, usize)> {
         const MAX_COUNT_SIZE: usize = usize::POSTCARD_MAX_SIZE;
         let mut tick_buffer = [0; RepliconTick::POSTCARD_MAX_SIZE];
         let update_tick = postcard::to_slice(&ticks.update_tick(), &mut tick_buffer)?;
@@ -223,6 +225,7 @@ impl Mutations {
             );
         }
 
+        let mut total_size = 0;
         for &(mutate_index, mut message_size, ref chunks_range) in &self.messages {
             if track_mutate_messages {
                 // Update message counter size based on actual value.
@@ -246,10 +249,26 @@ impl Mutations {
 
             debug_assert_eq!(message.len(), message_size);
 
-            messages.send(client, ServerChannel::Mutations, message);
+            messages.push((client, ServerChannel::Mutations, message));
+            total_size += message_size;
         }
 
-        Ok(self.messages.len())
+        Ok((self.messages.len(), total_size))
+    }
+
+    /// Returns the number of entities with mutated components.
+    pub(crate) fn entities_len(&self) -> usize {
+        self.related.iter().map(Vec::len).sum::<usize>() + self.standalone.len()
+    }
+
+    /// Returns the number of mutated components across all entities.
+    pub(crate) fn components_len(&self) -> usize {
+        self.related
+            .iter()
+            .flatten()
+            .chain(&self.standalone)
+            .map(|mutations| mutations.ranges.components_len)
+            .sum()
     }
 
     /// Clears all chunks.
@@ -446,7 +465,7 @@ mod tests {
         track_mutate_messages: bool,
     ) -> usize {
         let mut serialized = SerializedData::default();
-        let mut messages = ServerMessages::default();
+        let mut messages = Vec::new();
         let mut mutations = Mutations::default();
 
         mutations.resize_related(related.len());
@@ -476,6 +495,7 @@ mod tests {
                 MAX_SIZE,
             )
             .unwrap()
+            .0
     }
 
     /// Mocks writing an entity with a single mutated component of specified size.
@@ -489,12 +509,11 @@ mod tests {
         mutations_size: usize,
     ) {
         assert!(mutations_size > 4);
-        let start = serialized.len();
-        serialized.resize(start + mutations_size, 0);
+        let entity_range = serialized.write_zeros(4);
+        let component_range = serialized.write_zeros(mutations_size - 4);
 
-        let entity_size = start + 4;
         mutations.start_entity();
-        mutations.add_entity(Entity::PLACEHOLDER, graph_index, start..entity_size);
-        mutations.add_component(entity_size..serialized.len());
+        mutations.add_entity(Entity::PLACEHOLDER, graph_index, entity_range);
+        mutations.add_component(component_range);
     }
 }
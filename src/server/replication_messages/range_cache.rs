@@ -0,0 +1,123 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use bevy::{platform::sync::OnceLock, prelude::*};
+
+use super::serialized_data::SerializedData;
+
+/// Entities to visit during change collection with their ranges from [`SerializedData`]
+/// that are shared between clients.
+///
+/// Clients are processed in parallel, so the first client that needs an entity or a component
+/// serializes it into the segment of its task, and all other clients re-use the written range.
+///
+/// Slots are allocated for every entity and every replicated component of it.
+/// They are reset instead of being dropped on [`Self::clear`], so they are reused across ticks.
+#[derive(Default)]
+pub(crate) struct RangeCache {
+    entities: Vec<EntitySlot>,
+    entities_len: usize,
+    components: Vec<OnceLock<Range<usize>>>,
+    components_len: usize,
+}
+
+impl RangeCache {
+    /// Marks all slots as unused.
+    ///
+    /// Slots will be reset on the next [`Self::push`].
+    pub(crate) fn clear(&mut self) {
+        self.entities_len = 0;
+        self.components_len = 0;
+    }
+
+    /// Allocates slots for an entity from a replicated archetype.
+    pub(crate) fn push(&mut self, archetype_index: usize, row: usize, components_len: usize) {
+        let components_offset = self.components_len;
+        if let Some(slot) = self.entities.get_mut(self.entities_len) {
+            slot.archetype_index = archetype_index;
+            slot.row = row;
+            slot.range.take();
+            slot.components_offset = components_offset;
+        } else {
+            self.entities.push(EntitySlot {
+                archetype_index,
+                row,
+                range: OnceLock::new(),
+                components_offset,
+            });
+        }
+        self.entities_len += 1;
+
+        self.components_len += components_len;
+        let reused = self.components.len().min(self.components_len);
+        for slot in &mut self.components[components_offset.min(reused)..reused] {
+            slot.take();
+        }
+        if self.components.len() < self.components_len {
+            self.components
+                .resize_with(self.components_len, OnceLock::new);
+        }
+    }
+
+    /// Iterates over allocated entity slots.
+    ///
+    /// Returns the slot index, the index of the replicated archetype and the row of the entity in it.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.entities[..self.entities_len]
+            .iter()
+            .enumerate()
+            .map(|(index, slot)| (index, slot.archetype_index, slot.row))
+    }
+
+    /// Returns an entity range or writes it using `write` if no client wrote it yet.
+    pub(crate) fn entity(
+        &self,
+        serialized: &mut SerializedData,
+        entity_slot: usize,
+        write: impl FnOnce(&mut SerializedData) -> Result<Range<usize>>,
+    ) -> Result<Range<usize>> {
+        get_or_write(&self.entities[entity_slot].range, serialized, write)
+    }
+
+    /// Returns a component range or writes it using `write` if no client wrote it yet.
+    pub(crate) fn component(
+        &self,
+        serialized: &mut SerializedData,
+        entity_slot: usize,
+        component_index: usize,
+        write: impl FnOnce(&mut SerializedData) -> Result<Range<usize>>,
+    ) -> Result<Range<usize>> {
+        let offset = self.entities[entity_slot].components_offset;
+        let slot = &self.components[offset + component_index];
+        get_or_write(slot, serialized, write)
+    }
+}
+
+/// Returns the range from the slot or writes it into the task's segment.
+///
+/// If multiple tasks race for the same slot, only one of them writes it.
+fn get_or_write(
+    slot: &OnceLock<Range<usize>>,
+    serialized: &mut SerializedData,
+    write: impl FnOnce(&mut SerializedData) -> Result<Range<usize>>,
+) -> Result<Range<usize>> {
+    let mut error = None;
+    let range = slot.get_or_init(|| {
+        write(serialized).unwrap_or_else(|e| {
+            error = Some(e);
+            Default::default()
+        })
+    });
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(range.clone()),
+    }
+}
+
+struct EntitySlot {
+    archetype_index: usize,
+    row: usize,
+    range: OnceLock<Range<usize>>,
+    components_offset: usize,
+}
@@ -1,4 +1,4 @@
-use core::ops::Range;
+use core::ops::{Index, Range};
 
 use bevy::{prelude::*, ptr::Ptr};
 
@@ -10,21 +10,98 @@ use crate::{
     },
 };
 
+/// Number of bits for offsets inside a segment.
+///
+/// Higher bits of ranges store the segment number.
+#[cfg(not(test))]
+const SEGMENT_SHIFT: u32 = usize::BITS * 3 / 4;
+
+/// Reduced to test overflows without allocating the full addressable range.
+#[cfg(test)]
+const SEGMENT_SHIFT: u32 = 16;
+
 /// Single continuous buffer that stores serialized data for messages.
 ///
+/// Clients are processed in parallel and each task writes into its own segment
+/// obtained from [`Self::reset_segment`] without any locking. Segments are merged
+/// into the main buffer using [`Self::append`] and all ranges written into them stay valid.
+///
 /// See [`Updates`](super::updates::Updates) and
 /// [`MutateMessage`](super::mutations::MutateMessage).
-#[derive(Default, Deref, DerefMut)]
-pub(crate) struct SerializedData(Vec<u8>);
+#[derive(Default)]
+pub(crate) struct SerializedData {
+    /// Offset of all ranges written into this buffer.
+    ///
+    /// Zero for the main buffer, segments encode their number in the higher bits.
+    base: usize,
+
+    data: Vec<u8>,
+
+    /// Positions of appended segments in [`Self::data`].
+    ///
+    /// Index is the segment number minus one.
+    segments: Vec<usize>,
+}
 
 impl SerializedData {
+    /// Clears the buffer and turns it into a segment with the given index.
+    ///
+    /// Keeps allocated memory for reuse.
+    pub(crate) fn reset_segment(&mut self, index: usize) {
+        self.base = (index + 1) << SEGMENT_SHIFT;
+        self.data.clear();
+    }
+
+    /// Moves all data from a segment to the end of this buffer.
+    ///
+    /// The segment will be empty, but keep its allocated memory.
+    ///
+    /// Returns an error if the buffer would exceed the addressable range,
+    /// since ranges of the main buffer are addressed as the segment zero.
+    pub(crate) fn append(&mut self, segment: &mut Self) -> Result<()> {
+        if self.data.len() + segment.data.len() >= 1 << SEGMENT_SHIFT {
+            return Err("serialized data exceeds the addressable range".into());
+        }
+
+        let number = segment.base >> SEGMENT_SHIFT;
+        if self.segments.len() < number {
+            self.segments.resize(number, 0);
+        }
+        self.segments[number - 1] = self.data.len();
+        self.data.append(&mut segment.data);
+
+        Ok(())
+    }
+
+    /// Clears the buffer.
+    ///
+    /// Keeps allocated memory for reuse.
+    pub(crate) fn clear(&mut self) {
+        self.data.clear();
+        self.segments.clear();
+    }
+
+    /// Returns the end of the written data.
+    pub(crate) fn len(&self) -> usize {
+        self.base + self.data.len()
+    }
+
+    /// Like [`Self::len`], but returns an error if the data exceeds the addressable range of a segment.
+    fn end(&self) -> Result<usize> {
+        if self.data.len() >= 1 << SEGMENT_SHIFT {
+            return Err("serialized data exceeds the addressable range".into());
+        }
+
+        Ok(self.len())
+    }
+
     pub(crate) fn write_mapping(&mut self, entity: Entity, hash: u64) -> Result<Range<usize>> {
         let start = self.len();
 
         self.write_entity(entity)?;
-        self.extend(hash.to_le_bytes()); // Use fixint encoding because it's more efficient for hashes.
+        self.data.extend(hash.to_le_bytes()); // Use fixint encoding because it's more efficient for hashes.
 
-        let end = self.len();
+        let end = self.end()?;
 
         Ok(start..end)
     }
@@ -36,10 +113,10 @@ impl SerializedData {
         let start = self.len();
 
         for fns_id in fn_ids {
-            postcard_utils::to_extend_mut(&fns_id, &mut self.0)?;
+            postcard_utils::to_extend_mut(&fns_id, &mut self.data)?;
         }
 
-        let end = self.len();
+        let end = self.end()?;
 
         Ok(start..end)
     }
@@ -54,11 +131,11 @@ impl SerializedData {
     ) -> Result<Range<usize>> {
         let start = self.len();
 
-        postcard_utils::to_extend_mut(&fns_id, &mut self.0)?;
+        postcard_utils::to_extend_mut(&fns_id, &mut self.data)?;
         // SAFETY: `component_fns`, `ptr` and `rule_fns` were created for the same component type.
-        unsafe { component_fns.serialize(ctx, rule_fns, ptr, &mut self.0)? };
+        unsafe { component_fns.serialize(ctx, rule_fns, ptr, &mut self.data)? };
 
-        let end = self.len();
+        let end = self.end()?;
 
         Ok(start..end)
     }
@@ -66,9 +143,9 @@ impl SerializedData {
     pub(crate) fn write_entity(&mut self, entity: Entity) -> Result<Range<usize>> {
         let start = self.len();
 
-        postcard_utils::entity_to_extend_mut(&entity, &mut self.0)?;
+        postcard_utils::entity_to_extend_mut(&entity, &mut self.data)?;
 
-        let end = self.len();
+        let end = self.end()?;
 
         Ok(start..end)
     }
@@ -76,10 +153,75 @@ impl SerializedData {
     pub(crate) fn write_tick(&mut self, tick: RepliconTick) -> Result<Range<usize>> {
         let start = self.len();
 
-        postcard_utils::to_extend_mut(&tick, &mut self.0)?;
+        postcard_utils::to_extend_mut(&tick, &mut self.data)?;
 
-        let end = self.len();
+        let end = self.end()?;
 
         Ok(start..end)
     }
+
+    /// Writes zeros of the given length.
+    #[cfg(test)]
+    pub(crate) fn write_zeros(&mut self, len: usize) -> Range<usize> {
+        let start = self.len();
+        self.data.resize(self.data.len() + len, 0);
+        start..self.len()
+    }
+}
+
+impl Index<Range<usize>> for SerializedData {
+    type Output = [u8];
+
+    fn index(&self, range: Range<usize>) -> &Self::Output {
+        let number = range.start >> SEGMENT_SHIFT;
+        let start = if number == 0 {
+            range.start
+        } else {
+            self.segments[number - 1] + (range.start - (number << SEGMENT_SHIFT))
+        };
+
+        &self.data[start..start + range.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segments() {
+        let mut serialized = SerializedData::default();
+        let mut segments = [SerializedData::default(), SerializedData::default()];
+        for (index, segment) in segments.iter_mut().enumerate() {
+            segment.reset_segment(index);
+        }
+
+        let main_range = serialized.write_tick(RepliconTick::new(1)).unwrap();
+        let second_range = segments[1].write_tick(RepliconTick::new(3)).unwrap();
+        let first_range = segments[0].write_tick(RepliconTick::new(2)).unwrap();
+
+        for segment in &mut segments {
+            serialized.append(segment).unwrap();
+        }
+
+        assert_eq!(serialized[main_range], [1]);
+        assert_eq!(serialized[first_range], [2]);
+        assert_eq!(serialized[second_range], [3]);
+    }
+
+    #[test]
+    fn overflow() {
+        let mut serialized = SerializedData::default();
+        let mut segment = SerializedData::default();
+        segment.reset_segment(0);
+
+        serialized.write_zeros((1 << SEGMENT_SHIFT) - 2);
+        segment.write_tick(RepliconTick::new(1)).unwrap();
+        serialized.append(&mut segment).unwrap();
+
+        segment.reset_segment(0);
+        segment.write_tick(RepliconTick::new(2)).unwrap();
+        assert!(serialized.append(&mut segment).is_err());
+        assert!(serialized.write_tick(RepliconTick::new(3)).is_err());
+    }
 }
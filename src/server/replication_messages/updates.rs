@@ -6,7 +6,6 @@ use postcard::experimental::serialized_size;
 use super::{change_ranges::ChangeRanges, mutations::Mutations, serialized_data::SerializedData};
 use crate::{
     postcard_utils,
-    prelude::*,
     shared::{
         backend::channels::ServerChannel, replication::update_message_flags::UpdateMessageFlags,
     },
@@ -43,6 +42,17 @@ pub(crate) struct Updates {
     /// May not be equal to the length of [`Self::despawns`] since adjacent ranges are merged together.
     despawns_len: usize,
 
+    /// Entities that are no longer visible to the client in this tick.
+    ///
+    /// Serialized the same way as [`Self::despawns`], but the client handles them
+    /// using [`ReplicationRegistry::visibility_lost`](crate::shared::replication::registry::ReplicationRegistry::visibility_lost).
+    hidden: Vec<Range<usize>>,
+
+    /// Number of hidden entities.
+    ///
+    /// May not be equal to the length of [`Self::hidden`] since adjacent ranges are merged together.
+    hidden_len: usize,
+
     /// Component removals that happened in this tick.
     ///
     /// Serialized as a list of pairs of entity chunk and a list of
@@ -50,6 +60,12 @@ pub(crate) struct Updates {
     /// serialized as a single chunk.
     removals: Vec<RemovalRanges>,
 
+    /// Progress of the streamed initial sync as the number of sent entities and the total number of entities.
+    ///
+    /// Serialized as two numbers. Used only with
+    /// [`InitialSync::Streamed`](crate::server::initial_sync::InitialSync::Streamed).
+    sync: Option<(usize, usize)>,
+
     /// Component insertions or mutations that happened in this tick.
     ///
     /// Serialized as a list of pairs of entity chunk and multiple chunks with changed components.
@@ -93,6 +109,18 @@ impl Updates {
         self.despawns.push(entity);
     }
 
+    pub(crate) fn add_hidden(&mut self, entity: Range<usize>) {
+        self.hidden_len += 1;
+        if let Some(last) = self.hidden.last_mut() {
+            // Append to previous range if possible.
+            if last.end == entity.start {
+                last.end = entity.end;
+                return;
+            }
+        }
+        self.hidden.push(entity);
+    }
+
     pub(crate) fn add_removals(
         &mut self,
         entity: Range<usize>,
@@ -106,6 +134,10 @@ impl Updates {
         });
     }
 
+    pub(crate) fn set_sync(&mut self, synced: usize, total: usize) {
+        self.sync = Some((synced, total));
+    }
+
     /// Updates internal state to start writing changed components for an entity with the given visibility.
     ///
     /// Entities and their data are written lazily during the iteration.
@@ -165,14 +197,29 @@ impl Updates {
     pub(crate) fn is_empty(&self) -> bool {
         self.changes.is_empty()
             && self.despawns.is_empty()
+            && self.hidden.is_empty()
             && self.removals.is_empty()
             && self.mappings.is_empty()
+            && self.sync.is_none()
+    }
+
+    /// Returns the number of entities with changed components.
+    pub(crate) fn entities_len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Returns the number of changed components across all entities.
+    pub(crate) fn components_len(&self) -> usize {
+        self.changes
+            .iter()
+            .map(|changes| changes.components_len)
+            .sum()
     }
 
     /// Packs updates into a message.
     ///
-    /// Contains tick, mappings, insertions, removals, and despawns that
-    /// happened in this tick.
+    /// Contains tick, mappings, insertions, removals, despawns, visibility losses and initial sync progress
+    /// for this tick.
     ///
     /// Sent over [`ServerChannel::Updates`] channel.
     ///
@@ -186,13 +233,15 @@ impl Updates {
     ///
     /// Additionally, we don't serialize the size for the last array and
     /// on deserialization just consume all remaining bytes.
+    ///
+    /// Returns the size of the sent message.
     pub(crate) fn send(
         &self,
-        messages: &mut ServerMessages,
+        messages: &mut Vec<(Entity, ServerChannel, Vec<u8>)>,
         client: Entity,
         serialized: &SerializedData,
         server_tick_range: Range<usize>,
-    ) -> Result<()> {
+    ) -> Result<usize> {
         let flags = self.flags();
         let last_flag = flags.last();
 
@@ -212,6 +261,12 @@ impl Updates {
                     }
                     message_size += self.despawns.iter().map(Range::len).sum::<usize>();
                 }
+                UpdateMessageFlags::HIDDEN => {
+                    if flag != last_flag {
+                        message_size += serialized_size(&self.hidden_len)?;
+                    }
+                    message_size += self.hidden.iter().map(Range::len).sum::<usize>();
+                }
                 UpdateMessageFlags::REMOVALS => {
                     if flag != last_flag {
                         message_size += serialized_size(&self.removals.len())?;
@@ -222,6 +277,10 @@ impl Updates {
                         .map(RemovalRanges::size)
                         .sum::<Result<usize>>()?;
                 }
+                UpdateMessageFlags::SYNC => {
+                    let (synced, total) = self.sync.expect("sync should be set with the flag");
+                    message_size += serialized_size(&synced)? + serialized_size(&total)?;
+                }
                 UpdateMessageFlags::CHANGES => {
                     debug_assert_eq!(flag, last_flag);
                     message_size += self
@@ -255,6 +314,14 @@ impl Updates {
                         message.extend_from_slice(&serialized[range.clone()]);
                     }
                 }
+                UpdateMessageFlags::HIDDEN => {
+                    if flag != last_flag {
+                        postcard_utils::to_extend_mut(&self.hidden_len, &mut message)?;
+                    }
+                    for range in &self.hidden {
+                        message.extend_from_slice(&serialized[range.clone()]);
+                    }
+                }
                 UpdateMessageFlags::REMOVALS => {
                     if flag != last_flag {
                         postcard_utils::to_extend_mut(&self.removals.len(), &mut message)?;
@@ -265,6 +332,11 @@ impl Updates {
                         message.extend_from_slice(&serialized[removals.fn_ids.clone()]);
                     }
                 }
+                UpdateMessageFlags::SYNC => {
+                    let (synced, total) = self.sync.expect("sync should be set with the flag");
+                    postcard_utils::to_extend_mut(&synced, &mut message)?;
+                    postcard_utils::to_extend_mut(&total, &mut message)?;
+                }
                 UpdateMessageFlags::CHANGES => {
                     // Changes are always last, don't write len for it.
                     for changes in &self.changes {
@@ -281,9 +353,9 @@ impl Updates {
 
         debug_assert_eq!(message.len(), message_size);
 
-        messages.send(client, ServerChannel::Updates, message);
+        messages.push((client, ServerChannel::Updates, message));
 
-        Ok(())
+        Ok(message_size)
     }
 
     fn flags(&self) -> UpdateMessageFlags {
@@ -295,9 +367,15 @@ impl Updates {
         if !self.despawns.is_empty() {
             flags |= UpdateMessageFlags::DESPAWNS;
         }
+        if !self.hidden.is_empty() {
+            flags |= UpdateMessageFlags::HIDDEN;
+        }
         if !self.removals.is_empty() {
             flags |= UpdateMessageFlags::REMOVALS;
         }
+        if self.sync.is_some() {
+            flags |= UpdateMessageFlags::SYNC;
+        }
         if !self.changes.is_empty() {
             flags |= UpdateMessageFlags::CHANGES;
         }
@@ -313,7 +391,10 @@ impl Updates {
         self.mappings_len = 0;
         self.despawns.clear();
         self.despawns_len = 0;
+        self.hidden.clear();
+        self.hidden_len = 0;
         self.removals.clear();
+        self.sync = None;
         self.buffer
             .extend(self.changes.drain(..).map(|mut changes| {
                 changes.components.clear();
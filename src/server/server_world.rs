@@ -72,6 +72,62 @@ impl<'w> ServerWorld<'w, '_> {
         }
     }
 
+    /// Returns ticks of [`Replicated`] for an entity.
+    ///
+    /// # Safety
+    ///
+    /// The entity must belong to this archetype, and the archetype must be replicated.
+    pub(super) unsafe fn get_marker_ticks(
+        &self,
+        entity: &ArchetypeEntity,
+        archetype: &Archetype,
+    ) -> ComponentTicks {
+        let marker_id = self.state.marker_id;
+        unsafe {
+            let storage = archetype.get_storage_type(marker_id).unwrap_unchecked();
+            let (_, ticks) =
+                self.get_component_unchecked(entity, archetype.table_id(), storage, marker_id);
+            ticks
+        }
+    }
+
+    /// Returns the index of the replicated archetype and the row of a replicated entity in it.
+    ///
+    /// Returns [`None`] if the entity doesn't exist or isn't replicated.
+    pub(super) fn locate(&self, entity: Entity) -> Option<(usize, usize)> {
+        let location = self.world.entities().get(entity)?;
+        // Archetypes are registered in the order of their IDs.
+        let index = self
+            .state
+            .archetypes
+            .binary_search_by_key(&location.archetype_id, |archetype| archetype.id)
+            .ok()?;
+
+        Some((index, location.archetype_row.index()))
+    }
+
+    /// Returns a replicated archetype by its index from [`Self::iter_archetypes`].
+    pub(super) fn get_archetype(&self, index: usize) -> (&Archetype, &ReplicatedArchetype) {
+        let replicated_archetype = &self.state.archetypes[index];
+        // SAFETY: all IDs from replicated archetypes obtained from real archetypes.
+        let archetype = unsafe {
+            self.world
+                .archetypes()
+                .get(replicated_archetype.id)
+                .unwrap_unchecked()
+        };
+
+        (archetype, replicated_archetype)
+    }
+
+    /// Returns `true` if the component can be mutated without re-insertion.
+    pub(super) fn is_mutable(&self, component_id: ComponentId) -> bool {
+        self.world
+            .components()
+            .get_info(component_id)
+            .is_some_and(|info| info.mutable())
+    }
+
     /// Return iterator over replicated archetypes.
     pub(super) fn iter_archetypes(
         &self,
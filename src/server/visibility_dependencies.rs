@@ -0,0 +1,223 @@
+use core::mem;
+
+use alloc::vec::Vec;
+
+use bevy::{
+    ecs::{
+        entity::{Entities, EntityMapper, hash_map::EntityHashMap},
+        system::SystemChangeTick,
+    },
+    prelude::*,
+};
+use log::trace;
+
+use super::{
+    client_visibility::ClientVisibility, removal_buffer::RemovalBuffer, server_tick::ServerTick,
+    server_world::ServerWorld,
+};
+use crate::shared::{
+    replication::registry::{
+        ReplicationRegistry,
+        ctx::{SerializeCtx, WriteCtx},
+    },
+    server_entity_map::ServerEntityMap,
+};
+
+/// Replicated entities referenced by replicated components of other replicated entities.
+///
+/// Present only if [`ServerPlugin::visibility_dependencies`](super::ServerPlugin::visibility_dependencies) is enabled.
+#[derive(Resource, Default)]
+pub(super) struct EntityReferences {
+    /// Referenced entities for each entity, sorted and without duplicates.
+    references: EntityHashMap<Vec<Entity>>,
+
+    /// Entities whose references changed since the last [`resolve`].
+    changed: Vec<Entity>,
+
+    /// Forces [`collect`] to gather references from all entities, not only the changed ones.
+    collect_all: bool,
+}
+
+impl EntityReferences {
+    /// Clears all references.
+    ///
+    /// References will be gathered from all entities on the next [`collect`].
+    pub(super) fn clear(&mut self) {
+        self.references.clear();
+        self.changed.clear();
+        self.collect_all = true;
+    }
+}
+
+/// Gathers references from replicated components of changed entities.
+///
+/// Entities are gathered via [`Component::map_entities`] on copies of components
+/// made with their replication functions, so components are only read.
+///
+/// Only replicated archetypes are visited. Like [`ChangeCollection::Dirty`](super::dirty_entities::ChangeCollection::Dirty), replicated components
+/// of each entity in them are checked for changes since the last run, but only changed entities are copied.
+pub(super) fn collect(
+    mut entity_map: Local<ServerEntityMap>,
+    mut entity_references: Local<Vec<Entity>>,
+    mut references: ResMut<EntityReferences>,
+    registry: Res<ReplicationRegistry>,
+    type_registry: Res<AppTypeRegistry>,
+    removal_buffer: Res<RemovalBuffer>,
+    server_tick: Res<ServerTick>,
+    entities: &Entities,
+    world: ServerWorld,
+    change_tick: SystemChangeTick,
+) -> Result<()> {
+    let collect_all = mem::take(&mut references.collect_all);
+    for (archetype, replicated_archetype) in world.iter_archetypes() {
+        for archetype_entity in archetype.entities() {
+            let entity = archetype_entity.id();
+            // SAFETY: the entity belongs to this replicated archetype.
+            let marker_ticks = unsafe { world.get_marker_ticks(archetype_entity, archetype) };
+            let changed = collect_all
+                || removal_buffer.contains_key(&entity)
+                || marker_ticks.is_changed(change_tick.last_run(), change_tick.this_run())
+                || replicated_archetype
+                    .components
+                    .iter()
+                    .any(|&(component_rule, storage)| {
+                        // SAFETY: component and storage were obtained from this archetype.
+                        let (_, ticks) = unsafe {
+                            world.get_component_unchecked(
+                                archetype_entity,
+                                archetype.table_id(),
+                                storage,
+                                component_rule.id,
+                            )
+                        };
+                        ticks.is_changed(change_tick.last_run(), change_tick.this_run())
+                    });
+            if !changed {
+                continue;
+            }
+
+            let mut mapper = ReferenceMapper {
+                references: &mut entity_references,
+            };
+            for &(component_rule, storage) in &replicated_archetype.components {
+                let (component_id, component_fns, rule_fns) = registry.get(component_rule.fns_id);
+                // SAFETY: component and storage were obtained from this archetype.
+                let (component, _) = unsafe {
+                    world.get_component_unchecked(
+                        archetype_entity,
+                        archetype.table_id(),
+                        storage,
+                        component_id,
+                    )
+                };
+                let serialize_ctx = SerializeCtx {
+                    component_id,
+                    server_tick: **server_tick,
+                    type_registry: &type_registry,
+                };
+                let mut write_ctx = WriteCtx {
+                    entity_map: &mut entity_map,
+                    type_registry: &type_registry,
+                    component_id,
+                    message_tick: **server_tick,
+                    entities,
+                    ignore_mapping: true,
+                };
+                // SAFETY: `component` and `rule_fns` were obtained for the same component.
+                unsafe {
+                    component_fns.visit_entities(
+                        &serialize_ctx,
+                        &mut write_ctx,
+                        rule_fns,
+                        component,
+                        &mut mapper,
+                    )?
+                };
+            }
+
+            entity_references
+                .retain(|&reference| reference != entity && world.locate(reference).is_some());
+            entity_references.sort_unstable();
+            entity_references.dedup();
+
+            let old_references = references
+                .references
+                .get(&entity)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            if old_references != entity_references.as_slice() {
+                trace!("updating references of `{entity}` to {entity_references:?}");
+                if entity_references.is_empty() {
+                    references.references.remove(&entity);
+                } else {
+                    references
+                        .references
+                        .insert(entity, mem::take(&mut *entity_references));
+                }
+                references.changed.push(entity);
+            }
+            entity_references.clear();
+        }
+    }
+
+    // Remove entities that were despawned or stopped being replicated.
+    let EntityReferences {
+        references,
+        changed,
+        ..
+    } = &mut *references;
+    references.retain(|&entity, _| {
+        let retain = world.locate(entity).is_some();
+        if !retain {
+            trace!("removing references of `{entity}`");
+            changed.push(entity);
+        }
+        retain
+    });
+
+    Ok(())
+}
+
+/// Makes entities referenced by visible entities visible for each client.
+///
+/// Processes only entities whose references or visibility changed since the last run.
+pub(super) fn resolve(
+    mut changed: Local<Vec<Entity>>,
+    mut references: ResMut<EntityReferences>,
+    mut clients: Query<&mut ClientVisibility>,
+) {
+    for mut visibility in &mut clients {
+        if visibility.init_dependencies() {
+            changed.extend(references.references.keys());
+        } else {
+            changed.extend(&references.changed);
+        }
+        changed.extend(visibility.drain_set());
+
+        for entity in changed.drain(..) {
+            let entity_references = references
+                .references
+                .get(&entity)
+                .filter(|_| visibility.is_set_visible(entity))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            visibility.set_references(entity, entity_references);
+        }
+    }
+
+    references.changed.clear();
+}
+
+/// Records entities instead of mapping them.
+struct ReferenceMapper<'a> {
+    references: &'a mut Vec<Entity>,
+}
+
+impl EntityMapper for ReferenceMapper<'_> {
+    fn get_mapped(&mut self, source: Entity) -> Entity {
+        self.references.push(source);
+        source
+    }
+
+    fn set_mapped(&mut self, _source: Entity, _target: Entity) {}
+}
@@ -5,17 +5,21 @@ pub mod protocol;
 pub mod replication;
 pub mod replicon_tick;
 pub mod server_entity_map;
+pub mod token_auth;
 
 use bevy::prelude::*;
+use log::debug;
 
 use crate::prelude::*;
-use backend::connected_client::NetworkIdMap;
+use backend::{DisconnectNotice, connected_client::NetworkIdMap};
 use message::registry::RemoteMessageRegistry;
+use protocol::versioning::{ProtocolAccepted, ProtocolManifest, ProtocolVersioning};
 use replication::signature::SignatureMap;
 use replication::{
     command_markers::CommandMarkers, registry::ReplicationRegistry, rules::ReplicationRules,
     track_mutate_messages::TrackMutateMessages,
 };
+use token_auth::{AuthRejected, TokenHandshake};
 
 /// Initializes types, resources and events needed for both client and server.
 #[derive(Default)]
@@ -40,6 +44,7 @@ pub struct RepliconSharedPlugin {
         StatesPlugin,
         RepliconPlugins.set(RepliconSharedPlugin {
             auth_method: AuthMethod::Custom,
+            ..Default::default()
         }),
     ))
     .add_client_event::<ClientInfo>(Channel::Ordered)
@@ -78,7 +83,10 @@ pub struct RepliconSharedPlugin {
                 mode: SendMode::Direct(client_info.client_id),
                 message: ProtocolMismatch,
             });
-            disconnects.write(DisconnectRequest { client });
+            disconnects.write(DisconnectRequest {
+                client,
+                reason: DisconnectReason::ProtocolMismatch,
+            });
         }
 
         // Validate player name, run the necessary game logic...
@@ -96,11 +104,28 @@ pub struct RepliconSharedPlugin {
     ```
     **/
     pub auth_method: AuthMethod,
+
+    /// Enables versioned protocol mode with the given local protocol version.
+    ///
+    /// With [`AuthMethod::ProtocolCheck`], the client will send [`ProtocolManifest`] instead of
+    /// [`ProtocolHash`] and the server will negotiate the common subset of registrations.
+    /// Registrations can be configured via [`ProtocolVersioning`].
+    ///
+    /// By default it's [`None`], which requires an exact [`ProtocolHash`] match.
+    pub protocol_version: Option<u32>,
+
+    /// Sends [`DisconnectRequest::reason`] to clients before disconnecting them.
+    ///
+    /// The reason will be available in [`Disconnected::reason`] on the client.
+    /// Requires an additional server channel, so it's disabled by default.
+    /// Should be configured identically on both sides.
+    pub disconnect_reasons: bool,
 }
 
 impl Plugin for RepliconSharedPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Replicated>()
+            .register_type::<RepliconTick>()
             .register_type::<ConnectedClient>()
             .register_type::<NetworkIdMap>()
             .register_type::<ClientStats>()
@@ -118,10 +143,39 @@ impl Plugin for RepliconSharedPlugin {
             .insert_resource(self.auth_method)
             .add_message::<DisconnectRequest>();
 
+        if self.disconnect_reasons {
+            debug!("enabling disconnect reasons");
+            app.add_server_message::<DisconnectNotice>(Channel::Ordered)
+                .make_message_independent::<DisconnectNotice>()
+                .set_message_priority::<DisconnectNotice>(u32::MAX);
+        }
+
+        #[cfg(feature = "scene")]
+        app.register_type::<crate::scene::SignatureSnapshot>()
+            .register_type::<crate::scene::ServerEntitySnapshot>();
+
         if self.auth_method == AuthMethod::ProtocolCheck {
-            app.add_client_event::<ProtocolHash>(Channel::Ordered)
-                .add_server_event::<ProtocolMismatch>(Channel::Unreliable)
+            if let Some(version) = self.protocol_version {
+                debug!("using protocol version {version}");
+                app.insert_resource(ProtocolVersioning::new(version))
+                    .add_client_event::<ProtocolManifest>(Channel::Ordered)
+                    .add_server_event::<ProtocolAccepted>(Channel::Ordered)
+                    .make_event_independent::<ProtocolAccepted>();
+            } else {
+                app.add_client_event::<ProtocolHash>(Channel::Ordered);
+            }
+            app.add_server_event::<ProtocolMismatch>(Channel::Unreliable)
                 .make_event_independent::<ProtocolMismatch>();
+        } else if let AuthMethod::Token { .. } = self.auth_method {
+            assert!(
+                self.protocol_version.is_none(),
+                "versioned protocol is supported only with `AuthMethod::ProtocolCheck`"
+            );
+            app.add_client_event::<TokenHandshake>(Channel::Ordered)
+                .add_server_event::<ProtocolMismatch>(Channel::Unreliable)
+                .make_event_independent::<ProtocolMismatch>()
+                .add_server_event::<AuthRejected>(Channel::Ordered)
+                .make_event_independent::<AuthRejected>();
         }
     }
 
@@ -132,6 +186,21 @@ impl Plugin for RepliconSharedPlugin {
             .expect("protocol hasher should be initialized at the plugin build");
 
         app.world_mut().insert_resource(protocol_hasher.finish());
+
+        if let Some(versioning) = app.world_mut().remove_resource::<ProtocolVersioning>() {
+            let world = app.world_mut();
+            assert!(
+                !world.resource::<RepliconChannels>().has_shared(),
+                "shared channels aren't supported in the versioned protocol mode"
+            );
+            let track_mutate_messages = **world.resource::<TrackMutateMessages>();
+            let manifest = versioning.finish(
+                world.resource::<ReplicationRegistry>(),
+                world.resource::<RemoteMessageRegistry>(),
+                track_mutate_messages,
+            );
+            world.insert_resource(manifest);
+        }
     }
 }
 
@@ -145,6 +214,9 @@ pub enum AuthMethod {
     /// - If the hash differs from the server's, the client will be notified with
     ///   a [`ProtocolMismatch`] event and disconnected.
     /// - If the hash matches, the [`AuthorizedClient`] component will be inserted.
+    ///
+    /// If [`RepliconSharedPlugin::protocol_version`] is set, the client sends [`ProtocolManifest`] instead
+    /// and the server negotiates the common subset of registrations. See [`ProtocolVersioning`] for details.
     #[default]
     ProtocolCheck,
 
@@ -159,4 +231,21 @@ pub enum AuthMethod {
     ///
     /// The user is responsible for manually inserting [`AuthorizedClient`] on the server.
     Custom,
+
+    /// Like [`Self::ProtocolCheck`], but the client also sends a payload from
+    /// [`AuthToken`](token_auth::AuthToken) that the server verifies.
+    ///
+    /// - If the hash differs from the server's, the client will be notified with
+    ///   a [`ProtocolMismatch`] event and disconnected.
+    /// - If the hash matches, [`AuthRequested`](token_auth::AuthRequested) will be triggered
+    ///   for the client entity on the server. The user should verify the payload and report
+    ///   the result via [`ResolveAuth`](token_auth::ResolveAuth).
+    /// - If the client is rejected or not resolved within the timeout, it will be notified with
+    ///   an [`AuthRejected`] event that contains the reason and disconnected.
+    ///
+    /// [`RepliconSharedPlugin::protocol_version`] isn't supported with this method.
+    Token {
+        /// Maximum time between the connection and [`ResolveAuth`](token_auth::ResolveAuth).
+        timeout: core::time::Duration,
+    },
 }
@@ -8,6 +8,7 @@
 //! - Update the [`ServerMessages`](server_messages::ServerMessages) and [`ClientMessages`](client_messages::ClientMessages) resources.
 //! - Spawn and despawn entities with [`ConnectedClient`](connected_client::ConnectedClient) component.
 //! - React on [`DisconnectRequest`] message.
+//!   The [`DisconnectReason`] is delivered by Replicon itself if enabled, backends only need to send pending messages before disconnecting.
 //! - Optionally update statistic in [`ClientStats`] resource and components.
 //!
 //! This way, integrations can be provided as separate crates without requiring us or crate authors to maintain them under a feature.
@@ -27,7 +28,10 @@ pub mod client_messages;
 pub mod connected_client;
 pub mod server_messages;
 
+use alloc::string::String;
+
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 /// Connection state of the client.
 ///
@@ -71,9 +75,72 @@ pub enum ServerState {
 /// The disconnection should occur **after** all pending messages
 /// for this client have been sent. The actual delivery of these
 /// messages is not guaranteed.
-#[derive(Message, Clone, Copy, Debug)]
+///
+/// If [`RepliconSharedPlugin::disconnect_reasons`](crate::shared::RepliconSharedPlugin::disconnect_reasons)
+/// is enabled, the [`reason`](Self::reason) will be sent to the client right before the disconnection.
+/// It's sent as an independent message, so it will be delivered even to unauthorized clients.
+/// On the client, it will be available in the [`Disconnected`] event.
+///
+/// Should be written before [`ServerSystems::Send`](crate::server::ServerSystems::Send)
+/// for the reason to be sent in the same frame.
+#[derive(Message, Clone, Debug)]
 pub struct DisconnectRequest {
     pub client: Entity,
+    pub reason: DisconnectReason,
+}
+
+impl DisconnectRequest {
+    /// Creates a request with [`DisconnectReason::Unspecified`].
+    pub fn new(client: Entity) -> Self {
+        Self {
+            client,
+            reason: Default::default(),
+        }
+    }
+}
+
+/// Backend-agnostic reason of a disconnection initiated by the server.
+///
+/// See [`DisconnectRequest`] and [`Disconnected`].
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// No reason was specified.
+    #[default]
+    Unspecified,
+    /// The client was kicked.
+    Kicked,
+    /// The client was banned.
+    Banned,
+    /// The client's protocol doesn't match the server's.
+    ProtocolMismatch,
+    /// The client's authorization was rejected with the given reason.
+    AuthRejected(String),
+    /// The client exceeded a message limit.
+    LimitExceeded,
+    /// The server is shutting down.
+    ServerShutdown,
+    /// A user-defined reason.
+    Custom(String),
+}
+
+/// An event that indicates that the client disconnected.
+///
+/// Triggered on the client when [`ClientState`] exits [`ClientState::Connected`].
+#[derive(Event, Debug, Clone)]
+pub struct Disconnected {
+    /// Reason received from the server.
+    ///
+    /// [`None`] if the server didn't send it, for example, if the client disconnected
+    /// by itself, the connection was lost, or
+    /// [`RepliconSharedPlugin::disconnect_reasons`](crate::shared::RepliconSharedPlugin::disconnect_reasons)
+    /// is disabled.
+    pub reason: Option<DisconnectReason>,
+}
+
+/// A server message that delivers [`DisconnectRequest::reason`] to the client.
+#[derive(Message, Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct DisconnectNotice {
+    pub(crate) reason: DisconnectReason,
 }
 
 /// Statistic for the current client when used as a resource,
@@ -116,7 +183,7 @@ mod tests {
     fn client_to_server() {
         let channels = RepliconChannels::default();
         let mut client_messages = ClientMessages::default();
-        client_messages.setup_server_channels(channels.server_channels().len());
+        client_messages.setup_channels(&channels);
 
         const MESSAGES: &[&[u8]] = &[&[0], &[1]];
         for &message in MESSAGES {
@@ -124,7 +191,7 @@ mod tests {
         }
 
         let mut server_messages = ServerMessages::default();
-        server_messages.setup_client_channels(channels.client_channels().len());
+        server_messages.setup_channels(&channels);
 
         for (channel_id, message) in client_messages.drain_sent() {
             server_messages.insert_received(Entity::PLACEHOLDER, channel_id, message);
@@ -141,7 +208,7 @@ mod tests {
     fn server_to_client() {
         let channels = RepliconChannels::default();
         let mut server_messages = ServerMessages::default();
-        server_messages.setup_client_channels(channels.client_channels().len());
+        server_messages.setup_channels(&channels);
 
         const MESSAGES: &[&[u8]] = &[&[0], &[1]];
         for &message in MESSAGES {
@@ -149,7 +216,7 @@ mod tests {
         }
 
         let mut client_messages = ClientMessages::default();
-        client_messages.setup_server_channels(channels.server_channels().len());
+        client_messages.setup_channels(&channels);
 
         for (_, channel_id, message) in server_messages.drain_sent() {
             client_messages.insert_received(channel_id, message);
@@ -158,4 +225,43 @@ mod tests {
         let messages: Vec<_> = client_messages.receive(ServerChannel::Mutations).collect();
         assert_eq!(messages, MESSAGES);
     }
+
+    #[test]
+    fn shared_channels() {
+        const SHARED: SharedChannel = SharedChannel::new("shared", Channel::Ordered);
+
+        let mut channels = RepliconChannels::default();
+        let channel1 = channels.create_server_channel(SHARED);
+        let channel2 = channels.create_server_channel(Channel::Unreliable);
+        let channel3 = channels.create_server_channel(SHARED);
+        assert_eq!(
+            channels.server_channels(),
+            [
+                Channel::Ordered,
+                Channel::Unreliable,
+                Channel::Ordered,
+                Channel::Unreliable
+            ]
+        );
+
+        let mut server_messages = ServerMessages::default();
+        server_messages.setup_channels(&channels);
+
+        let messages = [(channel1, [0]), (channel2, [1]), (channel3, [2])];
+        for (channel_id, message) in messages {
+            server_messages.send(Entity::PLACEHOLDER, channel_id, message.to_vec());
+        }
+
+        let mut client_messages = ClientMessages::default();
+        client_messages.setup_channels(&channels);
+
+        for (_, channel_id, message) in server_messages.drain_sent() {
+            client_messages.insert_received(channel_id, message);
+        }
+
+        for (channel_id, message) in messages {
+            let received: Vec<_> = client_messages.receive(channel_id).collect();
+            assert_eq!(received, [message.as_slice()]);
+        }
+    }
 }
@@ -1,6 +1,11 @@
+use alloc::vec::Vec;
+
 use bevy::prelude::*;
+use bytes::Bytes;
 use log::debug;
 
+use crate::postcard_utils;
+
 /// A resource with all channels used by Replicon.
 ///
 /// Initialized in [`ClientPlugin::finish`](crate::client::ClientPlugin) and
@@ -20,7 +25,8 @@ use log::debug;
 ///
 /// Channel IDs are represented by [`usize`], but backends may limit the number of channels.
 /// See [`ServerChannel`] and [`ClientChannel`] for channels that are always reserved.
-/// Other channels are used for events, with one channel per event. For more details, see
+/// Other channels are used for events, with one channel per event by default. Messages registered
+/// with the same [`SharedChannel`] use a single channel instead. For more details, see
 /// [`RemoteMessageRegistry`](crate::shared::message::registry::RemoteMessageRegistry).
 ///
 /// The backend needs to provide an API for creating its own channels. This can be done
@@ -33,40 +39,64 @@ pub struct RepliconChannels {
 
     /// Same as [`Self::server`], but for client.
     client: Vec<Channel>,
+
+    /// Routes between message channels and [`Self::server`].
+    server_routes: ChannelRoutes,
+
+    /// Same as [`Self::server_routes`], but for client.
+    client_routes: ChannelRoutes,
 }
 
 /// Only stores the replication channel by default.
 impl Default for RepliconChannels {
     fn default() -> Self {
-        Self {
-            server: vec![
-                ServerChannel::Updates.into(),
-                ServerChannel::Mutations.into(),
-            ],
-            client: vec![ClientChannel::MutationAcks.into()],
-        }
+        let mut channels = Self {
+            server: Default::default(),
+            client: Default::default(),
+            server_routes: Default::default(),
+            client_routes: Default::default(),
+        };
+        channels.create_server_channel(Channel::from(ServerChannel::Updates));
+        channels.create_server_channel(Channel::from(ServerChannel::Mutations));
+        channels.create_client_channel(Channel::from(ClientChannel::MutationAcks));
+        channels
     }
 }
 
 impl RepliconChannels {
-    /// Creates a new server channel and returns its ID.
-    pub(crate) fn create_server_channel(&mut self, channel: Channel) -> usize {
-        let id = self.server.len();
+    /// Creates a new server message channel and returns its ID.
+    ///
+    /// For [`MessageChannel::Shared`] creates a backend channel only once per name.
+    pub(crate) fn create_server_channel(&mut self, channel: impl Into<MessageChannel>) -> usize {
+        let id = self.server_routes.create(&mut self.server, channel.into());
         debug!("creating a server channel with ID {id}");
-        self.server.push(channel);
-
         id
     }
 
-    /// Creates a new client channel and returns its ID.
-    pub(crate) fn create_client_channel(&mut self, channel: Channel) -> usize {
-        let id = self.client.len();
+    /// Creates a new client message channel and returns its ID.
+    ///
+    /// For [`MessageChannel::Shared`] creates a backend channel only once per name.
+    pub(crate) fn create_client_channel(&mut self, channel: impl Into<MessageChannel>) -> usize {
+        let id = self.client_routes.create(&mut self.client, channel.into());
         debug!("creating a client channel with ID {id}");
-        self.client.push(channel);
-
         id
     }
 
+    /// Returns routes for server channels.
+    pub(crate) fn server_routes(&self) -> &ChannelRoutes {
+        &self.server_routes
+    }
+
+    /// Returns routes for client channels.
+    pub(crate) fn client_routes(&self) -> &ChannelRoutes {
+        &self.client_routes
+    }
+
+    /// Returns `true` if any message is registered with a [`SharedChannel`].
+    pub(crate) fn has_shared(&self) -> bool {
+        !self.server_routes.shared.is_empty() || !self.client_routes.shared.is_empty()
+    }
+
     /// Returns the list of registered server channels, which are used for sending data from server to client.
     ///
     /// For example, if you register a client event, it won't be reflected here.
@@ -182,6 +212,209 @@ impl From<ClientChannel> for usize {
     }
 }
 
+/// Channel for a remote message or event registration.
+///
+/// Can be created from [`Channel`] for a dedicated channel or from [`SharedChannel`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MessageChannel {
+    /// A separate backend channel for the message.
+    Dedicated(Channel),
+    /// A backend channel shared between multiple messages.
+    Shared(SharedChannel),
+}
+
+impl From<Channel> for MessageChannel {
+    fn from(value: Channel) -> Self {
+        Self::Dedicated(value)
+    }
+}
+
+impl From<SharedChannel> for MessageChannel {
+    fn from(value: SharedChannel) -> Self {
+        Self::Shared(value)
+    }
+}
+
+/**
+A named backend channel that can be shared between multiple message types.
+
+By default, each message or event creates its own channel, but backends may limit the number of channels.
+Messages registered with a shared channel of the same name use a single channel and prefix each message
+with a compact ID of its type. The receiving side splits messages by this ID in arrival order,
+so the guarantee of [`Self::channel`] applies to each message type separately. For example,
+with [`Channel::Ordered`], messages of each type are received in the order they were sent. But since
+each type is still processed by its own system, messages of different types are processed in the order
+of their registration, not in the order they were sent.
+
+Shared channels are not supported in the versioned protocol mode.
+
+# Examples
+
+```
+# use bevy::state::app::StatesPlugin;
+use bevy::prelude::*;
+use bevy_replicon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const GAMEPLAY: SharedChannel = SharedChannel::new("gameplay", Channel::Ordered);
+
+# let mut app = App::new();
+# app.add_plugins((StatesPlugin, RepliconPlugins));
+app.add_client_message::<Attack>(GAMEPLAY)
+    .add_server_message::<Hit>(GAMEPLAY)
+    .add_server_event::<Death>(GAMEPLAY);
+
+#[derive(Message, Serialize, Deserialize)]
+struct Attack;
+
+#[derive(Message, Serialize, Deserialize)]
+struct Hit;
+
+#[derive(Event, Serialize, Deserialize)]
+struct Death;
+```
+**/
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SharedChannel {
+    name: &'static str,
+    channel: Channel,
+}
+
+impl SharedChannel {
+    /// Creates a new shared channel with the given name and delivery guarantee.
+    ///
+    /// All registrations with the same name should use the same guarantee.
+    pub const fn new(name: &'static str, channel: Channel) -> Self {
+        Self { name, channel }
+    }
+
+    /// Returns the name of the channel.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the delivery guarantee of the channel.
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+}
+
+/// Mapping between message channel IDs and backend channel IDs.
+///
+/// Message channels are the ones used by Replicon internally. Backends see only backend channels,
+/// which are the same unless [`SharedChannel`] is used.
+#[derive(Clone, Default)]
+pub(crate) struct ChannelRoutes {
+    /// Backend channel ID and message prefix for each message channel ID.
+    send: Vec<(usize, Option<usize>)>,
+
+    /// Message channel IDs for each backend channel ID.
+    ///
+    /// Shared channels contain IDs of all their messages, indexed by prefix.
+    receive: Vec<Vec<usize>>,
+
+    /// Shared channels with their backend channel IDs.
+    shared: Vec<(SharedChannel, usize)>,
+}
+
+impl ChannelRoutes {
+    fn create(&mut self, channels: &mut Vec<Channel>, channel: MessageChannel) -> usize {
+        let id = self.send.len();
+        match channel {
+            MessageChannel::Dedicated(channel) => {
+                self.send.push((channels.len(), None));
+                self.receive.push(vec![id]);
+                channels.push(channel);
+            }
+            MessageChannel::Shared(shared) => {
+                let backend_id = if let Some(&(existing, backend_id)) = self
+                    .shared
+                    .iter()
+                    .find(|(existing, _)| existing.name == shared.name)
+                {
+                    assert_eq!(
+                        existing.channel, shared.channel,
+                        "shared channel `{}` should be registered with the same delivery guarantee",
+                        shared.name
+                    );
+                    backend_id
+                } else {
+                    let backend_id = channels.len();
+                    debug!("creating shared channel `{}`", shared.name);
+                    self.shared.push((shared, backend_id));
+                    self.receive.push(Vec::new());
+                    channels.push(shared.channel);
+                    backend_id
+                };
+
+                let messages = &mut self.receive[backend_id];
+                self.send.push((backend_id, Some(messages.len())));
+                messages.push(id);
+            }
+        }
+
+        id
+    }
+
+    /// Returns the number of message channels.
+    pub(crate) fn len(&self) -> usize {
+        self.send.len()
+    }
+
+    /// Returns the backend channel ID for a message channel.
+    ///
+    /// Channels without a route are considered dedicated.
+    pub(crate) fn backend_id(&self, channel_id: usize) -> usize {
+        self.send
+            .get(channel_id)
+            .map_or(channel_id, |&(backend_id, _)| backend_id)
+    }
+
+    /// Returns the backend channel ID for a message channel and prefixes the message if the channel is shared.
+    ///
+    /// Channels without a route are considered dedicated.
+    pub(crate) fn route_sent(&self, channel_id: usize, message: Bytes) -> (usize, Bytes) {
+        match self.send.get(channel_id) {
+            Some(&(backend_id, Some(prefix))) => {
+                let mut prefixed = Vec::with_capacity(message.len() + 1);
+                postcard_utils::to_extend_mut(&prefix, &mut prefixed)
+                    .expect("prefix should be serializable");
+                prefixed.extend_from_slice(&message);
+                (backend_id, prefixed.into())
+            }
+            Some(&(backend_id, None)) => (backend_id, message),
+            None => (channel_id, message),
+        }
+    }
+
+    /// Returns the message channel ID for a backend channel and strips the prefix if the channel is shared.
+    ///
+    /// Returns [`None`] if the prefix is invalid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no backend channel with this ID.
+    pub(crate) fn route_received(
+        &self,
+        backend_id: usize,
+        mut message: Bytes,
+    ) -> Option<(usize, Bytes)> {
+        let messages = self
+            .receive
+            .get(backend_id)
+            .unwrap_or_else(|| panic!("there should be a receive channel with id {backend_id}"));
+
+        match *messages.as_slice() {
+            [channel_id] if self.send[channel_id].1.is_none() => Some((channel_id, message)),
+            _ => {
+                let prefix: usize = postcard_utils::from_buf(&mut message).ok()?;
+                let &channel_id = messages.get(prefix)?;
+                Some((channel_id, message))
+            }
+        }
+    }
+}
+
 /// Channel delivery guarantee.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Channel {
@@ -2,6 +2,8 @@ use bevy::prelude::*;
 use bytes::Bytes;
 use log::trace;
 
+use super::channels::{ChannelRoutes, RepliconChannels};
+
 /// Sent and received messages for exchange between Replicon and the messaging backend.
 ///
 /// The messaging backend is responsible for updating this resource:
@@ -21,12 +23,22 @@ pub struct ClientMessages {
 
     /// List of sent messages and their channels since the last tick.
     sent_messages: Vec<(usize, Bytes)>,
+
+    /// Routes for sending over client channels.
+    send_routes: ChannelRoutes,
+
+    /// Routes for receiving from server channels.
+    receive_routes: ChannelRoutes,
 }
 
 impl ClientMessages {
-    /// Changes the size of the receive messages storage according to the number of server channels.
-    pub(crate) fn setup_server_channels(&mut self, channels_count: usize) {
-        self.received_messages.resize(channels_count, Vec::new());
+    /// Changes the size of the receive messages storage according to the number of server channels
+    /// and stores routes for shared channels.
+    pub(crate) fn setup_channels(&mut self, channels: &RepliconChannels) {
+        self.send_routes = channels.client_routes().clone();
+        self.receive_routes = channels.server_routes().clone();
+        self.received_messages
+            .resize(self.receive_routes.len(), Vec::new());
     }
 
     /// Returns number of received messages for a channel.
@@ -77,8 +89,9 @@ impl ClientMessages {
     ///
     /// </div>
     pub fn send<I: Into<usize>, B: Into<Bytes>>(&mut self, channel_id: I, message: B) {
-        let channel_id = channel_id.into();
-        let message: Bytes = message.into();
+        let (channel_id, message) = self
+            .send_routes
+            .route_sent(channel_id.into(), message.into());
 
         trace!("sending {} bytes over channel {channel_id}", message.len());
 
@@ -112,11 +125,19 @@ impl ClientMessages {
     /// </div>
     pub fn insert_received<I: Into<usize>, B: Into<Bytes>>(&mut self, channel_id: I, message: B) {
         let channel_id = channel_id.into();
+        let Some((channel_id, message)) = self
+            .receive_routes
+            .route_received(channel_id, message.into())
+        else {
+            trace!("ignoring message with invalid prefix over channel {channel_id}");
+            return;
+        };
+
         let channel_messages = self
             .received_messages
             .get_mut(channel_id)
             .unwrap_or_else(|| panic!("client should have a channel with id {channel_id}"));
 
-        channel_messages.push(message.into());
+        channel_messages.push(message);
     }
 }
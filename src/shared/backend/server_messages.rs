@@ -1,7 +1,9 @@
-use bevy::prelude::*;
+use bevy::{ecs::entity::hash_map::EntityHashMap, prelude::*};
 use bytes::Bytes;
 use log::trace;
 
+use super::channels::{ChannelRoutes, RepliconChannels};
+
 /// Sent and received messages for exchange between Replicon and the messaging backend.
 ///
 /// The messaging backend is responsible for updating this resource:
@@ -21,12 +23,42 @@ pub struct ServerMessages {
 
     /// List of sent messages for each channel since the last tick.
     sent_messages: Vec<(Entity, usize, Bytes)>,
+
+    /// Channel mappings negotiated with clients in the versioned protocol mode.
+    ///
+    /// See [`ProtocolVersioning`](crate::shared::protocol::versioning::ProtocolVersioning).
+    channel_maps: EntityHashMap<ChannelMap>,
+
+    /// Client channel for the protocol negotiation in the versioned protocol mode.
+    ///
+    /// Messages over other channels from clients without a negotiated [`ChannelMap`] will be ignored.
+    negotiation_channel: Option<usize>,
+
+    /// Routes for sending over server channels.
+    send_routes: ChannelRoutes,
+
+    /// Routes for receiving from client channels.
+    receive_routes: ChannelRoutes,
 }
 
 impl ServerMessages {
-    /// Changes the size of the receive messages storage according to the number of client channels.
-    pub(crate) fn setup_client_channels(&mut self, channels_count: usize) {
-        self.received_messages.resize(channels_count, Vec::new());
+    /// Changes the size of the receive messages storage according to the number of client channels
+    /// and stores routes for shared channels.
+    pub(crate) fn setup_channels(&mut self, channels: &RepliconChannels) {
+        self.send_routes = channels.server_routes().clone();
+        self.receive_routes = channels.client_routes().clone();
+        self.received_messages
+            .resize(self.receive_routes.len(), Vec::new());
+    }
+
+    /// Enables the versioned protocol mode with the given negotiation channel.
+    pub(crate) fn set_negotiation_channel(&mut self, channel_id: usize) {
+        self.negotiation_channel = Some(channel_id);
+    }
+
+    /// Assigns a negotiated channel map for a client.
+    pub(crate) fn insert_channel_map(&mut self, client: Entity, channel_map: ChannelMap) {
+        self.channel_maps.insert(client, channel_map);
     }
 
     /// Removes a disconnected client.
@@ -35,6 +67,7 @@ impl ServerMessages {
             receive_channel.retain(|&(entity, _)| entity != client);
         }
         self.sent_messages.retain(|&(entity, ..)| entity != client);
+        self.channel_maps.remove(&client);
     }
 
     /// Receives all available messages from clients over a channel.
@@ -64,6 +97,19 @@ impl ServerMessages {
         channel_messages.drain(..)
     }
 
+    /// Retains only the received messages over a channel specified by the predicate.
+    pub(crate) fn retain_received<F>(&mut self, channel_id: usize, mut f: F)
+    where
+        F: FnMut(Entity, &Bytes) -> bool,
+    {
+        let channel_messages = self
+            .received_messages
+            .get_mut(channel_id)
+            .unwrap_or_else(|| panic!("server should have a receive channel with id {channel_id}"));
+
+        channel_messages.retain(|(client, message)| f(*client, message));
+    }
+
     /// Sends a message to a client over a channel.
     ///
     /// <div class="warning">
@@ -77,9 +123,21 @@ impl ServerMessages {
         channel_id: I,
         message: B,
     ) {
-        let channel_id = channel_id.into();
+        let mut channel_id = channel_id.into();
         let message: Bytes = message.into();
 
+        if let Some(channel_map) = self.channel_maps.get(&client) {
+            let Some(client_channel) = channel_map.send(channel_id) else {
+                trace!(
+                    "skipping message over channel {channel_id} unsupported by client `{client}`"
+                );
+                return;
+            };
+            channel_id = client_channel;
+        }
+
+        let (channel_id, message) = self.send_routes.route_sent(channel_id, message);
+
         trace!("sending {} bytes over channel {channel_id}", message.len());
 
         self.sent_messages.push((client, channel_id, message));
@@ -119,13 +177,38 @@ impl ServerMessages {
         channel_id: I,
         message: B,
     ) {
-        let channel_id = channel_id.into();
+        let mut channel_id = channel_id.into();
+        let mut message = message.into();
+        if let Some(channel_map) = self.channel_maps.get(&client) {
+            let Some(server_channel) = channel_map.receive(channel_id) else {
+                trace!("ignoring message over channel {channel_id} unsupported by server");
+                return;
+            };
+            channel_id = server_channel;
+        } else {
+            let Some(route) = self.receive_routes.route_received(channel_id, message) else {
+                trace!("ignoring message with invalid prefix over channel {channel_id}");
+                return;
+            };
+            (channel_id, message) = route;
+
+            if self
+                .negotiation_channel
+                .is_some_and(|negotiation_channel| negotiation_channel != channel_id)
+            {
+                trace!(
+                    "ignoring message over channel {channel_id} from not negotiated client `{client}`"
+                );
+                return;
+            }
+        }
+
         let receive_channel = self
             .received_messages
             .get_mut(channel_id)
             .unwrap_or_else(|| panic!("server should have a receive channel with id {channel_id}"));
 
-        receive_channel.push((client, message.into()));
+        receive_channel.push((client, message));
     }
 
     pub(crate) fn clear(&mut self) {
@@ -133,5 +216,31 @@ impl ServerMessages {
             receive_channel.clear();
         }
         self.sent_messages.clear();
+        self.channel_maps.clear();
+    }
+}
+
+/// Mapping between server and client channels negotiated in the versioned protocol mode.
+///
+/// See [`ProtocolVersioning`](crate::shared::protocol::versioning::ProtocolVersioning).
+pub(crate) struct ChannelMap {
+    /// Server channel ID for each client channel ID.
+    receive: Vec<Option<usize>>,
+
+    /// Client channel ID for each server channel ID.
+    send: Vec<Option<usize>>,
+}
+
+impl ChannelMap {
+    pub(crate) fn new(receive: Vec<Option<usize>>, send: Vec<Option<usize>>) -> Self {
+        Self { receive, send }
+    }
+
+    fn receive(&self, client_channel: usize) -> Option<usize> {
+        self.receive.get(client_channel).copied().flatten()
+    }
+
+    fn send(&self, server_channel: usize) -> Option<usize> {
+        self.send.get(server_channel).copied().flatten()
     }
 }
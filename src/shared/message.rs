@@ -1,7 +1,9 @@
 pub mod client_event;
 pub mod client_message;
 pub mod ctx;
+pub mod limits;
 pub mod message_fns;
 pub mod registry;
+pub mod request;
 pub mod server_event;
 pub mod server_message;
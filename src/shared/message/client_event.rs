@@ -1,16 +1,18 @@
-use core::any::TypeId;
+use core::any::{self, TypeId};
 
 use bevy::{ecs::entity::MapEntities, prelude::*, ptr::PtrMut};
+use bytes::Bytes;
 use log::debug;
 use serde::{Serialize, de::DeserializeOwned};
 
 use super::{
-    client_message::{self, ClientMessage},
+    client_message::{self, ClientMessage, FromClientTrigger},
     ctx::{ClientSendCtx, ServerReceiveCtx},
+    limits::{self, MessageLimit},
     message_fns::{DeserializeFn, MessageFns, SerializeFn},
     registry::RemoteMessageRegistry,
 };
-use crate::prelude::*;
+use crate::{postcard_utils, prelude::*, shared::backend::channels::MessageChannel};
 
 /// An extension trait for [`App`] for creating client events.
 ///
@@ -27,7 +29,7 @@ pub trait ClientEventAppExt {
     /// See also the [corresponding section](../index.html#from-client-to-server) from the quick start guide.
     fn add_client_event<E: Event + Serialize + DeserializeOwned>(
         &mut self,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
     ) -> &mut Self {
         self.add_client_event_with(
             channel,
@@ -42,7 +44,7 @@ pub trait ClientEventAppExt {
     /// For details, see [`Component::map_entities`].
     fn add_mapped_client_event<E: Event + Serialize + DeserializeOwned + MapEntities + Clone>(
         &mut self,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
     ) -> &mut Self {
         self.add_client_event_with(
             channel,
@@ -56,16 +58,57 @@ pub trait ClientEventAppExt {
     /// See also [`ClientMessageAppExt::add_client_message_with`].
     fn add_client_event_with<E: Event>(
         &mut self,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
         serialize: SerializeFn<ClientSendCtx, E>,
         deserialize: DeserializeFn<ServerReceiveCtx, E>,
     ) -> &mut Self;
+
+    /**
+    Same as [`Self::add_client_event`], but for [`EntityEvent`]s.
+
+    The event target is mapped from client to server entity before sending. On the server,
+    [`FromClient<E>`] is triggered with [`FromClientTrigger::target`](client_message::FromClientTrigger::target)
+    set to the mapped entity, so observers of the target entity will run too. Other entities inside
+    the event are not mapped. The target is sent as part of the serialized event, so it shouldn't be
+    skipped during serialization.
+
+    # Examples
+
+    ```
+    # use bevy::state::app::StatesPlugin;
+    use bevy::prelude::*;
+    use bevy_replicon::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    # let mut app = App::new();
+    # app.add_plugins((StatesPlugin, RepliconPlugins));
+    app.add_client_entity_event::<Interact>(Channel::Ordered);
+
+    # let mut commands = app.world_mut().commands();
+    # let door = commands.spawn_empty().id();
+    commands.entity(door).observe(|interact: On<FromClient<Interact>>| {
+        info!("`{}` opened door `{}`", interact.client_id, interact.entity);
+    });
+
+    #[derive(EntityEvent, Serialize, Deserialize)]
+    struct Interact {
+        entity: Entity,
+    }
+    ```
+    **/
+    fn add_client_entity_event<E: EntityEvent + Serialize + DeserializeOwned>(
+        &mut self,
+        channel: impl Into<MessageChannel>,
+    ) -> &mut Self;
+
+    /// Like [`ClientMessageAppExt::limit_client_message`], but for events.
+    fn limit_client_event<E: Event>(&mut self, limit: MessageLimit) -> &mut Self;
 }
 
 impl ClientEventAppExt for App {
     fn add_client_event_with<E: Event>(
         &mut self,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
         serialize: SerializeFn<ClientSendCtx, E>,
         deserialize: DeserializeFn<ServerReceiveCtx, E>,
     ) -> &mut Self {
@@ -74,17 +117,53 @@ impl ClientEventAppExt for App {
             .add_client_event::<E>();
 
         let fns = MessageFns::new(serialize, deserialize).with_convert::<ClientMessageEvent<E>>();
-        let event = ClientEvent::new(self, channel, fns);
+        let event = ClientEvent::new(self, channel, fns, ClientEvent::trigger_typed::<E>);
+        let mut registry = self.world_mut().resource_mut::<RemoteMessageRegistry>();
+        registry.register_client_event(event);
+
+        self
+    }
+
+    fn add_client_entity_event<E: EntityEvent + Serialize + DeserializeOwned>(
+        &mut self,
+        channel: impl Into<MessageChannel>,
+    ) -> &mut Self {
+        let mut hasher = self.world_mut().resource_mut::<ProtocolHasher>();
+        hasher.add_client_event::<E>();
+        hasher.add_entity_event::<E>();
+
+        let fns = MessageFns::new(serialize_entity_event::<E>, deserialize_entity_event::<E>)
+            .with_convert::<ClientMessageEvent<E>>();
+        let event = ClientEvent::new(self, channel, fns, ClientEvent::trigger_entity_typed::<E>);
         let mut registry = self.world_mut().resource_mut::<RemoteMessageRegistry>();
         registry.register_client_event(event);
 
         self
     }
+
+    fn limit_client_event<E: Event>(&mut self, limit: MessageLimit) -> &mut Self {
+        let registry = self.world().resource::<RemoteMessageRegistry>();
+        let event = registry
+            .iter_client_events()
+            .find(|e| e.type_id() == TypeId::of::<E>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "event `{}` should be previously registered as a client event",
+                    ShortName::of::<E>()
+                )
+            });
+
+        let channel_id = event.message().channel_id();
+        limits::limit_channel(self, channel_id, limit);
+
+        self
+    }
 }
 
 /// Small abstraction on top of [`ClientEvent`] that stores a function to trigger them.
 pub(crate) struct ClientEvent {
     type_id: TypeId,
+    type_name: &'static str,
     message: ClientMessage,
     trigger: TriggerFn,
 }
@@ -92,13 +171,15 @@ pub(crate) struct ClientEvent {
 impl ClientEvent {
     fn new<E: Event>(
         app: &mut App,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
         fns: MessageFns<ClientSendCtx, ServerReceiveCtx, ClientMessageEvent<E>, E>,
+        trigger: TriggerFn,
     ) -> Self {
         Self {
             type_id: TypeId::of::<E>(),
+            type_name: any::type_name::<E>(),
             message: ClientMessage::new(app, channel, fns),
-            trigger: Self::trigger_typed::<E>,
+            trigger,
         }
     }
 
@@ -132,10 +213,40 @@ impl ClientEvent {
         }
     }
 
+    /// Like [`Self::trigger_typed`], but also runs observers of the event target.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `messages` is [`Messages<FromClient<ClientMessageEvent<E>>>`].
+    unsafe fn trigger_entity_typed<E: EntityEvent>(commands: &mut Commands, from_messages: PtrMut) {
+        let from_messages: &mut Messages<FromClient<ClientMessageEvent<E>>> =
+            unsafe { from_messages.deref_mut() };
+        for FromClient { client_id, message } in from_messages.drain() {
+            let target = message.event.event_target();
+            debug!(
+                "triggering `{}` for `{target}` from `{client_id}`",
+                ShortName::of::<FromClient<E>>()
+            );
+            commands.trigger_with(
+                FromClient {
+                    client_id,
+                    message: message.event,
+                },
+                FromClientTrigger {
+                    target: Some(target),
+                },
+            );
+        }
+    }
+
     pub(super) fn type_id(&self) -> TypeId {
         self.type_id
     }
 
+    pub(super) fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
     pub(crate) fn message(&self) -> &ClientMessage {
         &self.message
     }
@@ -164,6 +275,33 @@ impl ClientTriggerExt for World {
     }
 }
 
+/// Serializes an [`EntityEvent`] with its target mapped to the server entity.
+///
+/// The event is only available by reference, so the target is mapped on a copy
+/// obtained by serializing and deserializing the event.
+fn serialize_entity_event<E: EntityEvent + Serialize + DeserializeOwned>(
+    ctx: &mut ClientSendCtx,
+    event: &E,
+    message_bytes: &mut Vec<u8>,
+) -> Result<()> {
+    let mut copy_bytes = Vec::new();
+    postcard_utils::to_extend_mut(event, &mut copy_bytes)?;
+    let mut event: E = postcard_utils::from_buf(&mut copy_bytes.as_slice())?;
+    let target = event.event_target_mut();
+    *target = ctx.get_mapped(*target);
+    postcard_utils::to_extend_mut(&event, message_bytes)?;
+    Ok(())
+}
+
+/// Deserializes an [`EntityEvent`] with its target already mapped to the server entity.
+fn deserialize_entity_event<E: EntityEvent + DeserializeOwned>(
+    _ctx: &mut ServerReceiveCtx,
+    message: &mut Bytes,
+) -> Result<E> {
+    let event = postcard_utils::from_buf(message)?;
+    Ok(event)
+}
+
 /// A message that used under the hood for client events.
 ///
 /// Events are implemented through messages in order to reuse their logic.
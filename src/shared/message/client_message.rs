@@ -1,7 +1,15 @@
-use core::any::TypeId;
+use core::any::{self, TypeId};
 
 use bevy::{
-    ecs::{component::ComponentId, entity::MapEntities, message::MessageCursor},
+    ecs::{
+        component::ComponentId,
+        entity::MapEntities,
+        event::{Trigger, trigger_entity_internal},
+        message::MessageCursor,
+        observer::{CachedObservers, TriggerContext},
+        world::DeferredWorld,
+    },
+    platform::collections::HashSet,
     prelude::*,
     ptr::{Ptr, PtrMut},
 };
@@ -11,10 +19,11 @@ use serde::{Serialize, de::DeserializeOwned};
 
 use super::{
     ctx::{ClientSendCtx, ServerReceiveCtx},
-    message_fns::{DeserializeFn, MessageFns, SerializeFn, UntypedMessageFns},
+    limits::{self, MessageLimit},
+    message_fns::{DeserializeFn, KeyFn, MessageFns, SerializeFn, UntypedKeyFn, UntypedMessageFns},
     registry::RemoteMessageRegistry,
 };
-use crate::{postcard_utils, prelude::*};
+use crate::{postcard_utils, prelude::*, shared::backend::channels::MessageChannel};
 
 /// An extension trait for [`App`] for creating client messages.
 ///
@@ -35,7 +44,7 @@ pub trait ClientMessageAppExt {
     /// See also the [corresponding section](../index.html#from-client-to-server) from the quick start guide.
     fn add_client_message<M: Message + Serialize + DeserializeOwned>(
         &mut self,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
     ) -> &mut Self {
         self.add_client_message_with(channel, default_serialize::<M>, default_deserialize::<M>)
     }
@@ -47,7 +56,7 @@ pub trait ClientMessageAppExt {
     ///
     /// [`Clone`] is required because, before sending, we need to map entities from the client to the server without
     /// modifying the original component.
-    fn add_mapped_client_message<M>(&mut self, channel: Channel) -> &mut Self
+    fn add_mapped_client_message<M>(&mut self, channel: impl Into<MessageChannel>) -> &mut Self
     where
         M: Message + Serialize + DeserializeOwned + MapEntities + Clone,
     {
@@ -63,6 +72,10 @@ pub trait ClientMessageAppExt {
 
     See also [`postcard_utils`] and [`ClientEventAppExt::add_client_event_with`].
 
+    [`ServerReceiveCtx`] provides the sender and read access to its entity, so deserialization
+    can depend on per-client state. Return an error to reject the message and use
+    [`ServerReceiveCtx::flag_client`] to report the client.
+
     # Examples
 
     Register a message with [`Box<dyn PartialReflect>`]:
@@ -115,16 +128,72 @@ pub trait ClientMessageAppExt {
     */
     fn add_client_message_with<M: Message>(
         &mut self,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
         serialize: SerializeFn<ClientSendCtx, M>,
         deserialize: DeserializeFn<ServerReceiveCtx, M>,
     ) -> &mut Self;
+
+    /// Limits how often and how large messages `M` can be received from each client.
+    ///
+    /// Exceeding messages are dropped on the server before deserialization.
+    /// Received and dropped messages are counted in
+    /// [`ClientMessageCounters`](super::limits::ClientMessageCounters) on the client entity.
+    /// See [`MessageLimit`] for details.
+    ///
+    /// See also [`ClientEventAppExt::limit_client_event`] and [`Self::limit_all_client_messages`].
+    fn limit_client_message<M: Message>(&mut self, limit: MessageLimit) -> &mut Self;
+
+    /// Limits all client messages and events combined for each client.
+    ///
+    /// Includes internal events, such as the protocol check sent on connection. Applied after the limit of the message type, if any. Replaces the previous aggregate limit.
+    /// See [`Self::limit_client_message`] for details.
+    fn limit_all_client_messages(&mut self, limit: MessageLimit) -> &mut Self;
+
+    /// Keeps only the newest message `M` per update.
+    ///
+    /// Older messages are dropped before serialization. Useful for messages that represent
+    /// the latest state, like input or aim direction, and are usually sent
+    /// over [`Channel::Unreliable`].
+    ///
+    /// Messages sent locally when the app is both client and server are not coalesced.
+    ///
+    /// See also [`Self::coalesce_client_message_by`] and
+    /// [`ServerMessageAppExt::coalesce_server_message`].
+    fn coalesce_client_message<M: Message>(&mut self) -> &mut Self {
+        self.coalesce_client_message_by::<M>(|_| 0)
+    }
+
+    /**
+    Like [`Self::coalesce_client_message`], but keeps the newest message for each key returned by
+    the specified function.
+
+    # Examples
+
+    ```
+    # use bevy::state::app::StatesPlugin;
+    use bevy::prelude::*;
+    use bevy_replicon::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    # let mut app = App::new();
+    # app.add_plugins((StatesPlugin, RepliconPlugins));
+    app.add_client_message::<AimAt>(Channel::Unreliable)
+        .coalesce_client_message_by::<AimAt>(|aim| aim.weapon.into());
+
+    #[derive(Message, Serialize, Deserialize)]
+    struct AimAt {
+        weapon: u8,
+        direction: Vec2,
+    }
+    ```
+    **/
+    fn coalesce_client_message_by<M: Message>(&mut self, key: KeyFn<M>) -> &mut Self;
 }
 
 impl ClientMessageAppExt for App {
     fn add_client_message_with<M: Message>(
         &mut self,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
         serialize: SerializeFn<ClientSendCtx, M>,
         deserialize: DeserializeFn<ServerReceiveCtx, M>,
     ) -> &mut Self {
@@ -139,6 +208,68 @@ impl ClientMessageAppExt for App {
 
         self
     }
+
+    fn limit_client_message<M: Message>(&mut self, limit: MessageLimit) -> &mut Self {
+        let messages_id = self
+            .world()
+            .components()
+            .resource_id::<Messages<M>>()
+            .unwrap_or_else(|| {
+                panic!(
+                    "message `{}` should be previously registered",
+                    ShortName::of::<M>()
+                )
+            });
+
+        let registry = self.world().resource::<RemoteMessageRegistry>();
+        let message = registry
+            .iter_client_messages()
+            .find(|m| m.messages_id() == messages_id)
+            .unwrap_or_else(|| {
+                panic!(
+                    "message `{}` should be previously registered as a client message",
+                    ShortName::of::<M>()
+                )
+            });
+
+        let channel_id = message.channel_id();
+        limits::limit_channel(self, channel_id, limit);
+
+        self
+    }
+
+    fn limit_all_client_messages(&mut self, limit: MessageLimit) -> &mut Self {
+        limits::limit_all(self, limit);
+        self
+    }
+
+    fn coalesce_client_message_by<M: Message>(&mut self, key: KeyFn<M>) -> &mut Self {
+        let messages_id = self
+            .world()
+            .components()
+            .resource_id::<Messages<M>>()
+            .unwrap_or_else(|| {
+                panic!(
+                    "message `{}` should be previously registered",
+                    ShortName::of::<M>()
+                )
+            });
+
+        let mut registry = self.world_mut().resource_mut::<RemoteMessageRegistry>();
+        let message = registry
+            .iter_client_messages_mut()
+            .find(|m| m.messages_id() == messages_id)
+            .unwrap_or_else(|| {
+                panic!(
+                    "message `{}` should be previously registered as a client message",
+                    ShortName::of::<M>()
+                )
+            });
+
+        message.coalesce = Some(UntypedKeyFn::new(key));
+
+        self
+    }
 }
 
 /// Type-erased functions and metadata for a registered client messages.
@@ -160,19 +291,32 @@ pub(crate) struct ClientMessage {
     /// ID of `M`.
     type_id: TypeId,
 
+    /// Full name of `M`.
+    type_name: &'static str,
+
     send: SendFn,
     receive: ReceiveFn,
     send_locally: SendLocallyFn,
     reset: ResetFn,
     fns: UntypedMessageFns,
+
+    /// Function to get a key for messages that should be coalesced.
+    coalesce: Option<UntypedKeyFn>,
 }
 
 impl ClientMessage {
     pub(super) fn new<M: Message, I: 'static>(
         app: &mut App,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
         fns: MessageFns<ClientSendCtx, ServerReceiveCtx, M, I>,
     ) -> Self {
+        let channel = channel.into();
+        if let MessageChannel::Shared(shared) = channel {
+            app.world_mut()
+                .resource_mut::<ProtocolHasher>()
+                .share_channel(shared.name());
+        }
+
         let channel_id = app
             .world_mut()
             .resource_mut::<RepliconChannels>()
@@ -195,11 +339,13 @@ impl ClientMessage {
             from_messages_id,
             channel_id,
             type_id: TypeId::of::<M>(),
+            type_name: any::type_name::<M>(),
             send: Self::send_typed::<M, I>,
             receive: Self::receive_typed::<M, I>,
             send_locally: Self::send_locally_typed::<M>,
             reset: Self::reset_typed::<M>,
             fns: fns.into(),
+            coalesce: None,
         }
     }
 
@@ -227,6 +373,10 @@ impl ClientMessage {
         self.type_id
     }
 
+    pub(super) fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
     /// Sends a message to the server.
     ///
     /// # Safety
@@ -258,19 +408,49 @@ impl ClientMessage {
     ) {
         let reader: &mut ClientMessageReader<M> = unsafe { reader.deref_mut() };
         let messages = unsafe { messages.deref() };
-        for message in reader.read(messages) {
-            let mut message_bytes = Vec::new();
-            if let Err(e) = unsafe { self.serialize::<M, I>(ctx, message, &mut message_bytes) } {
-                error!(
-                    "ignoring message `{}` that failed to serialize: {e}",
-                    ShortName::of::<M>()
-                );
-                continue;
+        if let Some(coalesce) = self.coalesce {
+            let key = unsafe { coalesce.typed::<M>() };
+            let messages: Vec<_> = reader.read(messages).collect();
+            let mut newest = HashSet::new();
+            let mut coalesced: Vec<_> = messages
+                .into_iter()
+                .rev()
+                .filter(|message| newest.insert((key)(message)))
+                .collect();
+            coalesced.reverse();
+
+            for message in coalesced {
+                unsafe { self.send_message::<M, I>(ctx, message, client_messages) };
+            }
+        } else {
+            for message in reader.read(messages) {
+                unsafe { self.send_message::<M, I>(ctx, message, client_messages) };
             }
+        }
+    }
 
-            debug!("sending message `{}`", ShortName::of::<M>());
-            client_messages.send(self.channel_id, message_bytes);
+    /// Serializes and sends a single message `M`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that this instance was created for `M` and `I`.
+    unsafe fn send_message<M: Message, I: 'static>(
+        &self,
+        ctx: &mut ClientSendCtx,
+        message: &M,
+        client_messages: &mut ClientMessages,
+    ) {
+        let mut message_bytes = Vec::new();
+        if let Err(e) = unsafe { self.serialize::<M, I>(ctx, message, &mut message_bytes) } {
+            error!(
+                "ignoring message `{}` that failed to serialize: {e}",
+                ShortName::of::<M>()
+            );
+            return;
         }
+
+        debug!("sending message `{}`", ShortName::of::<M>());
+        client_messages.send(self.channel_id, message_bytes);
     }
 
     /// Receives messages from a client.
@@ -302,6 +482,15 @@ impl ClientMessage {
     ) {
         let from_messages: &mut Messages<FromClient<M>> = unsafe { from_messages.deref_mut() };
         for (client, mut message) in server_messages.receive(self.channel_id) {
+            if !ctx.clients.contains(client) {
+                debug!(
+                    "ignoring message `{}` from disconnected client `{client}`",
+                    ShortName::of::<M>()
+                );
+                continue;
+            }
+
+            ctx.client_id = client.into();
             match unsafe { self.deserialize::<M, I>(ctx, &mut message) } {
                 Ok(message) => {
                     debug!(
@@ -454,7 +643,9 @@ impl<M: Message> FromWorld for ClientMessageReader<M> {
 /// A remote message from a client.
 ///
 /// Emitted only on server.
-#[derive(Message, Event, Deref, DerefMut, Debug, Clone, Copy)]
+///
+/// When triggered as an event, uses [`FromClientTrigger`].
+#[derive(Message, Deref, DerefMut, Debug, Clone, Copy)]
 pub struct FromClient<T> {
     /// Sender of the message.
     ///
@@ -466,6 +657,65 @@ pub struct FromClient<T> {
     pub message: T,
 }
 
+impl<T: Send + Sync + 'static> Event for FromClient<T> {
+    type Trigger<'a> = FromClientTrigger;
+}
+
+/// Allows observing events registered via [`ClientEventAppExt::add_client_entity_event`] on their targets.
+impl<T: EntityEvent> EntityEvent for FromClient<T> {
+    fn event_target(&self) -> Entity {
+        self.message.event_target()
+    }
+
+    fn event_target_mut(&mut self) -> &mut Entity {
+        self.message.event_target_mut()
+    }
+}
+
+/// [`Trigger`] for [`FromClient`].
+///
+/// Runs all global observers. If [`Self::target`] is set, also runs observers that watch the target.
+///
+/// The target is set for events registered via
+/// [`ClientEventAppExt::add_client_entity_event`].
+#[derive(Default, Debug, Clone, Copy)]
+pub struct FromClientTrigger {
+    /// Server entity targeted by the event.
+    pub target: Option<Entity>,
+}
+
+// SAFETY:
+// - `FromClient<T>`'s `Event::Trigger` is constrained to `FromClientTrigger`.
+// - The implementation abides by the other safety constraints defined in `Trigger`.
+unsafe impl<T: Send + Sync + 'static> Trigger<FromClient<T>> for FromClientTrigger {
+    unsafe fn trigger(
+        &mut self,
+        world: DeferredWorld,
+        observers: &CachedObservers,
+        trigger_context: &TriggerContext,
+        event: &mut FromClient<T>,
+    ) {
+        // Placeholder can't be observed, so only global observers will run without a target.
+        let target = self.target.unwrap_or(Entity::PLACEHOLDER);
+
+        // SAFETY:
+        // - `observers` come from `world` and match the event type, enforced by the call to `trigger`.
+        // - The event pointer comes from `event`, which is an `Event`.
+        // - `self` is the trigger for `FromClient<T>`.
+        // - `trigger_context`'s event key matches `FromClient<T>`, enforced by the call to `trigger`.
+        unsafe {
+            trigger_entity_internal(
+                world,
+                observers,
+                event.into(),
+                self.into(),
+                target,
+                trigger_context,
+            );
+        }
+    }
+}
+
 /// Default message serialization function.
 pub fn default_serialize<M: Serialize>(
     _ctx: &mut ClientSendCtx,
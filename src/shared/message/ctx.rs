@@ -1,6 +1,11 @@
+use alloc::string::String;
+
 use bevy::prelude::*;
 
-use crate::shared::server_entity_map::ServerEntityMap;
+use crate::{
+    prelude::*,
+    shared::{backend::connected_client::ConnectedClient, server_entity_map::ServerEntityMap},
+};
 
 /// Message sending context for client.
 #[non_exhaustive]
@@ -33,10 +38,66 @@ impl EntityMapper for ClientSendCtx<'_> {
 }
 
 /// Message receiving context for server.
+///
+/// To reject a message, return an error from the deserialization function.
+/// To additionally report the client, use [`Self::flag_client`].
 #[non_exhaustive]
 pub struct ServerReceiveCtx<'a> {
     /// Registry of reflected types.
     pub type_registry: &'a AppTypeRegistry,
+
+    /// Client that sent the message.
+    pub client_id: ClientId,
+
+    /// Connected clients for [`Self::client_entity`].
+    pub(crate) clients: &'a Query<'a, 'a, EntityRef<'static>, With<ConnectedClient>>,
+
+    /// Clients flagged by [`Self::flag_client`].
+    pub(crate) flagged: Vec<ClientFlagged>,
+}
+
+impl ServerReceiveCtx<'_> {
+    /// Returns the entity of the client that sent the message.
+    ///
+    /// Can be used to read per-client state from its components.
+    pub fn client_entity(&self) -> EntityRef<'_> {
+        let entity = self
+            .client_id
+            .entity()
+            .expect("messages should be deserialized only for remote clients");
+        self.clients
+            .get(entity)
+            .expect("sender should be a connected client")
+    }
+
+    /// Reports the client that sent the message.
+    ///
+    /// After the message is processed, [`ClientFlagged`] will be triggered for the client entity.
+    /// Flagging doesn't reject the message, return an error for it.
+    pub fn flag_client(&mut self, reason: impl Into<String>) {
+        let client = self
+            .client_id
+            .entity()
+            .expect("messages should be deserialized only for remote clients");
+        self.flagged.push(ClientFlagged {
+            client,
+            reason: reason.into(),
+        });
+    }
+}
+
+/// An event that indicates that a client sent a suspicious message.
+///
+/// Triggered on the server for the client entity by [`ServerReceiveCtx::flag_client`].
+/// Can be used to log, kick or ban the client.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct ClientFlagged {
+    /// Entity of the flagged client.
+    #[event_target]
+    pub client: Entity,
+
+    /// Reason passed to [`ServerReceiveCtx::flag_client`].
+    pub reason: String,
 }
 
 /// Message sending context for server.
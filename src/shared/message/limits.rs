@@ -0,0 +1,340 @@
+#[cfg(feature = "server")]
+use core::time::Duration;
+
+#[cfg(feature = "server")]
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+#[cfg(feature = "server")]
+use log::{debug, warn};
+
+#[cfg(feature = "server")]
+use super::registry::RemoteMessageRegistry;
+#[cfg(feature = "server")]
+use crate::prelude::*;
+
+/**
+Limits for a client message type, applied to each client separately.
+
+Messages that exceed the limit are dropped on the server before deserialization
+and handled according to [`Self::reaction`].
+
+Configured via [`ClientMessageAppExt::limit_client_message`]
+or [`ClientEventAppExt::limit_client_event`]. To limit all messages and events
+from a client combined, use [`ClientMessageAppExt::limit_all_client_messages`].
+
+# Examples
+
+```
+# use bevy::state::app::StatesPlugin;
+use bevy::prelude::*;
+use bevy_replicon::{
+    prelude::*,
+    shared::message::limits::{LimitReaction, MessageLimit},
+};
+use serde::{Deserialize, Serialize};
+
+# let mut app = App::new();
+# app.add_plugins((StatesPlugin, RepliconPlugins));
+app.add_client_message::<Chat>(Channel::Ordered)
+    .limit_client_message::<Chat>(MessageLimit {
+        max_per_second: Some(5),
+        max_size: Some(256),
+        reaction: LimitReaction::Disconnect,
+    });
+
+#[derive(Message, Serialize, Deserialize)]
+struct Chat(String);
+```
+**/
+#[derive(Default, Debug, Clone, Copy)]
+pub struct MessageLimit {
+    /// Maximum number of messages a client can send within a second.
+    ///
+    /// [`None`] means no limit.
+    pub max_per_second: Option<u32>,
+
+    /// Maximum size of a single message in bytes.
+    ///
+    /// [`None`] means no limit.
+    pub max_size: Option<usize>,
+
+    /// What to do when a client exceeds the limit.
+    pub reaction: LimitReaction,
+}
+
+/// Reaction to a client message that exceeds its [`MessageLimit`].
+///
+/// The message is dropped in all cases.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitReaction {
+    /// Drop silently.
+    Drop,
+    /// Drop and log a warning.
+    #[default]
+    Log,
+    /// Drop and trigger [`MessageLimitExceeded`] for the client entity.
+    Trigger,
+    /// Drop, log a warning and write [`DisconnectRequest`] for the client.
+    ///
+    /// The request is written only once. All remaining messages from the client are dropped silently.
+    Disconnect,
+}
+
+/// Which part of [`MessageLimit`] was exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    /// [`MessageLimit::max_per_second`].
+    Rate,
+    /// [`MessageLimit::max_size`].
+    Size,
+}
+
+/// An event that indicates that a client exceeded a [`MessageLimit`].
+///
+/// Triggered on the server for the client entity if [`MessageLimit::reaction`]
+/// is set to [`LimitReaction::Trigger`].
+#[cfg(feature = "server")]
+#[derive(EntityEvent, Debug, Clone, Copy)]
+pub struct MessageLimitExceeded {
+    /// Entity of the client that sent the message.
+    #[event_target]
+    pub client: Entity,
+
+    /// Full name of the dropped message type.
+    pub message: &'static str,
+
+    /// The exceeded limit.
+    pub kind: LimitKind,
+
+    /// Whether the exceeded limit was set via [`ClientMessageAppExt::limit_all_client_messages`].
+    pub aggregate: bool,
+}
+
+/// Message counters for a connected client.
+///
+/// Only messages with a configured [`MessageLimit`] are counted.
+/// With [`ClientMessageAppExt::limit_all_client_messages`], all messages are counted.
+/// After a [`LimitReaction::Disconnect`], all messages from the client are dropped and counted.
+///
+/// Automatically inserted on [`ConnectedClient`] entities after the first limit registration.
+#[cfg(feature = "server")]
+#[derive(Component, Default, Debug, Clone)]
+pub struct ClientMessageCounters {
+    /// Number of received messages.
+    pub received: usize,
+
+    /// Number of messages dropped due to exceeded limits.
+    pub dropped: usize,
+
+    /// Rate windows for each limited channel.
+    windows: HashMap<usize, RateWindow>,
+
+    /// Rate window for all messages.
+    aggregate_window: Option<RateWindow>,
+
+    /// Indicates that a disconnect was requested due to an exceeded limit.
+    disconnecting: bool,
+}
+
+#[cfg(feature = "server")]
+#[derive(Debug, Clone, Copy)]
+struct RateWindow {
+    start: Duration,
+    count: u32,
+}
+
+#[cfg(feature = "server")]
+impl RateWindow {
+    fn new(now: Duration) -> Self {
+        Self {
+            start: now,
+            count: 0,
+        }
+    }
+
+    /// Counts a message and returns the number of messages in the current window.
+    fn count(&mut self, now: Duration) -> u32 {
+        if now.saturating_sub(self.start) >= Duration::from_secs(1) {
+            self.start = now;
+            self.count = 0;
+        }
+        self.count += 1;
+        self.count
+    }
+}
+
+/// Configured client message limits.
+#[cfg(feature = "server")]
+#[derive(Resource, Default)]
+struct ClientMessageLimits {
+    /// Limits for each limited client channel.
+    channels: Vec<(usize, MessageLimit)>,
+
+    /// Limit for all client messages combined.
+    aggregate: Option<MessageLimit>,
+}
+
+/// Enables limits for a client channel.
+///
+/// Limits are enforced only on the server, so without the `server` feature this is a no-op.
+#[cfg_attr(
+    not(feature = "server"),
+    expect(unused_variables, reason = "limits are enforced only on server")
+)]
+pub(super) fn limit_channel(app: &mut App, channel_id: usize, limit: MessageLimit) {
+    #[cfg(feature = "server")]
+    {
+        let mut limits = enable_limits(app);
+        limits.channels.retain(|&(id, _)| id != channel_id);
+        limits.channels.push((channel_id, limit));
+    }
+}
+
+/// Enables a limit for all client messages combined.
+///
+/// Like [`limit_channel`], this is a no-op without the `server` feature.
+#[cfg_attr(
+    not(feature = "server"),
+    expect(unused_variables, reason = "limits are enforced only on server")
+)]
+pub(super) fn limit_all(app: &mut App, limit: MessageLimit) {
+    #[cfg(feature = "server")]
+    {
+        enable_limits(app).aggregate = Some(limit);
+    }
+}
+
+#[cfg(feature = "server")]
+fn enable_limits(app: &mut App) -> Mut<'_, ClientMessageLimits> {
+    if !app.world().contains_resource::<ClientMessageLimits>() {
+        debug!("enabling client message limits");
+        app.init_resource::<ClientMessageLimits>()
+            .register_required_components::<ConnectedClient, ClientMessageCounters>()
+            .add_systems(
+                PreUpdate,
+                limit_messages
+                    .after(ServerSystems::ReceivePackets)
+                    .before(ServerSystems::Receive)
+                    .run_if(in_state(ServerState::Running)),
+            );
+    }
+
+    app.world_mut().resource_mut::<ClientMessageLimits>()
+}
+
+#[cfg(feature = "server")]
+fn limit_messages(
+    mut commands: Commands,
+    mut disconnects: MessageWriter<DisconnectRequest>,
+    mut server_messages: ResMut<ServerMessages>,
+    mut clients: Query<&mut ClientMessageCounters>,
+    limits: Res<ClientMessageLimits>,
+    registry: Res<RemoteMessageRegistry>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed();
+    let any_disconnecting = clients.iter().any(|counters| counters.disconnecting);
+    let messages = registry
+        .iter_client_messages()
+        .map(|message| (message.channel_id(), message.type_name()))
+        .chain(
+            registry
+                .iter_client_events()
+                .map(|event| (event.message().channel_id(), event.type_name())),
+        );
+    for (channel_id, type_name) in messages {
+        let channel_limit = limits
+            .channels
+            .iter()
+            .find(|&&(id, _)| id == channel_id)
+            .map(|&(_, limit)| limit);
+        if channel_limit.is_none() && limits.aggregate.is_none() && !any_disconnecting {
+            continue;
+        }
+
+        server_messages.retain_received(channel_id, |client, message| {
+            let Ok(mut counters) = clients.get_mut(client) else {
+                return true;
+            };
+
+            if counters.disconnecting {
+                counters.received += 1;
+                counters.dropped += 1;
+                return false;
+            }
+            if channel_limit.is_none() && limits.aggregate.is_none() {
+                return true;
+            }
+
+            counters.received += 1;
+            let exceeded = channel_limit
+                .and_then(|limit| {
+                    let window = counters
+                        .windows
+                        .entry(channel_id)
+                        .or_insert_with(|| RateWindow::new(now));
+                    check_limit(limit, window, now, message.len()).map(|kind| (limit, kind, false))
+                })
+                .or_else(|| {
+                    let limit = limits.aggregate?;
+                    let window = counters
+                        .aggregate_window
+                        .get_or_insert_with(|| RateWindow::new(now));
+                    check_limit(limit, window, now, message.len()).map(|kind| (limit, kind, true))
+                });
+
+            let Some((limit, kind, aggregate)) = exceeded else {
+                return true;
+            };
+
+            counters.dropped += 1;
+            match limit.reaction {
+                LimitReaction::Drop => debug!(
+                    "dropping message `{type_name}` from `{client}` due to exceeded {kind:?} limit"
+                ),
+                LimitReaction::Log => warn!(
+                    "dropping message `{type_name}` from `{client}` due to exceeded {kind:?} limit"
+                ),
+                LimitReaction::Trigger => {
+                    debug!("triggering limit exceed for message `{type_name}` from `{client}`");
+                    commands.trigger(MessageLimitExceeded {
+                        client,
+                        message: type_name,
+                        kind,
+                        aggregate,
+                    });
+                }
+                LimitReaction::Disconnect => {
+                    warn!(
+                        "disconnecting `{client}` due to exceeded {kind:?} limit for message `{type_name}`"
+                    );
+                    counters.disconnecting = true;
+                    disconnects.write(DisconnectRequest {
+                        client,
+                        reason: DisconnectReason::LimitExceeded,
+                    });
+                }
+            }
+
+            false
+        });
+    }
+}
+
+/// Returns the exceeded part of the limit for a message.
+///
+/// Counts the message in the window if the size is within the limit.
+#[cfg(feature = "server")]
+fn check_limit(
+    limit: MessageLimit,
+    window: &mut RateWindow,
+    now: Duration,
+    size: usize,
+) -> Option<LimitKind> {
+    if limit.max_size.is_some_and(|max| size > max) {
+        return Some(LimitKind::Size);
+    }
+
+    let max = limit.max_per_second?;
+    (window.count(now) > max).then_some(LimitKind::Rate)
+}
@@ -108,6 +108,45 @@ impl<S, D, M: 'static, I: 'static> From<MessageFns<S, D, M, I>> for UntypedMessa
     }
 }
 
+/// Type-erased function that returns a coalescing key for a message.
+///
+/// See [`ServerMessageAppExt::coalesce_server_message_by`]
+/// and [`ClientMessageAppExt::coalesce_client_message_by`].
+#[derive(Clone, Copy)]
+pub(super) struct UntypedKeyFn {
+    message_id: TypeId,
+    message_name: ShortName<'static>,
+    key: unsafe fn(),
+}
+
+impl UntypedKeyFn {
+    pub(super) fn new<M: 'static>(key: KeyFn<M>) -> Self {
+        // SAFETY: the function won't be called until the type is restored.
+        Self {
+            message_id: TypeId::of::<M>(),
+            message_name: ShortName::of::<M>(),
+            key: unsafe { mem::transmute::<KeyFn<M>, unsafe fn()>(key) },
+        }
+    }
+
+    /// Restores the original function from which this type was created.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the function is called with the same generic with which this instance was created.
+    pub(super) unsafe fn typed<M: 'static>(self) -> KeyFn<M> {
+        debug_assert_eq!(
+            self.message_id,
+            TypeId::of::<M>(),
+            "trying to call key function with message `{}`, but it was created with `{}`",
+            ShortName::of::<M>(),
+            self.message_name,
+        );
+
+        unsafe { mem::transmute::<unsafe fn(), KeyFn<M>>(self.key) }
+    }
+}
+
 /// Serialization and deserialization functions for a message.
 ///
 /// For events, we want to allow users to customize these functions, but it would be inconvenient
@@ -200,6 +239,9 @@ pub type SerializeFn<C, M> = fn(&mut C, &M, &mut Vec<u8>) -> Result<()>;
 /// Signature of message deserialization functions.
 pub type DeserializeFn<C, M> = fn(&mut C, &mut Bytes) -> Result<M>;
 
+/// Signature of message coalescing key functions.
+pub type KeyFn<M> = fn(&M) -> u64;
+
 /// Signature of message adapter serialization functions.
 pub(super) type AdapterSerializeFn<C, M, I> =
     fn(&mut C, &M, &mut Vec<u8>, SerializeFn<C, I>) -> Result<()>;
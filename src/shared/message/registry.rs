@@ -43,6 +43,14 @@ impl RemoteMessageRegistry {
         self.server_events.iter_mut()
     }
 
+    pub(super) fn iter_client_messages(&self) -> impl Iterator<Item = &ClientMessage> {
+        self.client_messages.iter()
+    }
+
+    pub(super) fn iter_client_messages_mut(&mut self) -> impl Iterator<Item = &mut ClientMessage> {
+        self.client_messages.iter_mut()
+    }
+
     pub(crate) fn iter_all_server(&self) -> impl Iterator<Item = &ServerMessage> {
         self.server_messages
             .iter()
@@ -63,6 +71,40 @@ impl RemoteMessageRegistry {
         self.client_events.iter()
     }
 
+    /// Iterates over server messages and events with their type information and channel IDs.
+    ///
+    /// Returns whether it's an event, its type ID, type name and channel ID.
+    pub(crate) fn iter_server_types(
+        &self,
+    ) -> impl Iterator<Item = (bool, TypeId, &'static str, usize)> + '_ {
+        let messages = self
+            .server_messages
+            .iter()
+            .map(|m| (false, m.type_id(), m.type_name(), m.channel_id()));
+        let events = self
+            .server_events
+            .iter()
+            .map(|e| (true, e.type_id(), e.type_name(), e.message().channel_id()));
+
+        messages.chain(events)
+    }
+
+    /// Like [`Self::iter_server_types`], but for client messages and events.
+    pub(crate) fn iter_client_types(
+        &self,
+    ) -> impl Iterator<Item = (bool, TypeId, &'static str, usize)> + '_ {
+        let messages = self
+            .client_messages
+            .iter()
+            .map(|m| (false, m.type_id(), m.type_name(), m.channel_id()));
+        let events = self
+            .client_events
+            .iter()
+            .map(|e| (true, e.type_id(), e.type_name(), e.message().channel_id()));
+
+        messages.chain(events)
+    }
+
     /// Returns registered channel ID for server message `M`.
     ///
     /// See also [`ServerMessageAppExt::add_server_message`](super::server_message::ServerMessageAppExt::add_server_message).
@@ -0,0 +1,399 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    time::Duration,
+};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use log::debug;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{prelude::*, shared::backend::channels::MessageChannel};
+
+/// An extension trait for [`App`] for creating client requests.
+///
+/// See also [`ClientRequests::send`] and [`ClientRequestExt`] for sending requests
+/// and [`ServerRespondExt`] for responding to them.
+pub trait RequestAppExt {
+    /**
+    Registers a client request `Req` with the response `Resp`.
+
+    Requests are sent using [`ClientRequests::send`] or [`ClientRequestExt::client_request`],
+    which return a [`RequestId`] to correlate the response with. On the server, [`FromClient<Request<Req>>`] event will be triggered.
+    Use [`ServerRespondExt::server_respond`] to send the response back to the requesting client.
+
+    On the client, [`Response<Req, Resp>`] event will be triggered once the response arrives,
+    the request times out (see [`ClientRequests::timeout`]) or the client disconnects.
+
+    Internally, requests are registered as a client event and responses as a server message
+    on the specified channel. Just like with events, if [`ServerMessagePlugin`] is enabled and
+    the client state is [`ClientState::Disconnected`], requests and responses will be handled locally
+    with [`ClientId::Server`].
+
+    # Examples
+
+    ```
+    # use bevy::state::app::StatesPlugin;
+    use bevy::prelude::*;
+    use bevy_replicon::{
+        prelude::*,
+        shared::message::request::{ClientRequests, Request, Response},
+    };
+    use serde::{Deserialize, Serialize};
+
+    # let mut app = App::new();
+    # app.add_plugins((StatesPlugin, RepliconPlugins));
+    app.add_client_request::<BuyItem, Purchase>(Channel::Ordered)
+        .add_observer(buy)
+        .add_observer(receive_purchase)
+        .add_systems(Update, send.run_if(in_state(ClientState::Connected)));
+
+    fn send(mut commands: Commands, mut requests: ResMut<ClientRequests<BuyItem>>) {
+        let id = requests.send(&mut commands, BuyItem { item_id: 5 });
+        info!("sent request `{id:?}`");
+    }
+
+    fn buy(request: On<FromClient<Request<BuyItem>>>, mut commands: Commands) {
+        info!("`{}` buys item {}", request.client_id, request.request.item_id);
+        commands.server_respond(request.client_id, request.id, Purchase { success: true });
+    }
+
+    fn receive_purchase(response: On<Response<BuyItem, Purchase>>) {
+        match &response.result {
+            Ok(purchase) => info!("purchase success: {}", purchase.success),
+            Err(e) => error!("unable to buy item: {e:?}"),
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct BuyItem {
+        item_id: u32,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Purchase {
+        success: bool,
+    }
+    ```
+    **/
+    fn add_client_request<Req, Resp>(&mut self, channel: impl Into<MessageChannel>) -> &mut Self
+    where
+        Req: Serialize + DeserializeOwned + Send + Sync + 'static,
+        Resp: Serialize + DeserializeOwned + Send + Sync + 'static;
+}
+
+impl RequestAppExt for App {
+    fn add_client_request<Req, Resp>(&mut self, channel: impl Into<MessageChannel>) -> &mut Self
+    where
+        Req: Serialize + DeserializeOwned + Send + Sync + 'static,
+        Resp: Serialize + DeserializeOwned + Send + Sync + 'static,
+    {
+        let channel = channel.into();
+        self.add_client_event::<Request<Req>>(channel)
+            .add_server_message::<ResponseMessage<Req, Resp>>(channel)
+            .init_resource::<ClientRequests<Req>>();
+
+        let receive = receive_responses::<Req, Resp>;
+        #[cfg(feature = "client")]
+        let receive = receive.after(ClientSystems::Receive);
+
+        self.add_systems(PreUpdate, receive)
+            .add_systems(OnExit(ClientState::Connected), cancel_requests::<Req, Resp>)
+    }
+}
+
+/// Identifier of a sent request of type `Req`.
+///
+/// Returned by [`ClientRequests::send`] or [`ClientRequestExt::client_request`]
+/// and used to match the [`Response`] with the request.
+///
+/// IDs are assigned sequentially for each request type in each app.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RequestId<Req> {
+    id: u32,
+    #[serde(skip)]
+    marker: PhantomData<fn() -> Req>,
+}
+
+impl<Req> RequestId<Req> {
+    /// Returns the numeric value of the ID.
+    pub fn get(self) -> u32 {
+        self.id
+    }
+}
+
+impl<Req> Clone for RequestId<Req> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Req> Copy for RequestId<Req> {}
+
+impl<Req> PartialEq for RequestId<Req> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<Req> Eq for RequestId<Req> {}
+
+impl<Req> Hash for RequestId<Req> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<Req> Debug for RequestId<Req> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RequestId").field(&self.id).finish()
+    }
+}
+
+/// A request sent from a client.
+///
+/// Triggered on the server as [`FromClient<Request<Req>>`].
+/// See [`RequestAppExt::add_client_request`].
+#[derive(Event, Serialize, Deserialize, Debug)]
+pub struct Request<Req: Send + Sync + 'static> {
+    /// ID that should be passed to [`ServerRespondExt::server_respond`].
+    pub id: RequestId<Req>,
+
+    /// The request itself.
+    pub request: Req,
+}
+
+/// Result of a request.
+///
+/// Triggered on the client that sent the request.
+/// See [`RequestAppExt::add_client_request`].
+#[derive(Event, Debug, Clone)]
+pub struct Response<Req: Send + Sync + 'static, Resp: Send + Sync + 'static> {
+    /// ID returned by [`ClientRequestExt::client_request`].
+    pub id: RequestId<Req>,
+
+    /// Received response or the reason why it wasn't received.
+    pub result: Result<Resp, RequestError>,
+}
+
+/// Reasons why a request didn't receive its response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestError {
+    /// The response didn't arrive within [`ClientRequests::timeout`].
+    Timeout,
+
+    /// The client disconnected before receiving the response.
+    Disconnected,
+}
+
+/// Requests of type `Req` that are waiting for their responses.
+///
+/// Inserted by [`RequestAppExt::add_client_request`].
+#[derive(Resource)]
+pub struct ClientRequests<Req> {
+    /// Maximum duration to wait for a response.
+    ///
+    /// After it, [`Response`] will be triggered with [`RequestError::Timeout`]
+    /// and the response will be ignored if it arrives later.
+    ///
+    /// By default set to 10 seconds.
+    pub timeout: Duration,
+
+    /// Send timestamps of pending requests.
+    pending: HashMap<RequestId<Req>, Duration>,
+
+    /// ID that will be assigned to the next request.
+    next_id: u32,
+}
+
+impl<Req: Send + Sync + 'static> ClientRequests<Req> {
+    /// Sends a request to the server and returns its ID.
+    ///
+    /// Like [`ClientTriggerExt::client_trigger`], but also tracks the request
+    /// until [`Response`] is triggered.
+    ///
+    /// The request is sent when the commands are applied.
+    /// See also [`ClientRequestExt::client_request`] to send a request from [`World`].
+    pub fn send(&mut self, commands: &mut Commands, request: Req) -> RequestId<Req> {
+        let id = self.next_id();
+        commands.queue(move |world: &mut World| send_request(world, id, request));
+        id
+    }
+
+    /// Assigns a new ID for a request.
+    fn next_id(&mut self) -> RequestId<Req> {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        RequestId {
+            id,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Req> ClientRequests<Req> {
+    /// Returns `true` if the request was sent and is still waiting for a response.
+    ///
+    /// Can be used to poll the request state instead of observing [`Response`].
+    pub fn is_pending(&self, id: RequestId<Req>) -> bool {
+        self.pending.contains_key(&id)
+    }
+
+    /// Returns the number of pending requests.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns `true` if there are no pending requests.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+impl<Req> Default for ClientRequests<Req> {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            pending: Default::default(),
+            next_id: 0,
+        }
+    }
+}
+
+/// Extension trait for sending client requests.
+///
+/// See also [`RequestAppExt`].
+pub trait ClientRequestExt {
+    /// Sends a request to the server and returns its ID.
+    ///
+    /// Like [`ClientRequests::send`], but sends the request immediately.
+    fn client_request<Req: Send + Sync + 'static>(&mut self, request: Req) -> RequestId<Req>;
+}
+
+impl ClientRequestExt for World {
+    fn client_request<Req: Send + Sync + 'static>(&mut self, request: Req) -> RequestId<Req> {
+        let id = self.resource_mut::<ClientRequests<Req>>().next_id();
+        send_request(self, id, request);
+        id
+    }
+}
+
+fn send_request<Req: Send + Sync + 'static>(world: &mut World, id: RequestId<Req>, request: Req) {
+    debug!("sending request `{}` {id:?}", ShortName::of::<Req>());
+    let timestamp = world.resource::<Time>().elapsed();
+    world
+        .resource_mut::<ClientRequests<Req>>()
+        .pending
+        .insert(id, timestamp);
+    world.client_trigger(Request { id, request });
+}
+
+/// Extension trait for responding to client requests.
+///
+/// See also [`RequestAppExt`].
+pub trait ServerRespondExt {
+    /// Sends a response to the client that sent the request.
+    ///
+    /// Uses [`SendMode::Direct`] under the hood.
+    fn server_respond<Req: Send + Sync + 'static, Resp: Send + Sync + 'static>(
+        &mut self,
+        client_id: ClientId,
+        id: RequestId<Req>,
+        response: Resp,
+    );
+}
+
+impl ServerRespondExt for Commands<'_, '_> {
+    fn server_respond<Req: Send + Sync + 'static, Resp: Send + Sync + 'static>(
+        &mut self,
+        client_id: ClientId,
+        id: RequestId<Req>,
+        response: Resp,
+    ) {
+        self.write_message(ToClients {
+            mode: SendMode::Direct(client_id),
+            message: ResponseMessage { id, response },
+        });
+    }
+}
+
+impl ServerRespondExt for World {
+    fn server_respond<Req: Send + Sync + 'static, Resp: Send + Sync + 'static>(
+        &mut self,
+        client_id: ClientId,
+        id: RequestId<Req>,
+        response: Resp,
+    ) {
+        self.write_message(ToClients {
+            mode: SendMode::Direct(client_id),
+            message: ResponseMessage { id, response },
+        });
+    }
+}
+
+/// A message that used under the hood for responses.
+///
+/// After receiving, drained to trigger [`Response`].
+#[derive(Message, Serialize, Deserialize)]
+struct ResponseMessage<Req: Send + Sync + 'static, Resp> {
+    id: RequestId<Req>,
+    response: Resp,
+}
+
+fn receive_responses<Req: Send + Sync + 'static, Resp: Send + Sync + 'static>(
+    mut commands: Commands,
+    mut response_messages: ResMut<Messages<ResponseMessage<Req, Resp>>>,
+    mut requests: ResMut<ClientRequests<Req>>,
+    time: Res<Time>,
+) {
+    for ResponseMessage { id, response } in response_messages.drain() {
+        if requests.pending.remove(&id).is_none() {
+            debug!(
+                "ignoring response `{}` for unknown request {id:?}",
+                ShortName::of::<Resp>()
+            );
+            continue;
+        }
+
+        debug!(
+            "triggering response `{}` for {id:?}",
+            ShortName::of::<Resp>()
+        );
+        commands.trigger(Response::<Req, Resp> {
+            id,
+            result: Ok(response),
+        });
+    }
+
+    let min_timestamp = time.elapsed().saturating_sub(requests.timeout);
+    requests.pending.retain(|&id, &mut timestamp| {
+        if timestamp >= min_timestamp {
+            return true;
+        }
+
+        debug!("request `{}` {id:?} timed out", ShortName::of::<Req>());
+        commands.trigger(Response::<Req, Resp> {
+            id,
+            result: Err(RequestError::Timeout),
+        });
+        false
+    });
+}
+
+fn cancel_requests<Req: Send + Sync + 'static, Resp: Send + Sync + 'static>(
+    mut commands: Commands,
+    mut requests: ResMut<ClientRequests<Req>>,
+) {
+    for (id, _) in requests.pending.drain() {
+        debug!(
+            "cancelling request `{}` {id:?} due to disconnect",
+            ShortName::of::<Req>()
+        );
+        commands.trigger(Response::<Req, Resp> {
+            id,
+            result: Err(RequestError::Disconnected),
+        });
+    }
+}
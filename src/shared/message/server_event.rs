@@ -1,6 +1,7 @@
-use core::any::TypeId;
+use core::any::{self, TypeId};
 
 use bevy::{ecs::entity::MapEntities, prelude::*, ptr::PtrMut};
+use bytes::Bytes;
 use log::debug;
 use serde::{Serialize, de::DeserializeOwned};
 
@@ -10,7 +11,7 @@ use super::{
     registry::RemoteMessageRegistry,
     server_message::{self, ServerMessage},
 };
-use crate::prelude::*;
+use crate::{postcard_utils, prelude::*, shared::backend::channels::MessageChannel};
 
 /// An extension trait for [`App`] for creating server events.
 ///
@@ -25,7 +26,7 @@ pub trait ServerEventAppExt {
     /// then `E` event will be emitted on the server as well.
     ///
     /// See also the [corresponding section](../index.html#from-client-to-server) from the quick start guide.
-    fn add_server_event<'a, E>(&mut self, channel: Channel) -> &mut Self
+    fn add_server_event<'a, E>(&mut self, channel: impl Into<MessageChannel>) -> &mut Self
     where
         E: Event<Trigger<'a>: Default> + Serialize + DeserializeOwned,
     {
@@ -40,7 +41,7 @@ pub trait ServerEventAppExt {
     ///
     /// Always use it for events that contain entities. Entities must be annotated with `#[entities]`.
     /// For details, see [`Component::map_entities`].
-    fn add_mapped_server_event<'a, E>(&mut self, channel: Channel) -> &mut Self
+    fn add_mapped_server_event<'a, E>(&mut self, channel: impl Into<MessageChannel>) -> &mut Self
     where
         E: Event<Trigger<'a>: Default> + Serialize + DeserializeOwned + MapEntities,
     {
@@ -51,24 +52,67 @@ pub trait ServerEventAppExt {
         )
     }
 
+    /**
+    Same as [`Self::add_server_event`], but for [`EntityEvent`]s.
+
+    The event target is mapped from server to client entity on receive, so observers of the
+    target entity will run on clients. Other entities inside the event are not mapped.
+    The target is taken from the serialized event, so it shouldn't be skipped during serialization.
+
+    Like other server events, the event is triggered only after the update tick on which it
+    was sent is received, so the target is already spawned on the client. If the target
+    can't be mapped, the event is discarded with an error.
+
+    # Examples
+
+    ```
+    # use bevy::state::app::StatesPlugin;
+    use bevy::prelude::*;
+    use bevy_replicon::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    # let mut app = App::new();
+    # app.add_plugins((StatesPlugin, RepliconPlugins));
+    app.add_server_entity_event::<Damaged>(Channel::Ordered);
+
+    # let mut commands = app.world_mut().commands();
+    # let player = commands.spawn_empty().id();
+    commands.entity(player).observe(|damaged: On<Damaged>| {
+        info!("`{}` received {} damage", damaged.entity, damaged.amount);
+    });
+
+    #[derive(EntityEvent, Serialize, Deserialize)]
+    struct Damaged {
+        entity: Entity,
+        amount: u32,
+    }
+    ```
+    **/
+    fn add_server_entity_event<'a, E>(&mut self, channel: impl Into<MessageChannel>) -> &mut Self
+    where
+        E: EntityEvent<Trigger<'a>: Default> + Serialize + DeserializeOwned;
+
     /// Same as [`Self::add_server_event`], but uses the specified functions for serialization and deserialization.
     ///
     /// See also [`ServerMessageAppExt::add_server_message_with`].
     fn add_server_event_with<'a, E: Event<Trigger<'a>: Default>>(
         &mut self,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
         serialize: SerializeFn<ServerSendCtx, E>,
         deserialize: DeserializeFn<ClientReceiveCtx, E>,
     ) -> &mut Self;
 
     /// Like [`ServerMessageAppExt::make_message_independent`], but for triggers.
     fn make_event_independent<E: Event>(&mut self) -> &mut Self;
+
+    /// Like [`ServerMessageAppExt::set_message_priority`], but for triggers.
+    fn set_event_priority<E: Event>(&mut self, priority: u32) -> &mut Self;
 }
 
 impl ServerEventAppExt for App {
     fn add_server_event_with<'a, E: Event<Trigger<'a>: Default>>(
         &mut self,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
         serialize: SerializeFn<ServerSendCtx, E>,
         deserialize: DeserializeFn<ClientReceiveCtx, E>,
     ) -> &mut Self {
@@ -84,6 +128,23 @@ impl ServerEventAppExt for App {
         self
     }
 
+    fn add_server_entity_event<'a, E>(&mut self, channel: impl Into<MessageChannel>) -> &mut Self
+    where
+        E: EntityEvent<Trigger<'a>: Default> + Serialize + DeserializeOwned,
+    {
+        self.add_server_event_with(
+            channel,
+            serialize_entity_event::<E>,
+            deserialize_entity_event::<E>,
+        );
+
+        self.world_mut()
+            .resource_mut::<ProtocolHasher>()
+            .add_entity_event::<E>();
+
+        self
+    }
+
     fn make_event_independent<E: Event>(&mut self) -> &mut Self {
         self.world_mut()
             .resource_mut::<ProtocolHasher>()
@@ -115,11 +176,40 @@ impl ServerEventAppExt for App {
 
         self
     }
+
+    fn set_event_priority<E: Event>(&mut self, priority: u32) -> &mut Self {
+        let messages_id = self
+            .world()
+            .components()
+            .resource_id::<Messages<ServerTriggerEvent<E>>>()
+            .unwrap_or_else(|| {
+                panic!(
+                    "event `{}` should be previously registered",
+                    ShortName::of::<E>()
+                )
+            });
+
+        let mut registry = self.world_mut().resource_mut::<RemoteMessageRegistry>();
+        let event = registry
+            .iter_server_events_mut()
+            .find(|e| e.message().messages_id() == messages_id)
+            .unwrap_or_else(|| {
+                panic!(
+                    "message `{}` should be previously registered as a server message",
+                    ShortName::of::<E>()
+                )
+            });
+
+        event.message_mut().priority = priority;
+
+        self
+    }
 }
 
 /// Small abstraction on top of [`ServerEvent`] that stores a function to trigger them.
 pub(crate) struct ServerEvent {
     type_id: TypeId,
+    type_name: &'static str,
     message: ServerMessage,
     trigger: TriggerFn,
 }
@@ -127,11 +217,12 @@ pub(crate) struct ServerEvent {
 impl ServerEvent {
     fn new<'a, E: Event<Trigger<'a>: Default>>(
         app: &mut App,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
         fns: MessageFns<ServerSendCtx, ClientReceiveCtx, ServerTriggerEvent<E>, E>,
     ) -> Self {
         Self {
             type_id: TypeId::of::<E>(),
+            type_name: any::type_name::<E>(),
             message: ServerMessage::new(app, channel, fns),
             trigger: Self::trigger_typed::<E>,
         }
@@ -167,6 +258,10 @@ impl ServerEvent {
         self.type_id
     }
 
+    pub(super) fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
     pub(crate) fn message(&self) -> &ServerMessage {
         &self.message
     }
@@ -176,6 +271,29 @@ impl ServerEvent {
     }
 }
 
+/// Serializes an [`EntityEvent`].
+///
+/// The target is serialized as part of the event, so it's not written separately.
+fn serialize_entity_event<E: EntityEvent + Serialize>(
+    _ctx: &mut ServerSendCtx,
+    event: &E,
+    message_bytes: &mut Vec<u8>,
+) -> Result<()> {
+    postcard_utils::to_extend_mut(event, message_bytes)?;
+    Ok(())
+}
+
+/// Deserializes an [`EntityEvent`] and maps its target to the client entity.
+fn deserialize_entity_event<E: EntityEvent + DeserializeOwned>(
+    ctx: &mut ClientReceiveCtx,
+    message: &mut Bytes,
+) -> Result<E> {
+    let mut event: E = postcard_utils::from_buf(message)?;
+    let target = event.event_target_mut();
+    *target = ctx.get_mapped(*target);
+    Ok(event)
+}
+
 /// Signature of server trigger functions.
 type TriggerFn = unsafe fn(&mut Commands, PtrMut);
 
@@ -1,10 +1,12 @@
 pub(crate) mod message_buffer;
 mod message_queue;
+pub(crate) mod send_queue;
 
-use core::any::TypeId;
+use core::any::{self, TypeId};
 
 use bevy::{
     ecs::{component::ComponentId, entity::MapEntities},
+    platform::collections::HashSet,
     prelude::*,
     ptr::{Ptr, PtrMut},
 };
@@ -15,12 +17,13 @@ use serde::{Serialize, de::DeserializeOwned};
 
 use super::{
     ctx::{ClientReceiveCtx, ServerSendCtx},
-    message_fns::{DeserializeFn, MessageFns, SerializeFn, UntypedMessageFns},
+    message_fns::{DeserializeFn, KeyFn, MessageFns, SerializeFn, UntypedKeyFn, UntypedMessageFns},
     registry::RemoteMessageRegistry,
 };
-use crate::{postcard_utils, prelude::*};
+use crate::{postcard_utils, prelude::*, shared::backend::channels::MessageChannel};
 use message_buffer::{MessageBuffer, SerializedMessage};
 use message_queue::MessageQueue;
+use send_queue::SendQueue;
 
 /// An extension trait for [`App`] for creating server messages.
 ///
@@ -43,7 +46,7 @@ pub trait ServerMessageAppExt {
     /// See also the [corresponding section](../index.html#from-client-to-server) from the quick start guide.
     fn add_server_message<M: Message + Serialize + DeserializeOwned>(
         &mut self,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
     ) -> &mut Self {
         self.add_server_message_with(channel, default_serialize::<M>, default_deserialize::<M>)
     }
@@ -54,7 +57,7 @@ pub trait ServerMessageAppExt {
     /// For details, see [`Component::map_entities`].
     fn add_mapped_server_message<M: Message + Serialize + DeserializeOwned + MapEntities>(
         &mut self,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
     ) -> &mut Self {
         self.add_server_message_with(
             channel,
@@ -120,7 +123,7 @@ pub trait ServerMessageAppExt {
     */
     fn add_server_message_with<M: Message>(
         &mut self,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
         serialize: SerializeFn<ServerSendCtx, M>,
         deserialize: DeserializeFn<ClientReceiveCtx, M>,
     ) -> &mut Self;
@@ -151,12 +154,92 @@ pub trait ServerMessageAppExt {
     ///
     /// See also [`ServerEventAppExt::make_event_independent`].
     fn make_message_independent<M: Message>(&mut self) -> &mut Self;
+
+    /**
+    Sets the sending priority for the message `M`.
+
+    Messages with higher priority are sent first. By default all messages have priority 0.
+
+    Priority matters when [`ServerPlugin::message_budget`] is set. Messages that don't fit
+    into the budget are dropped on [`Channel::Unreliable`] and deferred to the next update on
+    other channels. This way low-priority messages, like chat or cosmetic effects,
+    don't delay gameplay-critical ones.
+
+    Messages that share a channel should have the same priority, otherwise their order
+    within the channel could change.
+
+    See also [`ServerEventAppExt::set_event_priority`].
+
+    # Examples
+
+    ```
+    # use bevy::state::app::StatesPlugin;
+    use bevy::prelude::*;
+    use bevy_replicon::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    # let mut app = App::new();
+    # app.add_plugins((StatesPlugin, RepliconPlugins));
+    app.add_server_message::<Damage>(Channel::Ordered)
+        .set_message_priority::<Damage>(10)
+        .add_server_message::<Emote>(Channel::Unreliable);
+
+    #[derive(Message, Serialize, Deserialize)]
+    struct Damage(u32);
+
+    #[derive(Message, Serialize, Deserialize)]
+    struct Emote(u8);
+    ```
+    **/
+    fn set_message_priority<M: Message>(&mut self, priority: u32) -> &mut Self;
+
+    /// Keeps only the newest message `M` for each client per send.
+    ///
+    /// Regular messages are buffered until the next [`ServerTick`](crate::server::server_tick::ServerTick)
+    /// and coalesced over all updates within it. Independent messages are sent immediately,
+    /// so they are coalesced within a single update. Older messages are dropped for clients
+    /// that receive a newer one. Useful for messages that represent the latest state,
+    /// like cursor positions or voice activity, and are usually sent over [`Channel::Unreliable`].
+    ///
+    /// Messages sent locally to [`ClientId::Server`] are not coalesced.
+    ///
+    /// See also [`Self::coalesce_server_message_by`] and
+    /// [`ClientMessageAppExt::coalesce_client_message`].
+    fn coalesce_server_message<M: Message>(&mut self) -> &mut Self {
+        self.coalesce_server_message_by::<M>(|_| 0)
+    }
+
+    /**
+    Like [`Self::coalesce_server_message`], but keeps the newest message for each key returned by
+    the specified function.
+
+    # Examples
+
+    ```
+    # use bevy::state::app::StatesPlugin;
+    use bevy::prelude::*;
+    use bevy_replicon::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    # let mut app = App::new();
+    # app.add_plugins((StatesPlugin, RepliconPlugins));
+    app.add_server_message::<CursorMoved>(Channel::Unreliable)
+        .coalesce_server_message_by::<CursorMoved>(|cursor| cursor.player.into());
+
+    #[derive(Message, Serialize, Deserialize)]
+    struct CursorMoved {
+        player: u32,
+        position: Vec2,
+    }
+    ```
+    **/
+    fn coalesce_server_message_by<M: Message>(&mut self, key: KeyFn<M>) -> &mut Self;
 }
 
 impl ServerMessageAppExt for App {
     fn add_server_message_with<M: Message>(
         &mut self,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
         serialize: SerializeFn<ServerSendCtx, M>,
         deserialize: DeserializeFn<ClientReceiveCtx, M>,
     ) -> &mut Self {
@@ -203,6 +286,62 @@ impl ServerMessageAppExt for App {
 
         self
     }
+
+    fn set_message_priority<M: Message>(&mut self, priority: u32) -> &mut Self {
+        let messages_id = self
+            .world()
+            .components()
+            .resource_id::<Messages<M>>()
+            .unwrap_or_else(|| {
+                panic!(
+                    "message `{}` should be previously registered",
+                    ShortName::of::<M>()
+                )
+            });
+
+        let mut registry = self.world_mut().resource_mut::<RemoteMessageRegistry>();
+        let message = registry
+            .iter_server_messages_mut()
+            .find(|m| m.messages_id() == messages_id)
+            .unwrap_or_else(|| {
+                panic!(
+                    "message `{}` should be previously registered as a server message",
+                    ShortName::of::<M>()
+                )
+            });
+
+        message.priority = priority;
+
+        self
+    }
+
+    fn coalesce_server_message_by<M: Message>(&mut self, key: KeyFn<M>) -> &mut Self {
+        let messages_id = self
+            .world()
+            .components()
+            .resource_id::<Messages<M>>()
+            .unwrap_or_else(|| {
+                panic!(
+                    "message `{}` should be previously registered",
+                    ShortName::of::<M>()
+                )
+            });
+
+        let mut registry = self.world_mut().resource_mut::<RemoteMessageRegistry>();
+        let message = registry
+            .iter_server_messages_mut()
+            .find(|m| m.messages_id() == messages_id)
+            .unwrap_or_else(|| {
+                panic!(
+                    "message `{}` should be previously registered as a server message",
+                    ShortName::of::<M>()
+                )
+            });
+
+        message.coalesce = Some(UntypedKeyFn::new(key));
+
+        self
+    }
 }
 
 /// Type-erased functions and metadata for a registered server message.
@@ -216,6 +355,16 @@ pub(crate) struct ServerMessage {
     /// immediately.
     pub(super) independent: bool,
 
+    /// Sending priority.
+    ///
+    /// See [`ServerMessageAppExt::set_message_priority`].
+    pub(super) priority: u32,
+
+    /// Function that returns the coalescing key for the message.
+    ///
+    /// See [`ServerMessageAppExt::coalesce_server_message_by`].
+    coalesce: Option<UntypedKeyFn>,
+
     /// ID of [`Messages<M>`].
     messages_id: ComponentId,
 
@@ -231,6 +380,9 @@ pub(crate) struct ServerMessage {
     /// ID of `M`.
     type_id: TypeId,
 
+    /// Full name of `M`.
+    type_name: &'static str,
+
     send_or_buffer: SendOrBufferFn,
     receive: ReceiveFn,
     send_locally: SendLocallyFn,
@@ -241,9 +393,16 @@ pub(crate) struct ServerMessage {
 impl ServerMessage {
     pub(super) fn new<M: Message, I: 'static>(
         app: &mut App,
-        channel: Channel,
+        channel: impl Into<MessageChannel>,
         fns: MessageFns<ServerSendCtx, ClientReceiveCtx, M, I>,
     ) -> Self {
+        let channel = channel.into();
+        if let MessageChannel::Shared(shared) = channel {
+            app.world_mut()
+                .resource_mut::<ProtocolHasher>()
+                .share_channel(shared.name());
+        }
+
         let channel_id = app
             .world_mut()
             .resource_mut::<RepliconChannels>()
@@ -259,11 +418,14 @@ impl ServerMessage {
 
         Self {
             independent: false,
+            priority: 0,
+            coalesce: None,
             messages_id,
             to_messages_id,
             queue_id,
             channel_id,
             type_id: TypeId::of::<M>(),
+            type_name: any::type_name::<M>(),
             send_or_buffer: Self::send_or_buffer_typed::<M, I>,
             receive: Self::receive_typed::<M, I>,
             send_locally: Self::send_locally_typed::<M>,
@@ -292,6 +454,10 @@ impl ServerMessage {
         self.type_id
     }
 
+    pub(super) fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
     /// Sends a message to client(s).
     ///
     /// # Safety
@@ -302,19 +468,12 @@ impl ServerMessage {
         &self,
         ctx: &mut ServerSendCtx,
         to_messages: &Ptr,
-        server_messages: &mut ServerMessages,
-        clients: &Query<Entity, With<ConnectedClient>>,
+        send_queue: &mut SendQueue,
+        clients: &Query<EntityRef, With<ConnectedClient>>,
         message_buffer: &mut MessageBuffer,
     ) {
         unsafe {
-            (self.send_or_buffer)(
-                self,
-                ctx,
-                to_messages,
-                server_messages,
-                clients,
-                message_buffer,
-            )
+            (self.send_or_buffer)(self, ctx, to_messages, send_queue, clients, message_buffer)
         }
     }
 
@@ -328,31 +487,87 @@ impl ServerMessage {
         &self,
         ctx: &mut ServerSendCtx,
         to_messages: &Ptr,
-        server_messages: &mut ServerMessages,
-        clients: &Query<Entity, With<ConnectedClient>>,
+        send_queue: &mut SendQueue,
+        clients: &Query<EntityRef, With<ConnectedClient>>,
         message_buffer: &mut MessageBuffer,
     ) {
         let to_messages: &Messages<ToClients<M>> = unsafe { to_messages.deref() };
         // For server messages we don't track read message because
         // all of them will always be drained in the local sending system.
-        for ToClients { message, mode } in to_messages.get_cursor().read(to_messages) {
-            debug!("sending message `{}` with `{mode:?}`", ShortName::of::<M>());
+        let mut cursor = to_messages.get_cursor();
+        let key = self
+            .coalesce
+            .map(|coalesce| unsafe { coalesce.typed::<M>() });
+        if self.independent
+            && let Some(key) = key
+        {
+            // Buffered messages are coalesced in `MessageBuffer` instead
+            // since they are sent only when the tick changes.
+            let messages: Vec<_> = cursor.read(to_messages).collect();
+            let mut coalescer = Coalescer::default();
+            let mut superseded: Vec<_> = messages
+                .iter()
+                .rev()
+                .map(|ToClients { message, mode }| {
+                    let mut superseded = Vec::new();
+                    let recipients_left = coalescer.supersede(
+                        mode,
+                        self.channel_id,
+                        (key)(message),
+                        clients,
+                        &mut superseded,
+                    );
+                    recipients_left.then_some(superseded)
+                })
+                .collect();
+            superseded.reverse();
+
+            for (ToClients { message, mode }, superseded) in messages.into_iter().zip(superseded) {
+                let Some(superseded) = superseded else {
+                    debug!("dropping outdated message `{}`", ShortName::of::<M>());
+                    continue;
+                };
 
-            if self.independent {
+                debug!("sending message `{}` with `{mode:?}`", ShortName::of::<M>());
                 unsafe {
                     self.send_independent_message::<M, I>(
                         ctx,
                         message,
                         mode,
-                        server_messages,
+                        &superseded,
+                        send_queue,
                         clients,
                     )
                     .expect("independent server message should be serializable");
                 }
-            } else {
-                unsafe {
-                    self.buffer_message::<M, I>(ctx, message, *mode, message_buffer)
+            }
+        } else {
+            for ToClients { message, mode } in cursor.read(to_messages) {
+                debug!("sending message `{}` with `{mode:?}`", ShortName::of::<M>());
+                if self.independent {
+                    unsafe {
+                        self.send_independent_message::<M, I>(
+                            ctx,
+                            message,
+                            mode,
+                            &[],
+                            send_queue,
+                            clients,
+                        )
+                        .expect("independent server message should be serializable");
+                    }
+                } else {
+                    let key = key.map(|key| (key)(message));
+                    unsafe {
+                        self.buffer_message::<M, I>(
+                            ctx,
+                            message,
+                            mode.clone(),
+                            key,
+                            message_buffer,
+                        )
                         .expect("server message should be serializable");
+                    }
                 }
             }
         }
@@ -364,35 +579,59 @@ impl ServerMessage {
     ///
     /// The caller must ensure that this instance was created for `M` and `I`.
     ///
+    /// Skips `superseded` clients.
+    ///
     /// For regular messages see [`Self::buffer_message`].
     unsafe fn send_independent_message<M: Message, I: 'static>(
         &self,
         ctx: &mut ServerSendCtx,
         message: &M,
         mode: &SendMode,
-        server_messages: &mut ServerMessages,
-        clients: &Query<Entity, With<ConnectedClient>>,
+        superseded: &[Entity],
+        send_queue: &mut SendQueue,
+        clients: &Query<EntityRef, With<ConnectedClient>>,
     ) -> Result<()> {
         let mut message_bytes = Vec::new();
         unsafe { self.serialize::<M, I>(ctx, message, &mut message_bytes)? }
         let message_bytes: Bytes = message_bytes.into();
 
-        match *mode {
-            SendMode::Broadcast => {
-                for client_entity in clients {
-                    server_messages.send(client_entity, self.channel_id, message_bytes.clone());
+        match mode {
+            SendMode::Direct(client_id) => {
+                if let ClientId::Client(client) = *client_id
+                    && !superseded.contains(&client)
+                {
+                    send_queue.push(
+                        client,
+                        self.priority,
+                        self.channel_id,
+                        message_bytes.clone(),
+                    );
                 }
             }
-            SendMode::BroadcastExcept(ignored_id) => {
-                for client in clients {
-                    if ignored_id != client.into() {
-                        server_messages.send(client, self.channel_id, message_bytes.clone());
+            SendMode::List(client_ids) => {
+                for client in unique_clients(client_ids) {
+                    if clients.contains(client) && !superseded.contains(&client) {
+                        send_queue.push(
+                            client,
+                            self.priority,
+                            self.channel_id,
+                            message_bytes.clone(),
+                        );
                     }
                 }
             }
-            SendMode::Direct(client_id) => {
-                if let ClientId::Client(client) = client_id {
-                    server_messages.send(client, self.channel_id, message_bytes.clone());
+            _ => {
+                let recipients = clients
+                    .iter()
+                    .filter(|client| mode.includes(client))
+                    .filter(|client| !superseded.contains(&client.id()));
+                for client in recipients {
+                    send_queue.push(
+                        client.id(),
+                        self.priority,
+                        self.channel_id,
+                        message_bytes.clone(),
+                    );
                 }
             }
         }
@@ -402,6 +641,8 @@ impl ServerMessage {
 
     /// Buffers message `M` based on mode.
     ///
+    /// If `key` is set, the message will be coalesced with other buffered messages.
+    ///
     /// # Safety
     ///
     /// The caller must ensure that this instance was created for `M` and `I`.
@@ -412,10 +653,11 @@ impl ServerMessage {
         ctx: &mut ServerSendCtx,
         message: &M,
         mode: SendMode,
+        key: Option<u64>,
         message_buffer: &mut MessageBuffer,
     ) -> Result<()> {
         let message_bytes = unsafe { self.serialize_with_padding::<M, I>(ctx, message)? };
-        message_buffer.insert(mode, self.channel_id, message_bytes);
+        message_buffer.insert(mode, self.channel_id, self.priority, key, message_bytes);
         Ok(())
     }
 
@@ -547,20 +789,8 @@ impl ServerMessage {
         let messages: &mut Messages<M> = unsafe { messages.deref_mut() };
         for ToClients { message, mode } in to_messages.drain() {
             debug!("writing message `{}` locally", ShortName::of::<M>());
-            match mode {
-                SendMode::Broadcast => {
-                    messages.write(message);
-                }
-                SendMode::BroadcastExcept(ignored_id) => {
-                    if ignored_id != ClientId::Server {
-                        messages.write(message);
-                    }
-                }
-                SendMode::Direct(client_id) => {
-                    if client_id == ClientId::Server {
-                        messages.write(message);
-                    }
-                }
+            if mode.includes_server() {
+                messages.write(message);
             }
         }
     }
@@ -646,8 +876,8 @@ type SendOrBufferFn = unsafe fn(
     &ServerMessage,
     &mut ServerSendCtx,
     &Ptr,
-    &mut ServerMessages,
-    &Query<Entity, With<ConnectedClient>>,
+    &mut SendQueue,
+    &Query<EntityRef, With<ConnectedClient>>,
     &mut MessageBuffer,
 );
 
@@ -668,7 +898,7 @@ type SendLocallyFn = unsafe fn(PtrMut, PtrMut);
 type ResetFn = unsafe fn(PtrMut);
 
 /// A remote message that will be send to client(s).
-#[derive(Event, Message, Deref, DerefMut, Debug, Clone, Copy)]
+#[derive(Event, Message, Deref, DerefMut, Debug, Clone)]
 pub struct ToClients<T> {
     /// Recipients.
     pub mode: SendMode,
@@ -677,8 +907,79 @@ pub struct ToClients<T> {
     pub message: T,
 }
 
+/// Tracks the newest coalesced messages for each client.
+///
+/// Messages should be visited from newest to oldest.
+#[derive(Default)]
+struct Coalescer(HashSet<(Entity, usize, u64)>);
+
+impl Coalescer {
+    /// Collects recipients of a message for which a newer message with the same key was already visited.
+    ///
+    /// Returns `false` if the message is superseded for all its recipients.
+    fn supersede(
+        &mut self,
+        mode: &SendMode,
+        channel_id: usize,
+        key: u64,
+        clients: &Query<EntityRef, With<ConnectedClient>>,
+        superseded: &mut Vec<Entity>,
+    ) -> bool {
+        let mut recipients_left = false;
+        let mut visit = |client: Entity| {
+            if self.0.insert((client, channel_id, key)) {
+                recipients_left = true;
+            } else {
+                superseded.push(client);
+            }
+        };
+
+        match mode {
+            SendMode::Direct(client_id) => {
+                if let Some(client) = client_id
+                    .entity()
+                    .filter(|&client| clients.contains(client))
+                {
+                    visit(client);
+                }
+            }
+            SendMode::List(client_ids) => {
+                let recipients =
+                    unique_clients(client_ids).filter(|&client| clients.contains(client));
+                for client in recipients {
+                    visit(client);
+                }
+            }
+            _ => {
+                for client in clients.iter().filter(|client| mode.includes(client)) {
+                    visit(client.id());
+                }
+            }
+        }
+
+        recipients_left
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Iterates over entities of clients from [`SendMode::List`] skipping duplicates.
+///
+/// Lists are expected to be small, so duplicates are found by a linear search.
+fn unique_clients(client_ids: &[ClientId]) -> impl Iterator<Item = Entity> + '_ {
+    client_ids
+        .iter()
+        .enumerate()
+        .filter(|&(index, client_id)| !client_ids[..index].contains(client_id))
+        .filter_map(|(_, client_id)| client_id.entity())
+}
+
 /// Type of server message sending.
-#[derive(Clone, Copy, Debug)]
+///
+/// For all modes the message is serialized only once.
+#[derive(Clone, Debug)]
 pub enum SendMode {
     /// Send to every client.
     Broadcast,
@@ -686,6 +987,77 @@ pub enum SendMode {
     BroadcastExcept(ClientId),
     /// Send only to the specified client.
     Direct(ClientId),
+    /// Send only to the specified clients.
+    ///
+    /// Disconnected clients are ignored. Duplicate clients receive the message only once.
+    List(Vec<ClientId>),
+    /// Send to every client for which the entity is visible.
+    ///
+    /// Uses [`ClientVisibility`] and includes the server itself.
+    Visible(Entity),
+    /// Send to every client whose entity has a component with the specified [`TypeId`].
+    ///
+    /// Use [`Self::with_component`] to create it.
+    WithComponent(TypeId),
+}
+
+impl SendMode {
+    /// Creates [`Self::WithComponent`] for component `C`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bevy::prelude::*;
+    /// use bevy_replicon::prelude::*;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// fn notify_team(mut commands: Commands) {
+    ///     commands.server_trigger(ToClients {
+    ///         mode: SendMode::with_component::<RedTeam>(),
+    ///         message: RoundStarted,
+    ///     });
+    /// }
+    ///
+    /// /// Marker for client entities.
+    /// #[derive(Component)]
+    /// struct RedTeam;
+    ///
+    /// #[derive(Event, Serialize, Deserialize)]
+    /// struct RoundStarted;
+    /// ```
+    pub fn with_component<C: Component>() -> Self {
+        Self::WithComponent(TypeId::of::<C>())
+    }
+
+    /// Returns `true` if the server itself is a recipient.
+    fn includes_server(&self) -> bool {
+        match self {
+            SendMode::Broadcast | SendMode::Visible(_) => true,
+            SendMode::BroadcastExcept(ignored_id) => *ignored_id != ClientId::Server,
+            SendMode::Direct(client_id) => *client_id == ClientId::Server,
+            SendMode::List(client_ids) => client_ids.contains(&ClientId::Server),
+            SendMode::WithComponent(_) => false,
+        }
+    }
+
+    /// Returns `true` if the connected client is a recipient.
+    ///
+    /// For [`Self::Direct`] and [`Self::List`] it's faster to get clients by their IDs directly.
+    pub(crate) fn includes(&self, client: &EntityRef) -> bool {
+        match self {
+            SendMode::Broadcast => true,
+            SendMode::BroadcastExcept(ignored_id) => *ignored_id != client.id().into(),
+            SendMode::Direct(client_id) => *client_id == client.id().into(),
+            SendMode::List(client_ids) => client_ids.contains(&client.id().into()),
+            #[cfg(feature = "server")]
+            SendMode::Visible(entity) => client
+                .get::<crate::server::client_visibility::ClientVisibility>()
+                .is_some_and(|visibility| visibility.is_visible(*entity)),
+            #[cfg(not(feature = "server"))]
+            SendMode::Visible(_) => false,
+            SendMode::WithComponent(type_id) => client.contains_type_id(*type_id),
+        }
+    }
 }
 
 /// Default message serialization function.
@@ -5,6 +5,7 @@ use bytes::Bytes;
 use log::{debug, error};
 use postcard::experimental::{max_size::MaxSize, serialized_size};
 
+use super::{Coalescer, send_queue::SendQueue, unique_clients};
 use crate::{postcard_utils, prelude::*, shared::replication::client_ticks::ClientTicks};
 
 /// Caches synchronization-dependent server messages until they can be sent with an accurate update tick.
@@ -20,6 +21,11 @@ pub(crate) struct MessageBuffer {
     ///
     /// These are cleared before insertion.
     buffer: Vec<TickMessages>,
+
+    /// Cached set of the newest coalesced messages.
+    ///
+    /// Cleared before each sending.
+    coalescer: Coalescer,
 }
 
 impl MessageBuffer {
@@ -31,7 +37,14 @@ impl MessageBuffer {
         self.ticks.last_mut()
     }
 
-    pub(super) fn insert(&mut self, mode: SendMode, channel_id: usize, message: SerializedMessage) {
+    pub(super) fn insert(
+        &mut self,
+        mode: SendMode,
+        channel_id: usize,
+        priority: u32,
+        key: Option<u64>,
+        message: SerializedMessage,
+    ) {
         let buffer = self
             .active_tick()
             .expect("`start_tick` should be called before buffering");
@@ -39,6 +52,9 @@ impl MessageBuffer {
         buffer.messages.push(BufferedMessage {
             mode,
             channel_id,
+            priority,
+            key,
+            superseded: Vec::new(),
             message,
         });
     }
@@ -50,57 +66,92 @@ impl MessageBuffer {
         }
     }
 
+    /// Sends all buffered messages.
+    ///
+    /// Messages with a coalescing key are sent only to clients for which they are the newest
+    /// with this key among all buffered messages.
     pub(crate) fn send_all(
         &mut self,
-        messages: &mut ServerMessages,
-        clients: &Query<(Entity, Option<&ClientTicks>), With<ConnectedClient>>,
+        send_queue: &mut SendQueue,
+        clients: &Query<EntityRef, With<ConnectedClient>>,
     ) -> Result<()> {
+        self.coalescer.clear();
+        for tick in self.ticks.iter_mut().rev() {
+            for message in tick.messages.iter_mut().rev() {
+                if let Some(key) = message.key {
+                    self.coalescer.supersede(
+                        &message.mode,
+                        message.channel_id,
+                        key,
+                        clients,
+                        &mut message.superseded,
+                    );
+                }
+            }
+        }
+
         for mut tick in self.ticks.drain(..) {
-            for mut message in tick.messages.drain(..) {
-                match message.mode {
-                    SendMode::Broadcast => {
-                        for (client, ticks) in
-                            clients.iter().filter(|(e, _)| !tick.excluded.contains(e))
+            for BufferedMessage {
+                mode,
+                channel_id,
+                priority,
+                superseded,
+                mut message,
+                ..
+            } in tick.messages.drain(..)
+            {
+                match &mode {
+                    SendMode::Direct(client_id) => {
+                        if let ClientId::Client(client) = *client_id
+                            && let Ok(client) = clients.get(client)
+                            && !tick.excluded.contains(&client.id())
+                            && !superseded.contains(&client.id())
                         {
-                            if let Some(ticks) = ticks {
-                                message.send(messages, client, ticks)?;
+                            if let Some(ticks) = client.get::<ClientTicks>() {
+                                let bytes = message.get_bytes(ticks.update_tick())?;
+                                send_queue.push(client.id(), priority, channel_id, bytes);
                             } else {
-                                debug!(
-                                    "ignoring broadcast for channel {} for non-authorized client `{client}`",
-                                    message.channel_id
+                                error!(
+                                    "ignoring direct message for non-authorized client `{}`, \
+                                         mark it as independent to allow this",
+                                    client.id()
                                 );
                             }
                         }
                     }
-                    SendMode::BroadcastExcept(ignored_id) => {
-                        for (client, ticks) in
-                            clients.iter().filter(|(c, _)| !tick.excluded.contains(c))
-                        {
-                            if ignored_id == client.into() {
-                                continue;
-                            }
-
-                            if let Some(ticks) = ticks {
-                                message.send(messages, client, ticks)?;
+                    SendMode::List(client_ids) => {
+                        let recipients = unique_clients(client_ids)
+                            .filter(|client| !tick.excluded.contains(client))
+                            .filter(|client| !superseded.contains(client))
+                            .filter_map(|client| clients.get(client).ok());
+                        for client in recipients {
+                            if let Some(ticks) = client.get::<ClientTicks>() {
+                                let bytes = message.get_bytes(ticks.update_tick())?;
+                                send_queue.push(client.id(), priority, channel_id, bytes);
                             } else {
                                 debug!(
-                                    "ignoring broadcast except `{ignored_id}` for channel {} for non-authorized client `{client}`",
-                                    message.channel_id
+                                    "ignoring list for channel {} for non-authorized client `{}`",
+                                    channel_id,
+                                    client.id()
                                 );
                             }
                         }
                     }
-                    SendMode::Direct(client_id) => {
-                        if let ClientId::Client(client) = client_id
-                            && let Ok((_, ticks)) = clients.get(client)
-                            && !tick.excluded.contains(&client)
-                        {
-                            if let Some(ticks) = ticks {
-                                message.send(messages, client, ticks)?;
+                    mode => {
+                        let recipients = clients
+                            .iter()
+                            .filter(|client| !tick.excluded.contains(&client.id()))
+                            .filter(|client| !superseded.contains(&client.id()))
+                            .filter(|client| mode.includes(client));
+                        for client in recipients {
+                            if let Some(ticks) = client.get::<ClientTicks>() {
+                                let bytes = message.get_bytes(ticks.update_tick())?;
+                                send_queue.push(client.id(), priority, channel_id, bytes);
                             } else {
-                                error!(
-                                    "ignoring direct message for non-authorized client `{client}`, \
-                                         mark it as independent to allow this"
+                                debug!(
+                                    "ignoring `{mode:?}` for channel {} for non-authorized client `{}`",
+                                    channel_id,
+                                    client.id()
                                 );
                             }
                         }
@@ -138,20 +189,17 @@ impl TickMessages {
 struct BufferedMessage {
     mode: SendMode,
     channel_id: usize,
-    message: SerializedMessage,
-}
+    priority: u32,
 
-impl BufferedMessage {
-    fn send(
-        &mut self,
-        messages: &mut ServerMessages,
-        client_entity: Entity,
-        client: &ClientTicks,
-    ) -> Result<()> {
-        let message = self.message.get_bytes(client.update_tick())?;
-        messages.send(client_entity, self.channel_id, message);
-        Ok(())
-    }
+    /// Coalescing key of the message.
+    ///
+    /// See [`ServerMessageAppExt::coalesce_server_message_by`].
+    key: Option<u64>,
+
+    /// Clients for which a newer message with the same key is buffered.
+    superseded: Vec<Entity>,
+
+    message: SerializedMessage,
 }
 
 /// Cached message for use in [`MessageBuffer`].
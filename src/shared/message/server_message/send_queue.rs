@@ -0,0 +1,112 @@
+use core::cmp::Reverse;
+
+use bevy::{ecs::entity::hash_map::EntityHashMap, prelude::*};
+use bytes::Bytes;
+use log::trace;
+
+use crate::prelude::*;
+
+/// Server messages waiting to be passed to [`ServerMessages`].
+///
+/// Messages are sent in order of their priority within the per-client budget.
+/// Messages that don't fit are dropped on [`Channel::Unreliable`] and deferred to the next update
+/// on other channels.
+#[derive(Resource, Default)]
+pub(crate) struct SendQueue {
+    /// Maximum number of bytes sent to each client per update.
+    budget: Option<usize>,
+
+    clients: EntityHashMap<Vec<QueuedMessage>>,
+
+    /// Cached buffer for channels that have deferred messages.
+    ///
+    /// Cleared for each client.
+    deferred_channels: Vec<usize>,
+}
+
+#[cfg_attr(
+    not(feature = "server"),
+    expect(dead_code, reason = "messages are sent only on server")
+)]
+impl SendQueue {
+    pub(crate) fn new(budget: Option<usize>) -> Self {
+        Self {
+            budget,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        client: Entity,
+        priority: u32,
+        channel_id: usize,
+        message: Bytes,
+    ) {
+        self.clients.entry(client).or_default().push(QueuedMessage {
+            priority,
+            channel_id,
+            message,
+        });
+    }
+
+    /// Sends queued messages in order of their priority.
+    ///
+    /// At least one message is sent to each client to avoid stalling on messages larger than the budget.
+    /// To preserve the order, all messages after the deferred one on the same channel are deferred too.
+    pub(crate) fn flush(
+        &mut self,
+        server_messages: &mut ServerMessages,
+        channels: &RepliconChannels,
+    ) {
+        for (&client, messages) in &mut self.clients {
+            // Stable sort to keep the order of messages with the same priority.
+            messages.sort_by_key(|message| Reverse(message.priority));
+
+            let mut sent_bytes = 0;
+            self.deferred_channels.clear();
+            messages.retain(|message| {
+                let fits = self.budget.is_none_or(|budget| {
+                    sent_bytes == 0 || sent_bytes + message.message.len() <= budget
+                });
+                if fits && !self.deferred_channels.contains(&message.channel_id) {
+                    sent_bytes += message.message.len();
+                    server_messages.send(client, message.channel_id, message.message.clone());
+                    return false;
+                }
+
+                let backend_id = channels.server_routes().backend_id(message.channel_id);
+                if channels.server_channels()[backend_id] == Channel::Unreliable {
+                    trace!(
+                        "dropping message with priority {} for channel {} for client `{client}` due to exceeded budget",
+                        message.priority, message.channel_id
+                    );
+                    return false;
+                }
+
+                trace!(
+                    "deferring message with priority {} for channel {} for client `{client}` due to exceeded budget",
+                    message.priority, message.channel_id
+                );
+                if !self.deferred_channels.contains(&message.channel_id) {
+                    self.deferred_channels.push(message.channel_id);
+                }
+                true
+            });
+        }
+    }
+
+    pub(crate) fn remove_client(&mut self, client: Entity) {
+        self.clients.remove(&client);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.clients.clear();
+    }
+}
+
+struct QueuedMessage {
+    priority: u32,
+    channel_id: usize,
+    message: Bytes,
+}
@@ -1,3 +1,5 @@
+pub mod versioning;
+
 use core::{
     any,
     fmt::Debug,
@@ -88,6 +90,11 @@ impl ProtocolHasher {
         self.hash::<E>(ProtocolPart::ServerEvent);
     }
 
+    pub(crate) fn add_entity_event<E>(&mut self) {
+        debug!("using entity target for `{}`", ShortName::of::<E>());
+        self.hash::<E>(ProtocolPart::EntityEvent);
+    }
+
     pub(crate) fn make_message_independent<E>(&mut self) {
         debug!("making message `{}` independent", ShortName::of::<E>());
         self.hash::<E>(ProtocolPart::IndependentMessage);
@@ -103,6 +110,17 @@ impl ProtocolHasher {
         ProtocolPart::TrackMutateMessages.hash(&mut self.0);
     }
 
+    pub(crate) fn share_channel(&mut self, name: &str) {
+        debug!("using shared channel `{name}`");
+        ProtocolPart::SharedChannel.hash(&mut self.0);
+        name.hash(&mut self.0);
+    }
+
+    pub(crate) fn add_checksum<C>(&mut self) {
+        debug!("adding checksum for `{}`", ShortName::of::<C>());
+        self.hash::<C>(ProtocolPart::Checksum);
+    }
+
     fn hash<T>(&mut self, part: ProtocolPart) {
         part.hash(&mut self.0);
         any::type_name::<T>().hash(&mut self.0);
@@ -133,6 +151,9 @@ enum ProtocolPart {
     IndependentMessage,
     IndependentEvent,
     TrackMutateMessages,
+    Checksum,
+    SharedChannel,
+    EntityEvent,
 }
 
 /// Hash of all registered events and replication rules.
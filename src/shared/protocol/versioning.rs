@@ -0,0 +1,584 @@
+use core::{
+    any::TypeId,
+    hash::{Hash, Hasher},
+    ops::{RangeFrom, RangeFull, RangeInclusive},
+};
+
+use alloc::vec::Vec;
+use bevy::{platform::collections::HashMap, prelude::*};
+use deterministic_hash::DeterministicHasher;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::Xxh3Default;
+
+use crate::shared::{
+    backend::{
+        channels::{ClientChannel, ServerChannel},
+        server_messages::ChannelMap,
+    },
+    message::registry::RemoteMessageRegistry,
+    replication::registry::{FnsId, ReplicationRegistry},
+};
+
+/**
+Stable IDs and compatible versions for protocol registrations.
+
+Available only if [`RepliconSharedPlugin::protocol_version`](crate::shared::RepliconSharedPlugin::protocol_version)
+is set. In this mode, [`AuthMethod::ProtocolCheck`](crate::shared::AuthMethod::ProtocolCheck) negotiates
+the common subset of registrations between the client and server instead of requiring an exact
+[`ProtocolHash`](super::ProtocolHash) match. This allows changing the registration order or rolling out
+new components and messages without forcing all clients to update at once.
+
+By default, each registration is identified by its type name and is required on both sides.
+Use [`Self::set`] to assign an ID that survives type renames, restrict compatible peer versions,
+or mark the registration as optional. Optional registrations missing on the other side are skipped:
+the server doesn't send such components or messages to the client and ignores such messages from it.
+If a required registration is missing or incompatible, the client will be notified with a
+[`ProtocolMismatch`](super::ProtocolMismatch) event and disconnected.
+
+Stable IDs are used only for negotiation. Components are still serialized using compact
+registration indices, and the server sends [`ProtocolAccepted`] with the mapping to the client's
+registrations. The client holds replication messages until it receives the mapping.
+
+Only available during the [`Plugin::build`] stage. Computes [`ProtocolManifest`] resource.
+
+# Examples
+
+```
+use bevy::{prelude::*, state::app::StatesPlugin};
+use bevy_replicon::{prelude::*, shared::protocol::versioning::VersionedEntry};
+use serde::{Deserialize, Serialize};
+
+let mut app = App::new();
+app.add_plugins((
+    MinimalPlugins,
+    StatesPlugin,
+    RepliconPlugins.set(RepliconSharedPlugin {
+        protocol_version: Some(3),
+        ..Default::default()
+    }),
+))
+.replicate::<Health>()
+.replicate::<Stamina>()
+.add_client_message::<Emote>(Channel::Ordered);
+
+// Should be called before `app.run()` or `app.finish()`.
+let mut versioning = app.world_mut().resource_mut::<ProtocolVersioning>();
+versioning.set::<Health>(VersionedEntry::new(1));
+// Added in version 3, so older clients will replicate entities without it.
+versioning.set::<Stamina>(VersionedEntry::new(2).with_versions(3..).optional());
+// Older clients will just not be able to send it.
+versioning.set::<Emote>(VersionedEntry::new(1).optional());
+
+#[derive(Component, Serialize, Deserialize)]
+struct Health(u32);
+
+#[derive(Component, Serialize, Deserialize)]
+struct Stamina(u32);
+
+#[derive(Message, Serialize, Deserialize)]
+struct Emote;
+```
+**/
+#[derive(Resource)]
+pub struct ProtocolVersioning {
+    /// Version of the local protocol.
+    version: u32,
+
+    /// Entries assigned by the user.
+    entries: HashMap<TypeId, VersionedEntry>,
+}
+
+impl ProtocolVersioning {
+    pub(crate) fn new(version: u32) -> Self {
+        Self {
+            version,
+            entries: Default::default(),
+        }
+    }
+
+    /// Returns the version of the local protocol.
+    ///
+    /// See also [`RepliconSharedPlugin::protocol_version`](crate::shared::RepliconSharedPlugin::protocol_version).
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Assigns an entry for all registrations that use `T`.
+    ///
+    /// For replication, it applies to the component. If the component is registered by
+    /// multiple rules with different functions, the IDs for subsequent registrations
+    /// are derived from the assigned ID.
+    pub fn set<T: 'static>(&mut self, entry: VersionedEntry) {
+        debug!("assigning `{entry:?}` to `{}`", ShortName::of::<T>());
+        self.entries.insert(TypeId::of::<T>(), entry);
+    }
+
+    /// Returns the assigned entry or creates a default one from the type name.
+    fn entry(
+        &self,
+        part: VersionedPart,
+        type_id: TypeId,
+        type_name: &str,
+        occurrence: usize,
+    ) -> VersionedEntry {
+        let mut entry = self.entries.get(&type_id).copied().unwrap_or_else(|| {
+            let mut hasher = DeterministicHasher::<Xxh3Default>::default();
+            part.hash(&mut hasher);
+            type_name.hash(&mut hasher);
+            VersionedEntry::new(hasher.finish())
+        });
+
+        if occurrence != 0 {
+            let mut hasher = DeterministicHasher::<Xxh3Default>::default();
+            entry.id.hash(&mut hasher);
+            (occurrence as u64).hash(&mut hasher);
+            entry.id = hasher.finish();
+        }
+
+        entry
+    }
+
+    /// Builds the manifest from all registrations.
+    pub(crate) fn finish(
+        self,
+        replication_registry: &ReplicationRegistry,
+        message_registry: &RemoteMessageRegistry,
+        track_mutate_messages: bool,
+    ) -> ProtocolManifest {
+        let mut occurrences = HashMap::<TypeId, usize>::default();
+        let components: Vec<_> = replication_registry
+            .iter_rule_types()
+            .map(|(type_id, type_name)| {
+                let occurrence = occurrences.entry(type_id).or_default();
+                let entry = self.entry(VersionedPart::Component, type_id, type_name, *occurrence);
+                *occurrence += 1;
+                entry
+            })
+            .collect();
+
+        let client_messages = message_registry
+            .iter_client_types()
+            .map(|(is_event, type_id, type_name, channel_id)| {
+                let part = if is_event {
+                    VersionedPart::ClientEvent
+                } else {
+                    VersionedPart::ClientMessage
+                };
+                (channel_id, self.entry(part, type_id, type_name, 0))
+            })
+            .collect();
+
+        let server_messages = message_registry
+            .iter_server_types()
+            .map(|(is_event, type_id, type_name, channel_id)| {
+                let part = if is_event {
+                    VersionedPart::ServerEvent
+                } else {
+                    VersionedPart::ServerMessage
+                };
+                (channel_id, self.entry(part, type_id, type_name, 0))
+            })
+            .collect();
+
+        let manifest = ProtocolManifest {
+            version: self.version,
+            track_mutate_messages,
+            components,
+            client_messages,
+            server_messages,
+        };
+
+        for entries in [
+            manifest.components.iter().collect::<Vec<_>>(),
+            manifest.client_messages.iter().map(|(_, e)| e).collect(),
+            manifest.server_messages.iter().map(|(_, e)| e).collect(),
+        ] {
+            for (index, entry) in entries.iter().enumerate() {
+                if entries[..index].iter().any(|other| other.id == entry.id) {
+                    panic!("stable ID {} is assigned more than once", entry.id);
+                }
+            }
+        }
+
+        debug!("created manifest for version {}", manifest.version);
+        manifest
+    }
+}
+
+/// Stable ID and compatibility settings for a protocol registration.
+///
+/// See [`ProtocolVersioning`] for more details.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VersionedEntry {
+    /// ID that identifies the registration on both sides.
+    ///
+    /// Should be unique among all components, client messages or server messages.
+    pub id: u64,
+
+    /// Versions of the other side's protocol with which this registration is compatible.
+    ///
+    /// Both sides should consider each other compatible.
+    pub versions: VersionRange,
+
+    /// Whether the registration can be skipped if it's missing or incompatible on the other side.
+    pub optional: bool,
+}
+
+impl VersionedEntry {
+    /// Creates a new required entry with the given ID that is compatible with all versions.
+    pub fn new(id: u64) -> Self {
+        Self {
+            id,
+            versions: Default::default(),
+            optional: false,
+        }
+    }
+
+    /// Sets [`Self::versions`].
+    #[must_use]
+    pub fn with_versions(mut self, versions: impl Into<VersionRange>) -> Self {
+        self.versions = versions.into();
+        self
+    }
+
+    /// Sets [`Self::optional`] to `true`.
+    #[must_use]
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+
+    /// Returns `true` if both entries consider each other compatible.
+    fn compatible(&self, version: u32, other: &Self, other_version: u32) -> bool {
+        self.versions.contains(other_version) && other.versions.contains(version)
+    }
+}
+
+/// Inclusive range of protocol versions.
+///
+/// Can be constructed from `a..=b`, `a..` or `..`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VersionRange {
+    /// Minimum supported version.
+    pub min: u32,
+
+    /// Maximum supported version.
+    pub max: u32,
+}
+
+impl VersionRange {
+    /// Returns `true` if the version is within the range.
+    pub fn contains(&self, version: u32) -> bool {
+        (self.min..=self.max).contains(&version)
+    }
+}
+
+impl Default for VersionRange {
+    fn default() -> Self {
+        Self {
+            min: 0,
+            max: u32::MAX,
+        }
+    }
+}
+
+impl From<RangeInclusive<u32>> for VersionRange {
+    fn from(value: RangeInclusive<u32>) -> Self {
+        Self {
+            min: *value.start(),
+            max: *value.end(),
+        }
+    }
+}
+
+impl From<RangeFrom<u32>> for VersionRange {
+    fn from(value: RangeFrom<u32>) -> Self {
+        Self {
+            min: value.start,
+            max: u32::MAX,
+        }
+    }
+}
+
+impl From<RangeFull> for VersionRange {
+    fn from(_value: RangeFull) -> Self {
+        Self::default()
+    }
+}
+
+/// Versioned protocol description.
+///
+/// Sent by the client instead of [`ProtocolHash`](super::ProtocolHash) when
+/// [`RepliconSharedPlugin::protocol_version`](crate::shared::RepliconSharedPlugin::protocol_version) is set.
+///
+/// Calculated by [`ProtocolVersioning`] and available only after [`Plugin::finish`].
+#[derive(Resource, Event, Serialize, Deserialize, Clone, Debug)]
+pub struct ProtocolManifest {
+    version: u32,
+    track_mutate_messages: bool,
+
+    /// Entries for each [`FnsId`].
+    components: Vec<VersionedEntry>,
+
+    /// Entries for client messages and events with their channel IDs.
+    client_messages: Vec<(usize, VersionedEntry)>,
+
+    /// Entries for server messages and events with their channel IDs.
+    server_messages: Vec<(usize, VersionedEntry)>,
+}
+
+impl ProtocolManifest {
+    /// Returns the protocol version.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Negotiates the common subset with the client's manifest.
+    ///
+    /// Should be called on the server. Returns [`None`] if the client is incompatible.
+    pub(crate) fn negotiate(
+        &self,
+        client: &Self,
+    ) -> Option<(NegotiatedProtocol, ProtocolAccepted, ChannelMap)> {
+        if self.track_mutate_messages != client.track_mutate_messages {
+            debug!("mutate messages tracking doesn't match");
+            return None;
+        }
+
+        let components = match_entries(
+            self.version,
+            self.components.iter(),
+            client.version,
+            client.components.iter(),
+        )
+        .inspect_err(|id| debug!("component with ID {id} is incompatible"))
+        .ok()?;
+
+        let client_messages = match_entries(
+            self.version,
+            self.client_messages.iter().map(|(_, entry)| entry),
+            client.version,
+            client.client_messages.iter().map(|(_, entry)| entry),
+        )
+        .inspect_err(|id| debug!("client message with ID {id} is incompatible"))
+        .ok()?;
+
+        let server_messages = match_entries(
+            self.version,
+            self.server_messages.iter().map(|(_, entry)| entry),
+            client.version,
+            client.server_messages.iter().map(|(_, entry)| entry),
+        )
+        .inspect_err(|id| debug!("server message with ID {id} is incompatible"))
+        .ok()?;
+
+        let receive_len = client
+            .client_messages
+            .iter()
+            .map(|&(channel_id, _)| channel_id + 1)
+            .max()
+            .unwrap_or_default()
+            .max(ClientChannel::MutationAcks as usize + 1);
+        let mut receive = vec![None; receive_len];
+        let acks_channel: usize = ClientChannel::MutationAcks.into();
+        receive[acks_channel] = Some(acks_channel);
+        for (&(channel_id, _), client_index) in self.client_messages.iter().zip(client_messages) {
+            if let Some(client_index) = client_index {
+                let (client_channel, _) = client.client_messages[client_index];
+                receive[client_channel] = Some(channel_id);
+            }
+        }
+
+        let send_len = self
+            .server_messages
+            .iter()
+            .map(|&(channel_id, _)| channel_id + 1)
+            .max()
+            .unwrap_or_default()
+            .max(ServerChannel::Mutations as usize + 1);
+        let mut send = vec![None; send_len];
+        for channel in [ServerChannel::Updates, ServerChannel::Mutations] {
+            let channel_id: usize = channel.into();
+            send[channel_id] = Some(channel_id);
+        }
+        for (&(channel_id, _), client_index) in self.server_messages.iter().zip(server_messages) {
+            if let Some(client_index) = client_index {
+                let (client_channel, _) = client.server_messages[client_index];
+                send[channel_id] = Some(client_channel);
+            }
+        }
+
+        let negotiated = NegotiatedProtocol {
+            components: components.iter().map(Option::is_some).collect(),
+        };
+
+        Some((
+            negotiated,
+            ProtocolAccepted { components },
+            ChannelMap::new(receive, send),
+        ))
+    }
+}
+
+/// Matches local entries with remote by their IDs.
+///
+/// Returns the index of the compatible remote entry for each local entry
+/// or the ID of the first required entry without a compatible pair.
+fn match_entries<'a>(
+    local_version: u32,
+    local: impl Iterator<Item = &'a VersionedEntry>,
+    remote_version: u32,
+    remote: impl Iterator<Item = &'a VersionedEntry>,
+) -> Result<Vec<Option<usize>>, u64> {
+    let remote: Vec<_> = remote.collect();
+    let remote_indices: HashMap<_, _> = remote
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| (entry.id, index))
+        .collect();
+
+    let mut matched = Vec::new();
+    for local_entry in local {
+        let remote_index = remote_indices.get(&local_entry.id).copied();
+        let remote_entry = remote_index.map(|index| remote[index]);
+        match remote_entry {
+            Some(remote_entry)
+                if local_entry.compatible(local_version, remote_entry, remote_version) =>
+            {
+                matched.push(remote_index);
+            }
+            _ => {
+                if !local_entry.optional || remote_entry.is_some_and(|entry| !entry.optional) {
+                    return Err(local_entry.id);
+                }
+                matched.push(None);
+            }
+        }
+    }
+
+    let mut remote_matched = vec![false; remote.len()];
+    for &remote_index in matched.iter().flatten() {
+        remote_matched[remote_index] = true;
+    }
+    for (remote_entry, matched) in remote.into_iter().zip(remote_matched) {
+        if !remote_entry.optional && !matched {
+            return Err(remote_entry.id);
+        }
+    }
+
+    Ok(matched)
+}
+
+/// Common subset of the protocol negotiated with a client.
+///
+/// Inserted on the client entity on the server when
+/// [`RepliconSharedPlugin::protocol_version`](crate::shared::RepliconSharedPlugin::protocol_version)
+/// is set and the client is compatible.
+#[derive(Component)]
+pub struct NegotiatedProtocol {
+    /// Support flag for each [`FnsId`].
+    components: Vec<bool>,
+}
+
+impl NegotiatedProtocol {
+    /// Returns `true` if the client supports the replication functions.
+    pub fn supports_component(&self, fns_id: FnsId) -> bool {
+        self.components
+            .get(fns_id.index())
+            .copied()
+            .unwrap_or(false)
+    }
+}
+
+/// A server event with the mapping of the server's replication functions to the client's.
+///
+/// Sent to the client after a successful negotiation in the versioned protocol mode.
+/// The client holds replication messages until it receives this event.
+#[derive(Event, Serialize, Deserialize, Clone, Debug)]
+pub struct ProtocolAccepted {
+    /// Index of the client's [`FnsId`] for each server's [`FnsId`].
+    components: Vec<Option<usize>>,
+}
+
+/// Mapping of the server's [`FnsId`] to the client's, received from [`ProtocolAccepted`].
+///
+/// Present on the client only in the versioned protocol mode after the negotiation.
+#[derive(Resource)]
+pub(crate) struct ServerFnsMap(Vec<Option<FnsId>>);
+
+impl ServerFnsMap {
+    pub(crate) fn new(registry: &ReplicationRegistry, accepted: &ProtocolAccepted) -> Self {
+        Self(
+            accepted
+                .components
+                .iter()
+                .map(|index| index.map(|index| registry.fns_id(index)))
+                .collect(),
+        )
+    }
+
+    /// Returns the client's functions ID for the server's.
+    ///
+    /// Returns [`None`] if the client doesn't have a compatible registration.
+    pub(crate) fn get(&self, fns_id: FnsId) -> Option<FnsId> {
+        self.0.get(fns_id.index()).copied().flatten()
+    }
+}
+
+/// Part of versioned registration.
+///
+/// Used to calculate default IDs, the same type can be used for different registrations.
+#[derive(Hash)]
+#[repr(u8)]
+enum VersionedPart {
+    Component,
+    ClientMessage,
+    ClientEvent,
+    ServerMessage,
+    ServerEvent,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optional_missing() {
+        let local = [VersionedEntry::new(0), VersionedEntry::new(1).optional()];
+        let remote = [VersionedEntry::new(0)];
+
+        let matched = match_entries(0, local.iter(), 0, remote.iter()).unwrap();
+        assert_eq!(matched, [Some(0), None]);
+    }
+
+    #[test]
+    fn required_missing() {
+        let local = [VersionedEntry::new(0)];
+        let remote = [VersionedEntry::new(0), VersionedEntry::new(1)];
+
+        assert_eq!(match_entries(0, local.iter(), 0, remote.iter()), Err(1));
+        assert_eq!(match_entries(0, remote.iter(), 0, local.iter()), Err(1));
+    }
+
+    #[test]
+    fn different_order() {
+        let local = [VersionedEntry::new(0), VersionedEntry::new(1)];
+        let remote = [VersionedEntry::new(1), VersionedEntry::new(0)];
+
+        let matched = match_entries(0, local.iter(), 0, remote.iter()).unwrap();
+        assert_eq!(matched, [Some(1), Some(0)]);
+    }
+
+    #[test]
+    fn incompatible_versions() {
+        let local = [VersionedEntry::new(0).with_versions(2..).optional()];
+        let remote = [VersionedEntry::new(0).optional()];
+
+        let matched = match_entries(2, local.iter(), 1, remote.iter()).unwrap();
+        assert_eq!(matched, [None]);
+
+        let matched = match_entries(2, local.iter(), 2, remote.iter()).unwrap();
+        assert_eq!(matched, [Some(0)]);
+    }
+}
@@ -1,8 +1,10 @@
+pub mod checksum;
 pub mod client_ticks;
 pub mod command_markers;
 pub mod deferred_entity;
 pub(crate) mod mutate_index;
 pub mod registry;
+pub mod relationship_order;
 pub mod rules;
 pub mod signature;
 pub mod track_mutate_messages;
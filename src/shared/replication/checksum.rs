@@ -0,0 +1,341 @@
+use core::hash::{Hash, Hasher};
+
+use bevy::{ecs::component::ComponentId, prelude::*};
+use bytes::Bytes;
+use deterministic_hash::DeterministicHasher;
+use log::debug;
+use xxhash_rust::xxh3::Xxh3Default;
+
+use super::track_mutate_messages::TrackAppExt;
+#[cfg(feature = "client")]
+use crate::client::{confirm_history::ConfirmHistory, server_mutate_ticks::ServerMutateTicks};
+#[cfg(feature = "server")]
+use crate::server::server_tick::ServerTick;
+use crate::{
+    postcard_utils,
+    prelude::*,
+    shared::message::ctx::{ClientReceiveCtx, ServerSendCtx},
+};
+
+/// Desync detection for replicated components.
+pub trait ChecksumAppExt {
+    /**
+    Includes component `C` into periodic replication checksums.
+
+    Every [`Checksums::interval`] ticks the server hashes registered components of all
+    replicated entities visible to a client and sends the hashes to it. The client hashes the same
+    components on its side and triggers [`ReplicationDesync`] for entities whose hashes don't match.
+
+    The client compares a checksum only after it received all changes and mutations for its tick.
+    To know when all mutations arrived, this method also enables
+    [`TrackAppExt::track_mutate_messages`].
+    Entities with a newer state on the client are skipped.
+
+    Hashes are computed via [`Hash`], so the implementation should be deterministic across platforms.
+    The component should be registered on both sides in the same order.
+
+    Checksums are sent only if [`Checksums`] is present, which is inserted on the first call of this method.
+
+    # Examples
+
+    ```
+    # use bevy::state::app::StatesPlugin;
+    use bevy::prelude::*;
+    use bevy_replicon::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    # let mut app = App::new();
+    # app.add_plugins((StatesPlugin, RepliconPlugins));
+    app.replicate::<Health>().add_checksum::<Health>();
+
+    #[derive(Component, Serialize, Deserialize, Hash)]
+    struct Health(u32);
+    ```
+    **/
+    fn add_checksum<C: Component + Hash>(&mut self) -> &mut Self;
+}
+
+impl ChecksumAppExt for App {
+    fn add_checksum<C: Component + Hash>(&mut self) -> &mut Self {
+        self.world_mut()
+            .resource_mut::<ProtocolHasher>()
+            .add_checksum::<C>();
+
+        if !self.world().contains_resource::<Checksums>() {
+            debug!("enabling replication checksums");
+            self.track_mutate_messages()
+                .init_resource::<Checksums>()
+                .add_server_message_with(
+                    Channel::Unordered,
+                    serialize_checksums,
+                    deserialize_checksums,
+                );
+
+            #[cfg(feature = "server")]
+            self.add_systems(
+                PostUpdate,
+                send_checksums
+                    .after(ServerSystems::IncrementTick)
+                    .before(ServerSystems::Send)
+                    .run_if(in_state(ServerState::Running).and(resource_changed::<ServerTick>)),
+            );
+
+            #[cfg(feature = "client")]
+            self.init_resource::<PendingChecksums>()
+                .add_systems(
+                    PreUpdate,
+                    compare_checksums
+                        .after(ClientSystems::Receive)
+                        .run_if(in_state(ClientState::Connected)),
+                )
+                .add_systems(OnExit(ClientState::Connected), reset);
+        }
+
+        let component_id = self.world_mut().register_component::<C>();
+        self.world_mut()
+            .resource_mut::<Checksums>()
+            .fns
+            .push(ChecksumFn {
+                component_id,
+                hash: hash::<C>,
+            });
+
+        self
+    }
+}
+
+/// Components registered via [`ChecksumAppExt`].
+///
+/// Inserted on the first call of [`ChecksumAppExt::add_checksum`].
+/// Checksums are sent only if the resource is present.
+#[derive(Resource)]
+pub struct Checksums {
+    /// Number of server ticks between checksums.
+    ///
+    /// Set to `0` to pause sending.
+    ///
+    /// By default set to `60`.
+    pub interval: u32,
+    fns: Vec<ChecksumFn>,
+}
+
+impl Checksums {
+    /// Hashes all registered components of the entity.
+    ///
+    /// Returns [`None`] for components missing on the entity.
+    fn hash(&self, entity: &EntityRef) -> Vec<Option<u64>> {
+        self.fns
+            .iter()
+            .map(|checksum| (checksum.hash)(entity))
+            .collect()
+    }
+}
+
+impl Default for Checksums {
+    fn default() -> Self {
+        Self {
+            interval: 60,
+            fns: Default::default(),
+        }
+    }
+}
+
+struct ChecksumFn {
+    component_id: ComponentId,
+    hash: HashFn,
+}
+
+/// Signature of component hashing functions.
+type HashFn = fn(&EntityRef) -> Option<u64>;
+
+fn hash<C: Component + Hash>(entity: &EntityRef) -> Option<u64> {
+    let component = entity.get::<C>()?;
+    let mut hasher = DeterministicHasher::<Xxh3Default>::default();
+    component.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// An event that indicates that replicated components on the client differ from the server.
+///
+/// Triggered on the client for each received checksum message with mismatches.
+/// See [`ChecksumAppExt::add_checksum`].
+#[cfg(feature = "client")]
+#[derive(Event, Debug, Clone)]
+pub struct ReplicationDesync {
+    /// Server tick for which the checksums were calculated.
+    pub tick: RepliconTick,
+
+    /// Entities with mismatched components.
+    pub entities: Vec<DesyncedEntity>,
+}
+
+/// A client entity whose components don't match the server.
+///
+/// See [`ReplicationDesync`].
+#[cfg(feature = "client")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesyncedEntity {
+    /// Entity on the client.
+    pub entity: Entity,
+
+    /// Components whose hashes don't match, including components
+    /// that present only on one side.
+    pub components: Vec<ComponentId>,
+}
+
+/// Hashes of registered components for entities visible to a client.
+///
+/// Entities are stored as server entities on the server and as client entities on the client.
+#[derive(Message, Clone)]
+struct EntityChecksums {
+    tick: RepliconTick,
+    entities: Vec<(Entity, Vec<Option<u64>>)>,
+}
+
+fn serialize_checksums(
+    _ctx: &mut ServerSendCtx,
+    checksums: &EntityChecksums,
+    message: &mut Vec<u8>,
+) -> Result<()> {
+    postcard_utils::to_extend_mut(&checksums.tick, message)?;
+    postcard_utils::to_extend_mut(&checksums.entities.len(), message)?;
+    for (entity, hashes) in &checksums.entities {
+        postcard_utils::entity_to_extend_mut(entity, message)?;
+        postcard_utils::to_extend_mut(hashes, message)?;
+    }
+
+    Ok(())
+}
+
+/// Deserializes checksums and maps entities.
+///
+/// Unlike regular messages, entities that aren't mapped are skipped
+/// since the server might have spawned them after the last update.
+fn deserialize_checksums(
+    ctx: &mut ClientReceiveCtx,
+    message: &mut Bytes,
+) -> Result<EntityChecksums> {
+    let tick = postcard_utils::from_buf(message)?;
+    let len: usize = postcard_utils::from_buf(message)?;
+    let mut entities = Vec::with_capacity(len);
+    for _ in 0..len {
+        let server_entity = postcard_utils::entity_from_buf(message)?;
+        let hashes = postcard_utils::from_buf(message)?;
+        if let Some(&client_entity) = ctx.entity_map.to_client().get(&server_entity) {
+            entities.push((client_entity, hashes));
+        }
+    }
+
+    Ok(EntityChecksums { tick, entities })
+}
+
+#[cfg(feature = "server")]
+fn send_checksums(
+    mut checksum_messages: MessageWriter<ToClients<EntityChecksums>>,
+    checksums: Res<Checksums>,
+    server_tick: Res<ServerTick>,
+    entities: Query<EntityRef, With<Replicated>>,
+    clients: Query<(Entity, &ClientVisibility), With<AuthorizedClient>>,
+) {
+    if checksums.interval == 0 || !server_tick.get().is_multiple_of(checksums.interval) {
+        return;
+    }
+
+    let hashes: Vec<_> = entities
+        .iter()
+        .map(|entity| (entity.id(), checksums.hash(&entity)))
+        .filter(|(_, hashes)| hashes.iter().any(Option::is_some))
+        .collect();
+
+    for (client, visibility) in &clients {
+        let entities = hashes
+            .iter()
+            .filter(|&&(entity, _)| visibility.is_visible(entity))
+            .cloned()
+            .collect();
+
+        debug!(
+            "sending checksums for tick {:?} to `{client}`",
+            **server_tick
+        );
+        checksum_messages.write(ToClients {
+            mode: SendMode::Direct(ClientId::Client(client)),
+            message: EntityChecksums {
+                tick: **server_tick,
+                entities,
+            },
+        });
+    }
+}
+
+/// Checksums that wait for all changes and mutations from their tick.
+#[cfg(feature = "client")]
+#[derive(Resource, Default, Deref, DerefMut)]
+struct PendingChecksums(Vec<EntityChecksums>);
+
+#[cfg(feature = "client")]
+fn compare_checksums(
+    mut commands: Commands,
+    mut checksum_messages: MessageReader<EntityChecksums>,
+    mut pending: ResMut<PendingChecksums>,
+    checksums: Res<Checksums>,
+    mutate_ticks: Res<ServerMutateTicks>,
+    entities: Query<EntityRef>,
+) {
+    pending.extend(checksum_messages.read().cloned());
+
+    pending.retain(|server_checksums| {
+        // Mutate messages are applied only after the update message they depend on,
+        // so a confirmed tick also means that all changes up to it were received.
+        if !mutate_ticks.contains(server_checksums.tick) {
+            return true;
+        }
+
+        let mut desynced = Vec::new();
+        for (entity, server_hashes) in &server_checksums.entities {
+            let Ok(entity) = entities.get(*entity) else {
+                continue;
+            };
+            if entity
+                .get::<ConfirmHistory>()
+                .is_some_and(|history| history.last_tick() > server_checksums.tick)
+            {
+                continue;
+            }
+
+            let client_hashes = checksums.hash(&entity);
+            let components: Vec<_> = checksums
+                .fns
+                .iter()
+                .zip(client_hashes.iter().zip(server_hashes))
+                .filter(|(_, (client_hash, server_hash))| client_hash != server_hash)
+                .map(|(checksum, _)| checksum.component_id)
+                .collect();
+            if !components.is_empty() {
+                desynced.push(DesyncedEntity {
+                    entity: entity.id(),
+                    components,
+                });
+            }
+        }
+
+        if !desynced.is_empty() {
+            debug!(
+                "detected desync for {} entities at tick {:?}",
+                desynced.len(),
+                server_checksums.tick
+            );
+            commands.trigger(ReplicationDesync {
+                tick: server_checksums.tick,
+                entities: desynced,
+            });
+        }
+
+        false
+    });
+}
+
+#[cfg(feature = "client")]
+fn reset(mut pending: ResMut<PendingChecksums>) {
+    pending.clear();
+}
@@ -3,7 +3,7 @@ use core::{mem, time::Duration};
 use bevy::{
     ecs::{
         component::{CheckChangeTicks, Tick},
-        entity::hash_map::EntityHashMap,
+        entity::{hash_map::EntityHashMap, hash_set::EntityHashSet},
     },
     platform::collections::HashMap,
     prelude::*,
@@ -33,6 +33,27 @@ pub struct ClientTicks {
     ///
     /// See also [`Self::register_mutate_message`].
     mutate_index: MutateIndex,
+
+    /// Entities with mutations that weren't acknowledged by the client yet.
+    ///
+    /// Used only with `ChangeCollection::Dirty`
+    /// to revisit these entities until the client acknowledges them.
+    pending: EntityHashSet,
+
+    /// Indicates that all replicated entities were visited for this client at least once.
+    ///
+    /// Used only with `ChangeCollection::Dirty`.
+    scanned: bool,
+
+    /// Number of entities sent during the initial sync.
+    ///
+    /// Used only with `InitialSync::Streamed`.
+    synced: usize,
+
+    /// Indicates that all entities from the initial sync were sent to this client.
+    ///
+    /// Used only with `InitialSync::Streamed`.
+    sync_finished: bool,
 }
 
 impl ClientTicks {
@@ -147,21 +168,68 @@ impl ClientTicks {
         Some(mutate_info.entities)
     }
 
+    /// Returns entities with mutations that weren't acknowledged by the client yet.
+    pub(crate) fn pending(&self) -> &EntityHashSet {
+        &self.pending
+    }
+
+    /// Marks or unmarks an entity as having unacknowledged mutations.
+    pub(crate) fn set_pending(&mut self, entity: Entity, pending: bool) {
+        if pending {
+            self.pending.insert(entity);
+        } else {
+            self.pending.remove(&entity);
+        }
+    }
+
+    /// Returns `true` if all replicated entities were visited for this client at least once.
+    pub(crate) fn is_scanned(&self) -> bool {
+        self.scanned
+    }
+
+    pub(crate) fn mark_scanned(&mut self) {
+        self.scanned = true;
+    }
+
+    /// Returns `true` if all entities from the initial sync were sent to this client.
+    pub(crate) fn is_sync_finished(&self) -> bool {
+        self.sync_finished
+    }
+
+    /// Adds the number of entities sent during the initial sync and returns the total number of sent entities.
+    pub(crate) fn add_synced(&mut self, count: usize) -> usize {
+        self.synced += count;
+        self.synced
+    }
+
+    pub(crate) fn finish_sync(&mut self) {
+        self.sync_finished = true;
+    }
+
     /// Removes a despawned or hidden entity from tracking by this client.
     pub(crate) fn remove_entity(&mut self, entity: Entity) {
         self.mutation_ticks.remove(&entity);
+        self.pending.remove(&entity);
         // We don't clean up `self.mutations` for efficiency reasons.
         // `Self::acknowledge` will properly ignore despawned entities.
     }
 
+    /// Returns the number of sent mutate messages that weren't acknowledged yet.
+    pub(crate) fn pending_mutate_messages(&self) -> usize {
+        self.mutations.len()
+    }
+
     /// Removes all mutate messages older then `min_timestamp`.
     ///
     /// Keeps allocated memory in the buffers for reuse.
+    ///
+    /// Returns the number of removed messages.
     pub(crate) fn cleanup_older_mutations(
         &mut self,
         entity_buffer: &mut EntityBuffer,
         min_timestamp: Duration,
-    ) {
+    ) -> usize {
+        let len = self.mutations.len();
         self.mutations.retain(|_, mutate_info| {
             if mutate_info.timestamp < min_timestamp {
                 entity_buffer.push(mem::take(&mut mutate_info.entities));
@@ -170,6 +238,8 @@ impl ClientTicks {
                 true
             }
         });
+
+        len - self.mutations.len()
     }
 }
 
@@ -4,7 +4,7 @@ use bevy::{ecs::component::ComponentId, prelude::*};
 use log::debug;
 
 use super::registry::{
-    ReplicationRegistry,
+    DespawnFn, ReplicationRegistry,
     command_fns::{MutWrite, RemoveFn, WriteFn},
 };
 
@@ -119,6 +119,40 @@ pub trait AppMarkerExt {
         remove: RemoveFn,
     ) -> &mut Self;
 
+    /**
+    Associates a despawn function with a marker.
+
+    If this marker is present on an entity and its priority is the highest among
+    markers with an assigned despawn function, then this function will be called
+    instead of [`ReplicationRegistry::despawn`] when the server despawns the entity.
+
+    Entities returned to a pool from [`AppPoolExt`](crate::client::entity_pool::AppPoolExt)
+    are not despawned, so this function won't be called for them.
+
+    # Examples
+
+    Keep entities with `Pooled` marker for reuse instead of despawning them.
+
+    ```
+    # use bevy::state::app::StatesPlugin;
+    use bevy::prelude::*;
+    use bevy_replicon::{prelude::*, shared::replication::registry::ctx::DespawnCtx};
+
+    # let mut app = App::new();
+    # app.add_plugins((StatesPlugin, RepliconPlugins));
+    app.register_marker::<Pooled>()
+        .set_marker_despawn_fn::<Pooled>(return_to_pool);
+
+    fn return_to_pool(_ctx: &DespawnCtx, mut entity: EntityWorldMut) {
+        entity.remove::<Replicated>().insert(Visibility::Hidden);
+    }
+
+    #[derive(Component)]
+    struct Pooled;
+    ```
+    **/
+    fn set_marker_despawn_fn<M: Component>(&mut self, despawn: DespawnFn) -> &mut Self;
+
     /// Sets default functions for a component when there are no markers.
     ///
     /// If there are no markers present on an entity, then these functions will
@@ -175,6 +209,18 @@ impl AppMarkerExt for App {
         self
     }
 
+    fn set_marker_despawn_fn<M: Component>(&mut self, despawn: DespawnFn) -> &mut Self {
+        debug!("adding despawn fn for marker `{}`", ShortName::of::<M>());
+        let component_id = self.world_mut().register_component::<M>();
+        let command_markers = self.world().resource::<CommandMarkers>();
+        let marker_id = command_markers.marker_id(component_id);
+        self.world_mut()
+            .resource_mut::<ReplicationRegistry>()
+            .set_marker_despawn_fn(marker_id, despawn);
+
+        self
+    }
+
     fn set_command_fns<C: Component<Mutability: MutWrite<C>>>(
         &mut self,
         write: WriteFn<C>,
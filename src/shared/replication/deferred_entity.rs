@@ -53,6 +53,32 @@ impl<'w> DeferredEntity<'w> {
         self.entity.get_mut()
     }
 
+    /// Returns `true` if insertion of the component with the given ID is buffered.
+    pub(crate) fn buffered_insertion(&self, component_id: ComponentId) -> bool {
+        self.changes.insertions.ids.contains(&component_id)
+    }
+
+    /// Wraps another entity, keeping all buffered changes.
+    ///
+    /// Spawns a new empty entity if `entity` is [`None`].
+    /// The wrapped entity is left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new entity doesn't exist.
+    pub(crate) fn replace(self, entity: Option<Entity>) -> Self {
+        let world = self.entity.into_world_mut();
+        let entity = match entity {
+            Some(entity) => world.entity_mut(entity),
+            None => world.spawn_empty(),
+        };
+
+        Self {
+            entity,
+            changes: self.changes,
+        }
+    }
+
     fn register_component<C: Component>(&mut self) -> ComponentId {
         // SAFETY: no location update is needed because we only register the component ID.
         unsafe { self.entity.world_mut().register_component::<C>() }
@@ -4,11 +4,13 @@ pub mod ctx;
 pub mod rule_fns;
 pub mod test_fns;
 
+use core::any::{self, TypeId};
+
 use bevy::{ecs::component::ComponentId, prelude::*};
 use log::trace;
 use serde::{Deserialize, Serialize};
 
-use super::command_markers::CommandMarkerIndex;
+use super::command_markers::{CommandMarkerIndex, EntityMarkers};
 use crate::prelude::*;
 use command_fns::{MutWrite, RemoveFn, UntypedCommandFns, WriteFn};
 use component_fns::ComponentFns;
@@ -22,13 +24,33 @@ pub struct ReplicationRegistry {
     ///
     /// By default uses [`despawn`].
     /// Useful if you need to intercept despawns and handle them in a special way.
+    ///
+    /// Used only if the entity has no markers with an assigned function.
+    /// See [`AppMarkerExt::set_marker_despawn_fn`].
     pub despawn: DespawnFn,
 
+    /// Custom function to handle entities that are no longer visible to the client.
+    ///
+    /// By default uses [`despawn`].
+    /// Called with [`DespawnCtx::visibility_lost`] set, so you could use the same function
+    /// as for [`Self::despawn`]. Useful if you want to fade out, keep or pool entities
+    /// that left the client's interest instead of destroying them.
+    ///
+    /// The mapping for the entity is removed from
+    /// [`ServerEntityMap`](crate::shared::server_entity_map::ServerEntityMap) before the call,
+    /// and the server will send the entity as a new one if it becomes visible again.
+    pub visibility_lost: DespawnFn,
+
     /// Functions for replicated components.
     ///
     /// Unique for each component.
     components: Vec<(ComponentId, ComponentFns)>,
 
+    /// Type information for each component from [`Self::components`].
+    ///
+    /// Used to build [`ProtocolManifest`](crate::shared::protocol::versioning::ProtocolManifest).
+    component_types: Vec<(TypeId, &'static str)>,
+
     /// Serialization/deserialization functions for a component and
     /// the component's index in [`Self::components`].
     ///
@@ -40,6 +62,11 @@ pub struct ReplicationRegistry {
     ///
     /// Used to initialize new [`ComponentFns`] with the registered number of slots.
     marker_slots: usize,
+
+    /// Despawn functions for each marker.
+    ///
+    /// Indices correspond to markers in [`CommandMarkers`](super::command_markers::CommandMarkers).
+    marker_despawns: Vec<Option<DespawnFn>>,
 }
 
 impl ReplicationRegistry {
@@ -49,11 +76,51 @@ impl ReplicationRegistry {
     /// [`CommandMarkers::insert`](super::command_markers::CommandMarkers::insert)
     pub(super) fn register_marker(&mut self, marker_id: CommandMarkerIndex) {
         self.marker_slots += 1;
+        self.marker_despawns.insert(*marker_id, None);
         for (_, command_fns) in &mut self.components {
             command_fns.add_marker_slot(marker_id);
         }
     }
 
+    /// Associates despawn function with a marker.
+    ///
+    /// **Must** be called **after** calling [`Self::register_marker`] with `marker_id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the marker wasn't registered or if the function was already set.
+    pub(super) fn set_marker_despawn_fn(
+        &mut self,
+        marker_id: CommandMarkerIndex,
+        despawn: DespawnFn,
+    ) {
+        let slot = self
+            .marker_despawns
+            .get_mut(*marker_id)
+            .unwrap_or_else(|| panic!("despawn fns should have a slot for {marker_id:?}"));
+
+        assert!(
+            slot.is_none(),
+            "despawn function for {marker_id:?} can't be set twice"
+        );
+
+        *slot = Some(despawn);
+    }
+
+    /// Returns the despawn function based on entity markers.
+    ///
+    /// The first-found function whose marker is present on the entity will be selected
+    /// (the functions are sorted by priority).
+    /// If there is no such function, it will return [`Self::despawn`].
+    pub(crate) fn despawn_fn(&self, entity_markers: &EntityMarkers) -> DespawnFn {
+        self.marker_despawns
+            .iter()
+            .zip(entity_markers.markers())
+            .filter(|&(_, contains)| *contains)
+            .find_map(|(&despawn, _)| despawn)
+            .unwrap_or(self.despawn)
+    }
+
     /// Associates command functions with a marker for a component.
     ///
     /// **Must** be called **after** calling [`Self::register_marker`] with `marker_id`.
@@ -132,6 +199,8 @@ impl ReplicationRegistry {
             .unwrap_or_else(|| {
                 self.components
                     .push((component_id, ComponentFns::new::<C>(self.marker_slots)));
+                self.component_types
+                    .push((TypeId::of::<C>(), any::type_name::<C>()));
                 self.components.len() - 1
             });
 
@@ -152,15 +221,38 @@ impl ReplicationRegistry {
 
         (*component_id, command_fns, rule_fns)
     }
+
+    /// Iterates over component types for each [`FnsId`].
+    pub(crate) fn iter_rule_types(&self) -> impl Iterator<Item = (TypeId, &'static str)> + '_ {
+        self.rules
+            .iter()
+            .map(|&(_, index)| self.component_types[index])
+    }
+
+    /// Returns ID of replication functions by its index.
+    pub(crate) fn fns_id(&self, index: usize) -> FnsId {
+        debug_assert!(index < self.rules.len());
+        FnsId(index)
+    }
+
+    /// Returns the type name of the component for the given replication functions.
+    pub(crate) fn type_name(&self, fns_id: FnsId) -> &'static str {
+        let (_, index) = self.rules[fns_id.0];
+        let (_, name) = self.component_types[index];
+        name
+    }
 }
 
 impl Default for ReplicationRegistry {
     fn default() -> Self {
         Self {
             despawn,
+            visibility_lost: despawn,
             components: Default::default(),
+            component_types: Default::default(),
             rules: Default::default(),
             marker_slots: 0,
+            marker_despawns: Default::default(),
         }
     }
 }
@@ -171,6 +263,12 @@ impl Default for ReplicationRegistry {
 #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct FnsId(usize);
 
+impl FnsId {
+    pub(crate) fn index(self) -> usize {
+        self.0
+    }
+}
+
 /// Signature of the entity despawn function.
 pub type DespawnFn = fn(&DespawnCtx, EntityWorldMut);
 
@@ -1,4 +1,6 @@
-use bevy::{prelude::*, ptr::Ptr};
+use core::mem;
+
+use bevy::{ecs::entity::EntityMapper, prelude::*, ptr::Ptr};
 use bytes::Bytes;
 
 use super::{
@@ -18,6 +20,7 @@ pub(crate) struct ComponentFns {
     serialize: UntypedSerializeFn,
     write: UntypedWriteFn,
     consume: UntypedConsumeFn,
+    visit_entities: UntypedVisitEntitiesFn,
     commands: UntypedCommandFns,
     markers: Vec<Option<UntypedCommandFns>>,
 }
@@ -29,6 +32,7 @@ impl ComponentFns {
             serialize: untyped_serialize::<C>,
             write: untyped_write::<C>,
             consume: untyped_consume::<C>,
+            visit_entities: untyped_visit_entities::<C>,
             commands: UntypedCommandFns::default_fns::<C>(),
             markers: vec![None; marker_slots],
         }
@@ -92,6 +96,27 @@ impl ComponentFns {
         unsafe { (self.serialize)(ctx, rule_fns, ptr, message) }
     }
 
+    /// Restores erased type from `ptr` and `rule_fns` and calls [`Component::map_entities`]
+    /// on a copy of the component.
+    ///
+    /// The copy is created by serializing the component and deserializing it back with `rule_fns`,
+    /// so the original component is never mutated and only replicated entities are visited.
+    /// Deserialization doesn't map entities, so `mapper` receives them unchanged.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `ptr` and `rule_fns` were created for the same type as this instance.
+    pub(crate) unsafe fn visit_entities(
+        &self,
+        serialize_ctx: &SerializeCtx,
+        write_ctx: &mut WriteCtx,
+        rule_fns: &UntypedRuleFns,
+        ptr: Ptr,
+        mapper: &mut dyn EntityMapper,
+    ) -> Result<()> {
+        unsafe { (self.visit_entities)(serialize_ctx, write_ctx, rule_fns, ptr, mapper) }
+    }
+
     /// Calls the assigned writing function based on entity markers.
     ///
     /// The first-found write function whose marker is present on the entity will be selected
@@ -187,6 +212,15 @@ type UntypedWriteFn = unsafe fn(
 /// Signature of component consuming functions that restores the original type.
 type UntypedConsumeFn = unsafe fn(&mut WriteCtx, &UntypedRuleFns, &mut Bytes) -> Result<()>;
 
+/// Signature of entity visiting functions that restore the original type.
+type UntypedVisitEntitiesFn = unsafe fn(
+    &SerializeCtx,
+    &mut WriteCtx,
+    &UntypedRuleFns,
+    Ptr,
+    &mut dyn EntityMapper,
+) -> Result<()>;
+
 /// Dereferences a component from a pointer and calls the passed serialization function.
 ///
 /// # Safety
@@ -231,3 +265,29 @@ unsafe fn untyped_consume<C: Component>(
 ) -> Result<()> {
     unsafe { rule_fns.typed::<C>().consume(ctx, message) }
 }
+
+/// Dereferences a component from a pointer, copies it via serialization and maps entities of the copy.
+///
+/// # Safety
+///
+/// The caller must ensure that `ptr` and `rule_fns` were created for `C`.
+unsafe fn untyped_visit_entities<C: Component>(
+    serialize_ctx: &SerializeCtx,
+    write_ctx: &mut WriteCtx,
+    rule_fns: &UntypedRuleFns,
+    ptr: Ptr,
+    mut mapper: &mut dyn EntityMapper,
+) -> Result<()> {
+    let rule_fns = unsafe { rule_fns.typed::<C>() };
+    let mut message = Vec::new();
+    rule_fns.serialize(serialize_ctx, unsafe { ptr.deref::<C>() }, &mut message)?;
+
+    let ignore_mapping = mem::replace(&mut write_ctx.ignore_mapping, true);
+    let result = rule_fns.deserialize(write_ctx, &mut Bytes::from(message));
+    write_ctx.ignore_mapping = ignore_mapping;
+
+    let mut component = result?;
+    C::map_entities(&mut component, &mut mapper);
+
+    Ok(())
+}
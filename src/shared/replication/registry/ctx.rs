@@ -71,4 +71,9 @@ pub struct RemoveCtx {
 pub struct DespawnCtx {
     /// Tick for the currently processing message.
     pub message_tick: RepliconTick,
+
+    /// Whether the entity wasn't despawned on the server, but became hidden for the client.
+    ///
+    /// See [`ReplicationRegistry::visibility_lost`](super::ReplicationRegistry::visibility_lost).
+    pub visibility_lost: bool,
 }
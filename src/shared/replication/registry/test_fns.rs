@@ -91,7 +91,9 @@ pub trait TestFnsEntityExt {
     /// See also [`AppMarkerExt`].
     fn apply_remove(&mut self, fns_id: FnsId, message_tick: RepliconTick) -> &mut Self;
 
-    /// Despawns an entity using [`ReplicationRegistry::despawn`].
+    /// Despawns an entity using [`ReplicationRegistry::despawn`] or a registered function for a marker.
+    ///
+    /// See also [`AppMarkerExt`].
     fn apply_despawn(self, message_tick: RepliconTick);
 }
 
@@ -203,9 +205,17 @@ impl TestFnsEntityExt for EntityWorldMut<'_> {
         self
     }
 
-    fn apply_despawn(self, message_tick: RepliconTick) {
+    fn apply_despawn(mut self, message_tick: RepliconTick) {
+        let mut entity_markers = self.world_scope(EntityMarkers::from_world);
+        let command_markers = self.world().resource::<CommandMarkers>();
+        entity_markers.read(command_markers, &self);
+
         let registry = self.world().resource::<ReplicationRegistry>();
-        let ctx = DespawnCtx { message_tick };
-        (registry.despawn)(&ctx, self);
+        let despawn = registry.despawn_fn(&entity_markers);
+        let ctx = DespawnCtx {
+            message_tick,
+            visibility_lost: false,
+        };
+        (despawn)(&ctx, self);
     }
 }
@@ -0,0 +1,231 @@
+use core::marker::PhantomData;
+
+#[cfg(feature = "client")]
+use bevy::ecs::relationship::{Relationship, RelationshipSourceCollection};
+use bevy::{
+    ecs::{
+        component::Mutable,
+        relationship::{OrderedRelationshipSourceCollection, RelationshipTarget},
+    },
+    prelude::*,
+};
+use bytes::Bytes;
+use log::debug;
+
+use super::registry::ctx::{SerializeCtx, WriteCtx};
+use crate::{postcard_utils, prelude::*};
+
+pub trait RelationshipOrderAppExt {
+    /**
+    Replicates the order of sources in relationship target `T`.
+
+    Only the relationship component is usually replicated, and targets like [`Children`]
+    are rebuilt on the client in the order in which relationships were inserted, which may
+    differ from the order on the server. This method adds a replication rule for
+    [`RelationshipOrder<T>`], which the server keeps in sync with `T`, and the client sorts `T`
+    according to it after receiving replication.
+
+    The order is replicated as an insertion, so it arrives in the same message as insertions
+    of the relationship component. It's applied by observers in the same write that inserts
+    the order or a relationship, so `T` is never observed unordered after receiving a message.
+    This also makes it compatible with
+    [`SyncRelatedAppExt::sync_related_entities`].
+
+    The relationship component itself still needs to be replicated. Only sources with [`Replicated`]
+    are included. Sources that aren't replicated on the client are kept after the ordered ones.
+
+    # Examples
+
+    ```
+    # use bevy::state::app::StatesPlugin;
+    use bevy::prelude::*;
+    use bevy_replicon::prelude::*;
+
+    # let mut app = App::new();
+    # app.add_plugins((StatesPlugin, RepliconPlugins));
+    app.replicate::<ChildOf>().replicate_order::<Children>();
+    ```
+    **/
+    fn replicate_order<T>(&mut self) -> &mut Self
+    where
+        T: RelationshipTarget<Collection: OrderedRelationshipSourceCollection>
+            + Component<Mutability = Mutable>;
+}
+
+impl RelationshipOrderAppExt for App {
+    fn replicate_order<T>(&mut self) -> &mut Self
+    where
+        T: RelationshipTarget<Collection: OrderedRelationshipSourceCollection>
+            + Component<Mutability = Mutable>,
+    {
+        debug!("replicating order for `{}`", ShortName::of::<T>());
+
+        self.replicate_with(RuleFns::new(serialize_order::<T>, deserialize_order::<T>));
+
+        #[cfg(feature = "server")]
+        self.add_systems(
+            PostUpdate,
+            update_order::<T>
+                .before(ServerSystems::Send)
+                .run_if(in_state(ServerState::Running)),
+        );
+
+        #[cfg(feature = "client")]
+        self.add_observer(apply_order::<T>)
+            .add_observer(place_source::<T>);
+
+        self
+    }
+}
+
+/// Order of sources in relationship target `T`.
+///
+/// Maintained by the server and used by the client to sort `T`.
+///
+/// See [`RelationshipOrderAppExt::replicate_order`].
+#[derive(Component, Deref, Debug)]
+#[component(immutable)]
+pub struct RelationshipOrder<T: RelationshipTarget> {
+    #[deref]
+    #[entities]
+    entities: Vec<Entity>,
+    marker: PhantomData<T>,
+}
+
+impl<T: RelationshipTarget> RelationshipOrder<T> {
+    fn new(entities: Vec<Entity>) -> Self {
+        Self {
+            entities,
+            marker: PhantomData,
+        }
+    }
+}
+
+fn serialize_order<T: RelationshipTarget>(
+    _ctx: &SerializeCtx,
+    order: &RelationshipOrder<T>,
+    message: &mut Vec<u8>,
+) -> Result<()> {
+    postcard_utils::to_extend_mut(&order.entities.len(), message)?;
+    for &entity in &order.entities {
+        postcard_utils::entity_to_extend_mut(&entity, message)?;
+    }
+
+    Ok(())
+}
+
+fn deserialize_order<T: RelationshipTarget>(
+    ctx: &mut WriteCtx,
+    message: &mut Bytes,
+) -> Result<RelationshipOrder<T>> {
+    let len: usize = postcard_utils::from_buf(message)?;
+    let mut entities = Vec::with_capacity(len);
+    for _ in 0..len {
+        let entity = postcard_utils::entity_from_buf(message)?;
+        entities.push(ctx.get_mapped(entity));
+    }
+
+    Ok(RelationshipOrder::new(entities))
+}
+
+/// Inserts [`RelationshipOrder<T>`] for changed targets and removes it for removed ones.
+#[cfg(feature = "server")]
+fn update_order<T: RelationshipTarget>(
+    mut commands: Commands,
+    mut removed: RemovedComponents<T>,
+    targets: Query<(Entity, &T), (Or<(Changed<T>, Added<Replicated>)>, With<Replicated>)>,
+    sources: Query<(), With<Replicated>>,
+) {
+    for entity in removed.read() {
+        if let Ok(mut entity) = commands.get_entity(entity) {
+            entity.try_remove::<RelationshipOrder<T>>();
+        }
+    }
+
+    for (entity, target) in &targets {
+        let entities = target
+            .iter()
+            .filter(|&source| sources.contains(source))
+            .collect();
+        commands
+            .entity(entity)
+            .insert(RelationshipOrder::<T>::new(entities));
+    }
+}
+
+/// Sorts the target according to its replicated [`RelationshipOrder<T>`] on insertion.
+///
+/// Sources that aren't present in the order are placed at the end.
+#[cfg(feature = "client")]
+fn apply_order<T>(
+    insert: On<Insert, RelationshipOrder<T>>,
+    state: Res<State<ClientState>>,
+    mut targets: Query<(&mut T, &RelationshipOrder<T>)>,
+) where
+    T: RelationshipTarget<Collection: OrderedRelationshipSourceCollection>
+        + Component<Mutability = Mutable>,
+{
+    // On listen servers the order is derived from the target itself.
+    if *state != ClientState::Connected {
+        return;
+    }
+    let Ok((mut target, order)) = targets.get_mut(insert.entity) else {
+        return;
+    };
+
+    let ordered = order
+        .entities
+        .iter()
+        .filter(|&source| target.iter().any(|entity| entity == source));
+    if target.iter().zip(ordered).all(|(a, b)| a == b) {
+        return;
+    }
+
+    let collection = target.collection_mut_risky();
+    let mut index = 0;
+    for &source in &order.entities {
+        if collection.iter().any(|entity| entity == source) {
+            collection.place(source, index);
+            index += 1;
+        }
+    }
+}
+
+/// Moves a source to its position from the replicated [`RelationshipOrder<T>`] on relationship insertion.
+///
+/// Relationship hooks add sources to the end of the target, so the order
+/// could arrive before its sources in the same message.
+#[cfg(feature = "client")]
+fn place_source<T>(
+    insert: On<Insert, T::Relationship>,
+    state: Res<State<ClientState>>,
+    sources: Query<&T::Relationship>,
+    mut targets: Query<(&mut T, &RelationshipOrder<T>)>,
+) where
+    T: RelationshipTarget<Collection: OrderedRelationshipSourceCollection>
+        + Component<Mutability = Mutable>,
+{
+    if *state != ClientState::Connected {
+        return;
+    }
+    let Ok(relationship) = sources.get(insert.entity) else {
+        return;
+    };
+    let Ok((mut target, order)) = targets.get_mut(relationship.get()) else {
+        return;
+    };
+    let Some(position) = order
+        .entities
+        .iter()
+        .position(|entity| entity == insert.entity)
+    else {
+        return;
+    };
+
+    let collection = target.collection_mut_risky();
+    let index = order.entities[..position]
+        .iter()
+        .filter(|&&source| collection.iter().any(|entity| entity == source))
+        .count();
+    collection.place(insert.entity, index);
+}
@@ -45,6 +45,9 @@ pub struct Signature {
     ///
     /// Calculated when added to an entity.
     hash: u64,
+
+    /// Indicates that [`Self::hash`] was restored and shouldn't be calculated.
+    restored: bool,
 }
 
 impl Signature {
@@ -136,6 +139,7 @@ impl Signature {
             fns: &[hash::<C>],
             client: None,
             hash: 0,
+            restored: false,
         }
     }
 
@@ -229,6 +233,7 @@ impl Signature {
             fns: S::HASH_FNS,
             client: None,
             hash: 0,
+            restored: false,
         }
     }
 
@@ -256,6 +261,19 @@ impl Signature {
         self
     }
 
+    /// Creates a new instance with a previously calculated hash.
+    ///
+    /// Used to restore signatures from scenes.
+    pub(crate) fn restored(hash: u64) -> Self {
+        Self {
+            salt: None,
+            fns: &[],
+            client: None,
+            hash,
+            restored: true,
+        }
+    }
+
     pub(crate) fn client(&self) -> Option<Entity> {
         self.client
     }
@@ -294,6 +312,7 @@ impl<T: Hash> From<T> for Signature {
             fns: &[],
             client: None,
             hash: 0,
+            restored: false,
         }
     }
 }
@@ -301,11 +320,16 @@ impl<T: Hash> From<T> for Signature {
 fn register_hash(mut world: DeferredWorld, ctx: HookContext) {
     let mut entity = world.entity_mut(ctx.entity);
     let signature = entity.get::<Signature>().unwrap();
-    let hash = signature.eval(&entity);
+    let hash = if signature.restored {
+        signature.hash
+    } else {
+        let hash = signature.eval(&entity);
 
-    // Re-borrow due to borrow-checker.
-    let mut signature = entity.get_mut::<Signature>().unwrap();
-    signature.hash = hash;
+        // Re-borrow due to borrow-checker.
+        let mut signature = entity.get_mut::<Signature>().unwrap();
+        signature.hash = hash;
+        hash
+    };
 
     world
         .resource_mut::<SignatureMap>()
@@ -9,8 +9,10 @@ bitflags! {
     pub(crate) struct UpdateMessageFlags: u8 {
         const MAPPINGS = 0b00000001;
         const DESPAWNS = 0b00000010;
-        const REMOVALS = 0b00000100;
-        const CHANGES = 0b00001000;
+        const HIDDEN = 0b00000100;
+        const REMOVALS = 0b00001000;
+        const SYNC = 0b00010000;
+        const CHANGES = 0b00100000;
     }
 }
 
@@ -45,5 +47,9 @@ mod tests {
             (UpdateMessageFlags::DESPAWNS | UpdateMessageFlags::REMOVALS).last(),
             UpdateMessageFlags::REMOVALS
         );
+        assert_eq!(
+            (UpdateMessageFlags::MAPPINGS | UpdateMessageFlags::HIDDEN).last(),
+            UpdateMessageFlags::HIDDEN
+        );
     }
 }
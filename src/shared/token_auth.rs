@@ -0,0 +1,114 @@
+//! Types for [`AuthMethod::Token`].
+
+use alloc::{string::String, vec::Vec};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+/// An opaque payload that the client sends to the server for authorization.
+///
+/// Could be a password, a session token or a signed ticket. The format is up to the user.
+///
+/// Sent on connection if [`AuthMethod::Token`] is used.
+/// If the resource is missing, an empty payload will be sent.
+#[cfg(feature = "client")]
+#[derive(Resource, Default, Debug, Clone, Deref, DerefMut)]
+pub struct AuthToken(pub Vec<u8>);
+
+/// A client event that carries the data for token authorization.
+#[derive(Event, Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct TokenHandshake {
+    pub(crate) protocol: ProtocolHash,
+    pub(crate) payload: Vec<u8>,
+}
+
+/// An event that requests verification of a client's [`AuthToken`].
+///
+/// Triggered on the server for the client entity after its protocol hash is verified.
+/// The verification result should be reported via [`ResolveAuth`]. It can be
+/// triggered from the observer directly or later, for example, after an async task completes.
+///
+/// If the result isn't reported within the timeout of [`AuthMethod::Token`],
+/// the client will be rejected.
+#[cfg(feature = "server")]
+#[derive(EntityEvent, Debug, Clone)]
+pub struct AuthRequested {
+    /// Entity of the client.
+    #[event_target]
+    pub client: Entity,
+
+    /// Payload from the client's [`AuthToken`].
+    pub payload: Vec<u8>,
+}
+
+/// An event that reports the verification result for [`AuthRequested`].
+///
+/// On success, [`AuthorizedClient`] will be inserted. On failure, the client will receive
+/// [`AuthRejected`] with the reason and will be disconnected.
+///
+/// Ignored if the client has already been authorized or rejected.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::*;
+/// use bevy_replicon::shared::token_auth::{AuthRequested, ResolveAuth};
+///
+/// fn verify_token(auth: On<AuthRequested>, mut commands: Commands) {
+///     if auth.payload == b"secret" {
+///         commands.trigger(ResolveAuth::accept(auth.client));
+///     } else {
+///         commands.trigger(ResolveAuth::reject(auth.client, "invalid token"));
+///     }
+/// }
+/// ```
+#[cfg(feature = "server")]
+#[derive(EntityEvent, Debug, Clone)]
+pub struct ResolveAuth {
+    /// Entity of the client.
+    #[event_target]
+    pub client: Entity,
+
+    /// Verification result with the rejection reason on failure.
+    pub result: Result<(), String>,
+}
+
+#[cfg(feature = "server")]
+impl ResolveAuth {
+    /// Creates an event that authorizes the client.
+    pub fn accept(client: Entity) -> Self {
+        Self {
+            client,
+            result: Ok(()),
+        }
+    }
+
+    /// Creates an event that rejects the client with the given reason.
+    pub fn reject(client: Entity, reason: impl Into<String>) -> Self {
+        Self {
+            client,
+            result: Err(reason.into()),
+        }
+    }
+}
+
+/// A server event to notify the client that its authorization was rejected.
+///
+/// Registered and sent only if [`AuthMethod::Token`] is used.
+/// The server will immediately disconnect after sending it, so there is no delivery guarantee.
+#[derive(Event, Serialize, Deserialize, Debug, Clone)]
+pub struct AuthRejected {
+    /// Reason passed to [`ResolveAuth::reject`] on the server.
+    pub reason: String,
+}
+
+/// Marks a client that is waiting for [`ResolveAuth`].
+///
+/// Stores the time of the connection to reject clients after the timeout.
+#[cfg(feature = "server")]
+#[derive(Component)]
+pub(crate) struct PendingAuth {
+    pub(crate) since: core::time::Duration,
+}
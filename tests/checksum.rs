@@ -0,0 +1,172 @@
+use test_log::test;
+
+use bevy::{prelude::*, state::app::StatesPlugin};
+use bevy_replicon::{
+    prelude::*,
+    shared::{
+        backend::channels::ServerChannel,
+        replication::checksum::{Checksums, ReplicationDesync},
+    },
+    test_app::ServerTestAppExt,
+};
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn matching() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .replicate::<A>()
+        .add_checksum::<A>()
+        .finish();
+    }
+    server_app.world_mut().resource_mut::<Checksums>().interval = 1;
+    client_app
+        .init_resource::<Desyncs>()
+        .add_observer(record_desync);
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app.world_mut().spawn((Replicated, A(0))).id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let mut a = server_app.world_mut().get_mut::<A>(server_entity).unwrap();
+    a.0 = 1;
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let desyncs = client_app.world().resource::<Desyncs>();
+    assert!(desyncs.is_empty());
+}
+
+#[test]
+fn desync() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .replicate::<A>()
+        .replicate::<B>()
+        .add_checksum::<A>()
+        .add_checksum::<B>()
+        .finish();
+    }
+    server_app.world_mut().resource_mut::<Checksums>().interval = 1;
+    client_app
+        .init_resource::<Desyncs>()
+        .add_observer(record_desync);
+
+    server_app.connect_client(&mut client_app);
+
+    server_app.world_mut().spawn((Replicated, A(0), B(0)));
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let (client_entity, mut b) = client_app
+        .world_mut()
+        .query::<(Entity, &mut B)>()
+        .single_mut(client_app.world_mut())
+        .unwrap();
+    b.0 = 1;
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let b_id = client_app.world().component_id::<B>().unwrap();
+    let desyncs = client_app.world().resource::<Desyncs>();
+    assert_eq!(desyncs.len(), 1);
+    let desync = &desyncs[0];
+    assert_eq!(desync.entities.len(), 1);
+    assert_eq!(desync.entities[0].entity, client_entity);
+    assert_eq!(desync.entities[0].components, [b_id]);
+}
+
+#[test]
+fn delayed_mutations() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .replicate::<A>()
+        .add_checksum::<A>()
+        .finish();
+    }
+    server_app.world_mut().resource_mut::<Checksums>().interval = 1;
+    client_app
+        .init_resource::<Desyncs>()
+        .add_observer(record_desync);
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app.world_mut().spawn((Replicated, A(0))).id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let mut a = server_app.world_mut().get_mut::<A>(server_entity).unwrap();
+    a.0 = 1;
+
+    server_app.update();
+
+    // Deliver the checksum without the mutate message.
+    let mut server_messages = server_app.world_mut().resource_mut::<ServerMessages>();
+    let messages: Vec<_> = server_messages
+        .drain_sent()
+        .filter(|&(_, channel_id, _)| channel_id != ServerChannel::Mutations as usize)
+        .collect();
+    let mut client_messages = client_app.world_mut().resource_mut::<ClientMessages>();
+    for (_, channel_id, message) in messages {
+        client_messages.insert_received(channel_id, message);
+    }
+
+    client_app.update();
+
+    let desyncs = client_app.world().resource::<Desyncs>();
+    assert!(desyncs.is_empty(), "checksum should wait for the mutation");
+
+    // The mutation is resent since it wasn't acknowledged.
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let desyncs = client_app.world().resource::<Desyncs>();
+    assert!(desyncs.is_empty());
+}
+
+fn record_desync(desync: On<ReplicationDesync>, mut desyncs: ResMut<Desyncs>) {
+    desyncs.push(desync.clone());
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct Desyncs(Vec<ReplicationDesync>);
+
+#[derive(Component, Deserialize, Serialize, Hash)]
+struct A(u8);
+
+#[derive(Component, Deserialize, Serialize, Hash)]
+struct B(u8);
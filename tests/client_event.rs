@@ -71,6 +71,56 @@ fn mapped() {
     assert_eq!(mapped_entities, [server_entity]);
 }
 
+#[test]
+fn entity() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .add_client_entity_event::<EntityTest>(Channel::Ordered)
+        .finish();
+    }
+    server_app.init_resource::<Targets>();
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn(Replicated)
+        .observe(
+            |event: On<FromClient<EntityTest>>, mut targets: ResMut<Targets>| {
+                targets.push(event.entity);
+            },
+        )
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let client_entity = *client_app
+        .world()
+        .resource::<ServerEntityMap>()
+        .to_client()
+        .get(&server_entity)
+        .unwrap();
+
+    client_app.world_mut().client_trigger(EntityTest {
+        entity: client_entity,
+    });
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+
+    let targets = server_app.world().resource::<Targets>();
+    assert_eq!(**targets, [server_entity]);
+}
+
 #[test]
 fn without_plugins() {
     let mut server_app = App::new();
@@ -136,6 +186,14 @@ struct Test;
 #[derive(Deserialize, Event, Serialize, Clone, MapEntities)]
 struct WithEntity(#[entities] Entity);
 
+#[derive(EntityEvent, Deserialize, Serialize)]
+struct EntityTest {
+    entity: Entity,
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct Targets(Vec<Entity>);
+
 #[derive(Resource)]
 struct EventReader<E: Event> {
     events: Vec<FromClient<E>>,
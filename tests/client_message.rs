@@ -1,6 +1,17 @@
 use bevy::{ecs::entity::MapEntities, prelude::*, state::app::StatesPlugin, time::TimePlugin};
 use bevy_replicon::{
-    prelude::*, shared::server_entity_map::ServerEntityMap, test_app::ServerTestAppExt,
+    bytes::Bytes,
+    prelude::*,
+    shared::{
+        message::{
+            ctx::{ClientFlagged, ClientSendCtx, ServerReceiveCtx},
+            limits::{
+                ClientMessageCounters, LimitKind, LimitReaction, MessageLimit, MessageLimitExceeded,
+            },
+        },
+        server_entity_map::ServerEntityMap,
+    },
+    test_app::ServerTestAppExt,
 };
 use serde::{Deserialize, Serialize};
 use test_log::test;
@@ -112,6 +123,266 @@ fn without_plugins() {
     assert_eq!(messages.len(), 1);
 }
 
+#[test]
+fn sender_aware() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, StatesPlugin, RepliconPlugins))
+            .add_client_message_with(Channel::Ordered, serialize_test, deserialize_trusted)
+            .finish();
+    }
+    server_app.init_resource::<FlaggedClients>().add_observer(
+        |flagged: On<ClientFlagged>, mut flagged_clients: ResMut<FlaggedClients>| {
+            flagged_clients.push(flagged.client);
+        },
+    );
+
+    server_app.connect_client(&mut client_app);
+
+    client_app.world_mut().write_message(Test);
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+
+    let messages = server_app.world().resource::<Messages<FromClient<Test>>>();
+    assert!(messages.is_empty(), "untrusted client should be rejected");
+
+    let client = server_app
+        .world_mut()
+        .query_filtered::<Entity, With<ConnectedClient>>()
+        .single(server_app.world())
+        .unwrap();
+    let flagged_clients = server_app.world().resource::<FlaggedClients>();
+    assert_eq!(**flagged_clients, [client]);
+
+    server_app.world_mut().entity_mut(client).insert(Trusted);
+    client_app.world_mut().write_message(Test);
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+
+    let messages = server_app.world().resource::<Messages<FromClient<Test>>>();
+    assert_eq!(messages.len(), 1);
+}
+
+#[test]
+fn rate_limit() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, StatesPlugin, RepliconPlugins))
+            .add_client_message::<Test>(Channel::Ordered)
+            .limit_client_message::<Test>(MessageLimit {
+                max_per_second: Some(1),
+                reaction: LimitReaction::Trigger,
+                ..Default::default()
+            })
+            .finish();
+    }
+    server_app.init_resource::<ExceededLimits>().add_observer(
+        |exceeded: On<MessageLimitExceeded>, mut exceeded_limits: ResMut<ExceededLimits>| {
+            exceeded_limits.push(exceeded.kind);
+        },
+    );
+
+    server_app.connect_client(&mut client_app);
+
+    client_app.world_mut().write_message(Test);
+    client_app.world_mut().write_message(Test);
+    client_app.world_mut().write_message(Test);
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+
+    let messages = server_app.world().resource::<Messages<FromClient<Test>>>();
+    assert_eq!(messages.len(), 1);
+
+    let exceeded_limits = server_app.world().resource::<ExceededLimits>();
+    assert_eq!(**exceeded_limits, [LimitKind::Rate; 2]);
+
+    let counters = server_app
+        .world_mut()
+        .query::<&ClientMessageCounters>()
+        .single(server_app.world())
+        .unwrap();
+    assert_eq!(counters.received, 3);
+    assert_eq!(counters.dropped, 2);
+}
+
+#[test]
+fn aggregate_limit() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, StatesPlugin, RepliconPlugins))
+            .add_client_message::<Test>(Channel::Ordered)
+            .add_client_event::<Payload>(Channel::Ordered)
+            .limit_all_client_messages(MessageLimit {
+                // Includes the protocol check event sent on connection.
+                max_per_second: Some(3),
+                reaction: LimitReaction::Trigger,
+                ..Default::default()
+            })
+            .finish();
+    }
+    server_app.init_resource::<ExceededLimits>().add_observer(
+        |exceeded: On<MessageLimitExceeded>, mut exceeded_limits: ResMut<ExceededLimits>| {
+            assert!(exceeded.aggregate);
+            exceeded_limits.push(exceeded.kind);
+        },
+    );
+
+    server_app.connect_client(&mut client_app);
+
+    client_app.world_mut().write_message(Test);
+    client_app.world_mut().write_message(Test);
+    client_app.world_mut().client_trigger(Payload(vec![0; 2]));
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+
+    let messages = server_app.world().resource::<Messages<FromClient<Test>>>();
+    assert_eq!(messages.len(), 2);
+
+    let exceeded_limits = server_app.world().resource::<ExceededLimits>();
+    assert_eq!(**exceeded_limits, [LimitKind::Rate]);
+
+    let counters = server_app
+        .world_mut()
+        .query::<&ClientMessageCounters>()
+        .single(server_app.world())
+        .unwrap();
+    assert_eq!(counters.received, 4);
+    assert_eq!(counters.dropped, 1);
+}
+
+#[test]
+fn size_limit() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, StatesPlugin, RepliconPlugins))
+            .add_client_event::<Payload>(Channel::Ordered)
+            .limit_client_event::<Payload>(MessageLimit {
+                max_size: Some(4),
+                reaction: LimitReaction::Disconnect,
+                ..Default::default()
+            })
+            .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    client_app.world_mut().client_trigger(Payload(vec![0; 2]));
+    client_app.world_mut().client_trigger(Payload(vec![0; 10]));
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+
+    let disconnects = server_app.world().resource::<Messages<DisconnectRequest>>();
+    assert_eq!(disconnects.len(), 1);
+
+    let counters = server_app
+        .world_mut()
+        .query::<&ClientMessageCounters>()
+        .single(server_app.world())
+        .unwrap();
+    assert_eq!(counters.received, 2);
+    assert_eq!(counters.dropped, 1);
+}
+
+#[test]
+fn disconnect_once() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, StatesPlugin, RepliconPlugins))
+            .add_client_event::<Payload>(Channel::Ordered)
+            .limit_client_event::<Payload>(MessageLimit {
+                max_size: Some(4),
+                reaction: LimitReaction::Disconnect,
+                ..Default::default()
+            })
+            .add_client_message::<Test>(Channel::Ordered)
+            .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    for _ in 0..3 {
+        client_app.world_mut().client_trigger(Payload(vec![0; 10]));
+    }
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+
+    let disconnects = server_app.world().resource::<Messages<DisconnectRequest>>();
+    assert_eq!(disconnects.len(), 1);
+
+    client_app.world_mut().client_trigger(Payload(vec![0; 10]));
+    client_app.world_mut().write_message(Test);
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+
+    let disconnects = server_app.world().resource::<Messages<DisconnectRequest>>();
+    assert_eq!(disconnects.len(), 1, "should be written only once");
+
+    let messages = server_app.world().resource::<Messages<FromClient<Test>>>();
+    assert!(messages.is_empty());
+
+    let counters = server_app
+        .world_mut()
+        .query::<&ClientMessageCounters>()
+        .single(server_app.world())
+        .unwrap();
+    assert_eq!(counters.received, 5);
+    assert_eq!(counters.dropped, 5);
+}
+
+#[test]
+fn coalesce() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, StatesPlugin, RepliconPlugins))
+            .add_client_message::<Test>(Channel::Unreliable)
+            .coalesce_client_message::<Test>()
+            .add_client_message::<Keyed>(Channel::Unreliable)
+            .coalesce_client_message_by::<Keyed>(|keyed| keyed.key.into())
+            .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    client_app.world_mut().write_message(Test);
+    client_app.world_mut().write_message(Test);
+    for (key, value) in [(0, 1), (1, 2), (0, 3)] {
+        client_app.world_mut().write_message(Keyed { key, value });
+    }
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+
+    let messages = server_app.world().resource::<Messages<FromClient<Test>>>();
+    assert_eq!(messages.len(), 1);
+
+    let mut messages = server_app
+        .world_mut()
+        .resource_mut::<Messages<FromClient<Keyed>>>();
+    let values: Vec<_> = messages.drain().map(|keyed| keyed.value).collect();
+    assert_eq!(values, [2, 3]);
+}
+
 #[test]
 fn local_sending() {
     let mut app = App::new();
@@ -133,5 +404,36 @@ fn local_sending() {
 #[derive(Deserialize, Message, Serialize)]
 struct Test;
 
+#[derive(Deserialize, Message, Serialize)]
+struct Keyed {
+    key: u8,
+    value: u8,
+}
+
 #[derive(Deserialize, Message, Serialize, Clone, MapEntities)]
 struct WithEntity(#[entities] Entity);
+
+#[derive(Deserialize, Event, Serialize)]
+struct Payload(Vec<u8>);
+
+#[derive(Component)]
+struct Trusted;
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct ExceededLimits(Vec<LimitKind>);
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct FlaggedClients(Vec<Entity>);
+
+fn serialize_test(_ctx: &mut ClientSendCtx, _message: &Test, _bytes: &mut Vec<u8>) -> Result<()> {
+    Ok(())
+}
+
+fn deserialize_trusted(ctx: &mut ServerReceiveCtx, _bytes: &mut Bytes) -> Result<Test> {
+    if ctx.client_entity().contains::<Trusted>() {
+        Ok(Test)
+    } else {
+        ctx.flag_client("untrusted client");
+        Err("only trusted clients can send this message".into())
+    }
+}
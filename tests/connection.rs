@@ -1,10 +1,14 @@
-use core::marker::PhantomData;
+use core::{marker::PhantomData, time::Duration};
 
 use bevy::{prelude::*, state::app::StatesPlugin};
 use bevy_replicon::{
     prelude::*,
     server::server_tick::ServerTick,
-    shared::backend::connected_client::{ConnectedClient, NetworkId, NetworkIdMap},
+    shared::{
+        backend::connected_client::{ConnectedClient, NetworkId, NetworkIdMap},
+        protocol::versioning::VersionedEntry,
+        token_auth::{AuthRejected, AuthRequested, AuthToken, ResolveAuth},
+    },
     test_app::ServerTestAppExt,
 };
 use serde::{Deserialize, Serialize};
@@ -31,6 +35,62 @@ fn client_connect_disconnect() {
     assert_eq!(clients.iter(server_app.world()).len(), 0);
 }
 
+#[test]
+fn disconnect_reason() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins
+                .set(RepliconSharedPlugin {
+                    disconnect_reasons: true,
+                    ..Default::default()
+                })
+                .set(ServerPlugin::new(PostUpdate)),
+        ))
+        .finish();
+    }
+    client_app.init_resource::<DisconnectReasons>();
+
+    server_app.connect_client(&mut client_app);
+
+    let client = server_app
+        .world_mut()
+        .query_filtered::<Entity, With<ConnectedClient>>()
+        .single(server_app.world())
+        .unwrap();
+    server_app.world_mut().write_message(DisconnectRequest {
+        client,
+        reason: DisconnectReason::Kicked,
+    });
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.disconnect_client(&mut client_app);
+
+    let reasons = client_app.world().resource::<DisconnectReasons>();
+    assert_eq!(reasons.0, [Some(DisconnectReason::Kicked)]);
+}
+
+#[test]
+fn disconnect_without_reason() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((MinimalPlugins, StatesPlugin, RepliconPlugins))
+            .finish();
+    }
+    client_app.init_resource::<DisconnectReasons>();
+
+    server_app.connect_client(&mut client_app);
+    server_app.disconnect_client(&mut client_app);
+
+    let reasons = client_app.world().resource::<DisconnectReasons>();
+    assert_eq!(reasons.0, [None]);
+}
+
 #[test]
 fn server_start_stop() {
     let mut server_app = App::new();
@@ -42,6 +102,7 @@ fn server_start_stop() {
             RepliconPlugins
                 .set(RepliconSharedPlugin {
                     auth_method: AuthMethod::Custom,
+                    ..Default::default()
                 })
                 .set(ServerPlugin::new(PostUpdate)),
         ))
@@ -93,6 +154,210 @@ fn protocol_mismatch() {
     assert_eq!(counter.events, 1);
 }
 
+#[test]
+fn versioned_protocol() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins
+                .set(RepliconSharedPlugin {
+                    protocol_version: Some(0),
+                    ..Default::default()
+                })
+                .set(ServerPlugin::new(PostUpdate)),
+        ));
+    }
+
+    // Register in a different order.
+    server_app
+        .replicate::<A>()
+        .replicate::<B>()
+        .add_client_message::<Test>(Channel::Ordered)
+        .add_client_message::<Other>(Channel::Ordered)
+        .finish();
+    client_app
+        .replicate::<B>()
+        .replicate::<A>()
+        .add_client_message::<Other>(Channel::Ordered)
+        .add_client_message::<Test>(Channel::Ordered)
+        .finish();
+
+    server_app.connect_client(&mut client_app);
+
+    let mut clients = server_app
+        .world_mut()
+        .query_filtered::<Entity, With<AuthorizedClient>>();
+    assert_eq!(clients.iter(server_app.world()).len(), 1);
+
+    server_app.world_mut().spawn((Replicated, A, B));
+    client_app.world_mut().write_message(Test);
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let messages = server_app.world().resource::<Messages<FromClient<Test>>>();
+    assert_eq!(messages.len(), 1);
+    let other_messages = server_app.world().resource::<Messages<FromClient<Other>>>();
+    assert!(other_messages.is_empty());
+
+    let mut components = client_app.world_mut().query::<(&A, &B)>();
+    assert_eq!(components.iter(client_app.world()).count(), 1);
+}
+
+#[test]
+fn versioned_optional() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    server_app
+        .add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins
+                .set(RepliconSharedPlugin {
+                    protocol_version: Some(1),
+                    ..Default::default()
+                })
+                .set(ServerPlugin::new(PostUpdate)),
+        ))
+        .replicate::<A>()
+        .replicate::<B>()
+        .add_client_message::<Test>(Channel::Ordered)
+        .add_server_message::<Other>(Channel::Ordered)
+        .make_message_independent::<Other>();
+    let mut versioning = server_app.world_mut().resource_mut::<ProtocolVersioning>();
+    versioning.set::<B>(VersionedEntry::new(0).with_versions(1..).optional());
+    versioning.set::<Test>(VersionedEntry::new(0).optional());
+    versioning.set::<Other>(VersionedEntry::new(0).optional());
+    server_app.finish();
+
+    client_app
+        .add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(RepliconSharedPlugin {
+                protocol_version: Some(0),
+                ..Default::default()
+            }),
+        ))
+        .replicate::<A>()
+        .finish();
+
+    server_app.connect_client(&mut client_app);
+
+    let mut clients = server_app
+        .world_mut()
+        .query_filtered::<Entity, With<AuthorizedClient>>();
+    assert_eq!(clients.iter(server_app.world()).len(), 1);
+
+    server_app.world_mut().spawn((Replicated, A, B));
+    server_app.world_mut().write_message(ToClients {
+        mode: SendMode::Broadcast,
+        message: Other,
+    });
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let mut components = client_app.world_mut().query_filtered::<&A, Without<B>>();
+    assert_eq!(
+        components.iter(client_app.world()).count(),
+        1,
+        "component unsupported by the client version should be skipped"
+    );
+}
+
+#[test]
+fn versioned_optional_removal() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins
+                .set(RepliconSharedPlugin {
+                    protocol_version: Some(0),
+                    ..Default::default()
+                })
+                .set(ServerPlugin::new(PostUpdate)),
+        ))
+        .replicate::<A>();
+    }
+    server_app.replicate::<B>();
+    let mut versioning = server_app.world_mut().resource_mut::<ProtocolVersioning>();
+    versioning.set::<B>(VersionedEntry::new(0).optional());
+    server_app.finish();
+    client_app.finish();
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app.world_mut().spawn((Replicated, A, B)).id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    server_app
+        .world_mut()
+        .entity_mut(server_entity)
+        .remove::<(A, B)>();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let mut replicated = client_app
+        .world_mut()
+        .query_filtered::<(), (With<Replicated>, Without<A>)>();
+    assert_eq!(
+        replicated.iter(client_app.world()).count(),
+        1,
+        "only supported components should be removed"
+    );
+}
+
+#[test]
+fn versioned_mismatch() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(RepliconSharedPlugin {
+                protocol_version: Some(0),
+                ..Default::default()
+            }),
+        ));
+    }
+    server_app
+        .add_client_message::<Test>(Channel::Ordered)
+        .finish();
+    client_app
+        .init_resource::<EventCounter<ProtocolMismatch>>()
+        .finish();
+
+    server_app.connect_client(&mut client_app);
+
+    let mut clients = server_app
+        .world_mut()
+        .query_filtered::<Entity, With<AuthorizedClient>>();
+    assert_eq!(clients.iter(server_app.world()).len(), 0);
+
+    let counter = client_app
+        .world()
+        .resource::<EventCounter<ProtocolMismatch>>();
+    assert_eq!(counter.events, 1);
+}
+
 #[test]
 fn custom_auth() {
     let mut server_app = App::new();
@@ -103,6 +368,7 @@ fn custom_auth() {
             StatesPlugin,
             RepliconPlugins.set(RepliconSharedPlugin {
                 auth_method: AuthMethod::Custom,
+                ..Default::default()
             }),
         ))
         .finish();
@@ -116,6 +382,110 @@ fn custom_auth() {
     assert_eq!(clients.iter(server_app.world()).count(), 1);
 }
 
+#[test]
+fn token_auth() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(RepliconSharedPlugin {
+                auth_method: AuthMethod::Token {
+                    timeout: Duration::from_secs(10),
+                },
+                ..Default::default()
+            }),
+        ))
+        .finish();
+    }
+    server_app.add_observer(verify_token);
+    client_app.insert_resource(AuthToken(b"secret".to_vec()));
+
+    server_app.connect_client(&mut client_app);
+
+    let mut clients = server_app
+        .world_mut()
+        .query_filtered::<Entity, With<AuthorizedClient>>();
+    assert_eq!(clients.iter(server_app.world()).len(), 1);
+}
+
+#[test]
+fn token_rejected() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(RepliconSharedPlugin {
+                auth_method: AuthMethod::Token {
+                    timeout: Duration::from_secs(10),
+                },
+                disconnect_reasons: true,
+                ..Default::default()
+            }),
+        ))
+        .finish();
+    }
+    server_app.add_observer(verify_token);
+    client_app
+        .init_resource::<EventCounter<AuthRejected>>()
+        .init_resource::<DisconnectReasons>()
+        .insert_resource(AuthToken(b"invalid".to_vec()));
+
+    server_app.connect_client(&mut client_app);
+
+    let mut clients = server_app
+        .world_mut()
+        .query_filtered::<Entity, With<AuthorizedClient>>();
+    assert_eq!(clients.iter(server_app.world()).len(), 0);
+
+    let disconnects = server_app.world().resource::<Messages<DisconnectRequest>>();
+    assert_eq!(disconnects.len(), 1);
+
+    let counter = client_app.world().resource::<EventCounter<AuthRejected>>();
+    assert_eq!(counter.events, 1);
+
+    server_app.disconnect_client(&mut client_app);
+
+    let reasons = client_app.world().resource::<DisconnectReasons>();
+    assert_eq!(
+        reasons.0,
+        [Some(DisconnectReason::AuthRejected("invalid token".into()))]
+    );
+}
+
+#[test]
+fn token_timeout() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(RepliconSharedPlugin {
+                auth_method: AuthMethod::Token {
+                    timeout: Duration::ZERO,
+                },
+                ..Default::default()
+            }),
+        ))
+        .finish();
+    }
+    client_app.init_resource::<EventCounter<AuthRejected>>();
+
+    server_app.connect_client(&mut client_app);
+
+    let mut clients = server_app
+        .world_mut()
+        .query_filtered::<Entity, With<AuthorizedClient>>();
+    assert_eq!(clients.iter(server_app.world()).len(), 0);
+
+    let counter = client_app.world().resource::<EventCounter<AuthRejected>>();
+    assert_eq!(counter.events, 1);
+}
+
 #[test]
 fn disabled_auth() {
     let mut app = App::new();
@@ -124,6 +494,7 @@ fn disabled_auth() {
         StatesPlugin,
         RepliconPlugins.set(RepliconSharedPlugin {
             auth_method: AuthMethod::None,
+            ..Default::default()
         }),
     ))
     .finish();
@@ -148,6 +519,23 @@ fn network_id_map() {
 #[derive(Message, Serialize, Deserialize)]
 struct Test;
 
+#[derive(Message, Serialize, Deserialize)]
+struct Other;
+
+#[derive(Component, Serialize, Deserialize)]
+struct A;
+
+#[derive(Component, Serialize, Deserialize)]
+struct B;
+
+fn verify_token(auth: On<AuthRequested>, mut commands: Commands) {
+    if auth.payload == b"secret" {
+        commands.trigger(ResolveAuth::accept(auth.client));
+    } else {
+        commands.trigger(ResolveAuth::reject(auth.client, "invalid token"));
+    }
+}
+
 #[derive(Resource)]
 struct EventCounter<E: Event> {
     events: usize,
@@ -166,3 +554,18 @@ impl<E: Event> FromWorld for EventCounter<E> {
         }
     }
 }
+
+#[derive(Resource)]
+struct DisconnectReasons(Vec<Option<DisconnectReason>>);
+
+impl FromWorld for DisconnectReasons {
+    fn from_world(world: &mut World) -> Self {
+        world.add_observer(
+            |disconnected: On<Disconnected>, mut reasons: ResMut<Self>| {
+                reasons.0.push(disconnected.reason.clone());
+            },
+        );
+
+        Self(Default::default())
+    }
+}
@@ -1,6 +1,9 @@
 use bevy::{prelude::*, state::app::StatesPlugin};
 use bevy_replicon::{
-    prelude::*, shared::server_entity_map::ServerEntityMap, test_app::ServerTestAppExt,
+    client::entity_pool::{EntityPool, Pooled},
+    prelude::*,
+    shared::server_entity_map::ServerEntityMap,
+    test_app::ServerTestAppExt,
 };
 use serde::{Deserialize, Serialize};
 use test_log::test;
@@ -114,6 +117,129 @@ fn after_spawn() {
     assert_eq!(replicated.iter(client_app.world()).len(), 0);
 }
 
+#[test]
+fn pooled() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .replicate::<TestComponent>()
+        .finish();
+    }
+    client_app.pool_replicated::<TestComponent>();
+
+    let mut pooled = client_app.world_mut().query::<&Pooled>();
+    assert_eq!(
+        pooled.iter(client_app.world()).len(),
+        0,
+        "staging entity should be spawned only when needed"
+    );
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, TestComponent))
+        .id();
+    let other_entity = server_app.world_mut().spawn(Replicated).id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let entity_map = client_app.world().resource::<ServerEntityMap>();
+    let client_entity = *entity_map.to_client().get(&server_entity).unwrap();
+
+    server_app.world_mut().despawn(server_entity);
+    server_app.world_mut().despawn(other_entity);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let entity_map = client_app.world().resource::<ServerEntityMap>();
+    assert!(entity_map.to_client().is_empty());
+    assert!(entity_map.to_server().is_empty());
+
+    let pool = client_app.world().resource::<EntityPool>();
+    assert_eq!(
+        pool.len(),
+        1,
+        "only entities with pooled component should be kept"
+    );
+
+    let pooled = client_app.world().entity(client_entity);
+    assert!(pooled.contains::<Pooled>());
+    assert!(!pooled.contains::<Replicated>());
+    assert!(!pooled.contains::<TestComponent>());
+
+    let unpooled_entity = server_app.world_mut().spawn(Replicated).id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let entity_map = client_app.world().resource::<ServerEntityMap>();
+    let unpooled_client_entity = *entity_map.to_client().get(&unpooled_entity).unwrap();
+    assert_ne!(
+        unpooled_client_entity, client_entity,
+        "entities without pooled component shouldn't take pooled entities"
+    );
+    assert!(
+        !client_app
+            .world()
+            .entity(unpooled_client_entity)
+            .contains::<Pooled>()
+    );
+    assert_eq!(client_app.world().resource::<EntityPool>().len(), 1);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, TestComponent))
+        .id();
+
+    let entities_count = client_app.world().entities().len();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    assert_eq!(
+        client_app.world().entities().len(),
+        entities_count,
+        "no entity should be spawned when reusing a pooled one"
+    );
+
+    let entity_map = client_app.world().resource::<ServerEntityMap>();
+    assert_eq!(
+        entity_map.to_client().get(&server_entity),
+        Some(&client_entity),
+        "pooled entity should be reused"
+    );
+    assert_eq!(
+        entity_map.to_server().get(&client_entity),
+        Some(&server_entity)
+    );
+
+    let pool = client_app.world().resource::<EntityPool>();
+    assert!(pool.is_empty());
+
+    let reused = client_app.world().entity(client_entity);
+    assert!(!reused.contains::<Pooled>());
+    assert!(reused.contains::<Replicated>());
+    assert!(reused.contains::<TestComponent>());
+
+    let mut replicated = client_app.world_mut().query::<&Replicated>();
+    assert_eq!(replicated.iter(client_app.world()).len(), 2);
+}
+
 #[test]
 fn signature() {
     let mut server_app = App::new();
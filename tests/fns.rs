@@ -6,7 +6,7 @@ use bevy_replicon::{
             command_markers::MarkerConfig,
             deferred_entity::DeferredEntity,
             registry::{
-                ReplicationRegistry, command_fns,
+                self, ReplicationRegistry, command_fns,
                 ctx::{DespawnCtx, WriteCtx},
                 test_fns::TestFnsEntityExt,
             },
@@ -344,6 +344,50 @@ fn despawn() {
     assert!(app.world().get::<Despawned>(id).is_some());
 }
 
+#[test]
+fn despawn_with_marker() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin, RepliconPlugins))
+        .register_marker::<Marker>()
+        .set_marker_despawn_fn::<Marker>(mark_despawned);
+
+    let tick = RepliconTick::default();
+    let entity = app.world_mut().spawn(Marker);
+    let id = entity.id();
+    entity.apply_despawn(tick);
+    assert!(app.world().get::<Despawned>(id).is_some());
+
+    let entity = app.world_mut().spawn_empty();
+    let id = entity.id();
+    entity.apply_despawn(tick);
+    assert!(
+        app.world().get_entity(id).is_err(),
+        "entity without marker should use the default function"
+    );
+}
+
+#[test]
+fn despawn_with_priority_marker() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin, RepliconPlugins))
+        .register_marker_with::<ReplaceMarker>(MarkerConfig {
+            priority: 1,
+            ..Default::default()
+        })
+        .register_marker::<Marker>()
+        .set_marker_despawn_fn::<ReplaceMarker>(mark_despawned)
+        .set_marker_despawn_fn::<Marker>(registry::despawn);
+
+    let tick = RepliconTick::default();
+    let entity = app.world_mut().spawn((Marker, ReplaceMarker));
+    let id = entity.id();
+    entity.apply_despawn(tick);
+    assert!(
+        app.world().get::<Despawned>(id).is_some(),
+        "marker with priority should take priority"
+    );
+}
+
 #[derive(Component, Deserialize, Serialize)]
 struct OriginalComponent;
 
@@ -229,6 +229,63 @@ fn mapped_new_entity() {
     assert_eq!(replicated.iter(client_app.world()).count(), 1);
 }
 
+#[test]
+fn ordered_children() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .replicate::<ChildOf>()
+        .replicate_order::<Children>()
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_parent = server_app
+        .world_mut()
+        .spawn((Replicated, children![Replicated, Replicated]))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    assert_children_eq(&server_app, &client_app, server_parent);
+
+    // Insert at the beginning to make the order differ from the insertion order.
+    let server_child = server_app.world_mut().spawn(Replicated).id();
+    server_app
+        .world_mut()
+        .entity_mut(server_parent)
+        .insert_child(0, server_child);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    assert_children_eq(&server_app, &client_app, server_parent);
+
+    // Reorder without changing relationships.
+    server_app
+        .world_mut()
+        .get_mut::<Children>(server_parent)
+        .unwrap()
+        .swap(0, 2);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    assert_children_eq(&server_app, &client_app, server_parent);
+}
+
 #[test]
 fn multiple_components() {
     let mut server_app = App::new();
@@ -607,3 +664,18 @@ fn replace(
 
     Ok(())
 }
+
+/// Asserts that children of a server entity are mapped to children of the client entity in the same order.
+fn assert_children_eq(server_app: &App, client_app: &App, server_parent: Entity) {
+    let entity_map = client_app.world().resource::<ServerEntityMap>();
+    let client_parent = entity_map.to_client()[&server_parent];
+    let server_children: Vec<_> = server_app
+        .world()
+        .get::<Children>(server_parent)
+        .unwrap()
+        .iter()
+        .map(|server_child| entity_map.to_client()[&server_child])
+        .collect();
+    let client_children = client_app.world().get::<Children>(client_parent).unwrap();
+    assert_eq!(**client_children, server_children);
+}
@@ -949,6 +949,60 @@ fn old_ignored() {
     );
 }
 
+#[test]
+fn dirty_collection() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin {
+                change_collection: ChangeCollection::Dirty,
+                ..ServerPlugin::new(PostUpdate)
+            }),
+        ))
+        .replicate::<BoolComponent>()
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, BoolComponent(false)))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let mut component = server_app
+        .world_mut()
+        .get_mut::<BoolComponent>(server_entity)
+        .unwrap();
+    component.0 = true;
+
+    server_app.update();
+
+    // Drop the mutate message.
+    let mut messages = server_app.world_mut().resource_mut::<ServerMessages>();
+    assert_eq!(messages.drain_sent().count(), 1);
+
+    // Entity is no longer dirty, but should be resent until acknowledged.
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let component = client_app
+        .world_mut()
+        .query::<&BoolComponent>()
+        .single(client_app.world())
+        .unwrap();
+    assert!(component.0, "mutated value should be updated on client");
+}
+
 #[test]
 fn acknowledgment() {
     let mut server_app = App::new();
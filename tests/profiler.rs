@@ -0,0 +1,69 @@
+use core::time::Duration;
+
+use bevy::{prelude::*, state::app::StatesPlugin};
+use bevy_replicon::{
+    prelude::*,
+    server::bandwidth_profiler::{BandwidthProfiler, BandwidthReport},
+    test_app::ServerTestAppExt,
+};
+use serde::{Deserialize, Serialize};
+use test_log::test;
+
+#[test]
+fn report() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .replicate::<SmallComponent>()
+        .replicate::<LargeComponent>()
+        .finish();
+    }
+    server_app.insert_resource(BandwidthProfiler::new(Duration::ZERO));
+
+    server_app.connect_client(&mut client_app);
+
+    server_app
+        .world_mut()
+        .spawn((Replicated, SmallComponent(1), LargeComponent([1; 16])));
+
+    let mut clients = server_app
+        .world_mut()
+        .query_filtered::<Entity, With<ConnectedClient>>();
+    let client = clients.single(server_app.world()).unwrap();
+
+    server_app.update();
+
+    let report = server_app.world().resource::<BandwidthReport>();
+    assert_eq!(report.components.len(), 2);
+
+    let [large, small] = report.components.as_slice() else {
+        panic!("report should contain both components");
+    };
+    assert!(large.name.ends_with("LargeComponent"));
+    assert!(small.name.ends_with("SmallComponent"));
+    assert!(large.bytes > small.bytes);
+    assert_eq!(large.count, 1);
+    assert_eq!(small.count, 1);
+    assert_eq!(report.total_bytes(), large.bytes + small.bytes);
+
+    assert_eq!(large.clients, [(client, large.bytes)]);
+
+    server_app.update();
+
+    let report = server_app.world().resource::<BandwidthReport>();
+    assert!(
+        report.components.is_empty(),
+        "data should be reset after each window"
+    );
+}
+
+#[derive(Component, Deserialize, Serialize)]
+struct SmallComponent(u8);
+
+#[derive(Component, Deserialize, Serialize)]
+struct LargeComponent([u8; 16]);
@@ -485,6 +485,58 @@ fn hidden() {
     );
 }
 
+#[test]
+fn streamed_deferred() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin {
+                initial_sync: InitialSync::Streamed { max_bytes: 1 },
+                ..ServerPlugin::new(PostUpdate)
+            }),
+        ))
+        .replicate::<A>()
+        .replicate::<B>()
+        .finish();
+    }
+
+    let server_entities: Vec<_> = server_app
+        .world_mut()
+        .spawn_batch([(Replicated, A, B), (Replicated, A, B), (Replicated, A, B)])
+        .collect();
+
+    // Sends the first entity.
+    server_app.connect_client(&mut client_app);
+
+    for entity in server_entities {
+        server_app.world_mut().entity_mut(entity).remove::<B>();
+    }
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let mut replicated = client_app.world_mut().query::<&Replicated>();
+    assert_eq!(
+        replicated.iter(client_app.world()).len(),
+        2,
+        "removals shouldn't be sent for deferred entities"
+    );
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let mut components = client_app
+        .world_mut()
+        .query_filtered::<&A, (With<Replicated>, Without<B>)>();
+    assert_eq!(components.iter(client_app.world()).len(), 3);
+}
+
 #[derive(Component, Deserialize, Serialize)]
 struct A;
 
@@ -0,0 +1,211 @@
+use core::time::Duration;
+
+use bevy::{prelude::*, state::app::StatesPlugin, time::TimeUpdateStrategy};
+use bevy_replicon::{
+    prelude::*,
+    shared::message::request::{ClientRequests, Request, RequestError, Response},
+    test_app::ServerTestAppExt,
+};
+use serde::{Deserialize, Serialize};
+use test_log::test;
+
+#[test]
+fn regular() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .add_client_request::<TestRequest, TestResponse>(Channel::Ordered)
+        .finish();
+    }
+    server_app.add_observer(respond);
+    client_app.init_resource::<Responses>();
+
+    server_app.connect_client(&mut client_app);
+
+    let id = client_app.world_mut().client_request(TestRequest(1));
+    assert!(
+        client_app
+            .world()
+            .resource::<ClientRequests<TestRequest>>()
+            .is_pending(id)
+    );
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let responses = client_app.world().resource::<Responses>();
+    assert_eq!(responses.0, [(id.get(), Ok(TestResponse(2)))]);
+
+    let requests = client_app.world().resource::<ClientRequests<TestRequest>>();
+    assert!(requests.is_empty());
+}
+
+#[test]
+fn commands() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .add_client_request::<TestRequest, TestResponse>(Channel::Ordered)
+        .finish();
+    }
+    server_app.add_observer(respond);
+    client_app.init_resource::<Responses>();
+
+    server_app.connect_client(&mut client_app);
+
+    client_app.add_systems(
+        Update,
+        (|mut commands: Commands, mut requests: ResMut<ClientRequests<TestRequest>>| {
+            requests.send(&mut commands, TestRequest(1));
+            requests.send(&mut commands, TestRequest(1));
+        })
+        .run_if(run_once),
+    );
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let responses = client_app.world().resource::<Responses>();
+    assert_eq!(
+        responses.0,
+        [(0, Ok(TestResponse(2))), (1, Ok(TestResponse(2)))],
+        "IDs should be assigned sequentially within the app"
+    );
+}
+
+#[test]
+fn timeout() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .add_client_request::<TestRequest, TestResponse>(Channel::Ordered)
+        .finish();
+    }
+    server_app.add_observer(respond);
+    client_app
+        .init_resource::<Responses>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs(1)));
+    client_app
+        .world_mut()
+        .resource_mut::<ClientRequests<TestRequest>>()
+        .timeout = Duration::ZERO;
+
+    server_app.connect_client(&mut client_app);
+
+    let id = client_app.world_mut().client_request(TestRequest(1));
+
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let responses = client_app.world().resource::<Responses>();
+    assert_eq!(
+        responses.0,
+        [(id.get(), Err(RequestError::Timeout))],
+        "late response should be ignored"
+    );
+}
+
+#[test]
+fn disconnect() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .add_client_request::<TestRequest, TestResponse>(Channel::Ordered)
+        .finish();
+    }
+    client_app.init_resource::<Responses>();
+
+    server_app.connect_client(&mut client_app);
+
+    let id = client_app.world_mut().client_request(TestRequest(1));
+
+    client_app.update();
+    server_app.disconnect_client(&mut client_app);
+
+    let responses = client_app.world().resource::<Responses>();
+    assert_eq!(responses.0, [(id.get(), Err(RequestError::Disconnected))]);
+
+    let requests = client_app.world().resource::<ClientRequests<TestRequest>>();
+    assert!(requests.is_empty());
+}
+
+#[test]
+fn local_sending() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin, RepliconPlugins))
+        .add_client_request::<TestRequest, TestResponse>(Channel::Ordered)
+        .add_observer(respond)
+        .init_resource::<Responses>()
+        .finish();
+
+    let id = app.world_mut().client_request(TestRequest(1));
+
+    // Requires 3 updates because local sending runs in `PostUpdate`
+    // and triggering runs in `PreUpdate` on both sides.
+    app.update();
+    app.update();
+    app.update();
+
+    let responses = app.world().resource::<Responses>();
+    assert_eq!(responses.0, [(id.get(), Ok(TestResponse(2)))]);
+}
+
+fn respond(request: On<FromClient<Request<TestRequest>>>, mut commands: Commands) {
+    commands.server_respond(
+        request.client_id,
+        request.id,
+        TestResponse(request.request.0 + 1),
+    );
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct TestRequest(u32);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct TestResponse(u32);
+
+#[derive(Resource)]
+struct Responses(Vec<(u32, Result<TestResponse, RequestError>)>);
+
+impl FromWorld for Responses {
+    fn from_world(world: &mut World) -> Self {
+        world.add_observer(
+            |response: On<Response<TestRequest, TestResponse>>, mut responses: ResMut<Self>| {
+                responses
+                    .0
+                    .push((response.id.get(), response.result.clone()));
+            },
+        );
+
+        Self(Default::default())
+    }
+}
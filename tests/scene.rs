@@ -1,6 +1,9 @@
-use bevy::{prelude::*, state::app::StatesPlugin};
-use bevy_replicon::{prelude::*, scene};
-use serde::{Deserialize, Serialize};
+use bevy::{asset::ron, prelude::*, scene::serde::SceneDeserializer, state::app::StatesPlugin};
+use bevy_replicon::{
+    prelude::*,
+    scene::{self, SignatureSnapshot},
+};
+use serde::{Deserialize, Serialize, de::DeserializeSeed};
 use test_log::test;
 
 #[test]
@@ -106,6 +109,160 @@ fn entity_update() {
     assert_eq!(dyn_entity.components.len(), 2);
 }
 
+#[test]
+fn restore() {
+    let mut app = App::new();
+    app.add_plugins((StatesPlugin, RepliconPlugins))
+        .register_type::<TestComponent>()
+        .register_type::<MappedComponent>()
+        .replicate::<TestComponent>()
+        .replicate::<MappedComponent>()
+        .finish();
+
+    let entity = app
+        .world_mut()
+        .spawn((Replicated, TestComponent, Signature::from(0)))
+        .id();
+    app.world_mut().spawn((Replicated, MappedComponent(entity)));
+
+    let mut scene = DynamicScene::default();
+    scene::replicate_into(&mut scene, app.world());
+
+    let registry = app.world().resource::<AppTypeRegistry>();
+    let type_registry = &*registry.read();
+    let serialized = scene
+        .serialize(type_registry)
+        .expect("scene should be serialized");
+    let scene_deserializer = SceneDeserializer { type_registry };
+    let mut deserializer =
+        ron::Deserializer::from_str(&serialized).expect("scene should be serialized as valid ron");
+    let scene = scene_deserializer
+        .deserialize(&mut deserializer)
+        .expect("ron should be convertible to scene");
+
+    let mut restored_app = App::new();
+    restored_app
+        .add_plugins((StatesPlugin, RepliconPlugins))
+        .register_type::<TestComponent>()
+        .register_type::<MappedComponent>()
+        .replicate::<TestComponent>()
+        .replicate::<MappedComponent>()
+        .finish();
+
+    // Shift entity IDs.
+    restored_app.world_mut().spawn_empty();
+
+    let entity_map = scene::restore_replicated(&scene, restored_app.world_mut())
+        .expect("scene should be restored");
+    assert_eq!(entity_map.len(), 2);
+
+    let restored_entity = entity_map[&entity];
+    let restored_entity_ref = restored_app.world().entity(restored_entity);
+    assert!(restored_entity_ref.contains::<Replicated>());
+    assert!(restored_entity_ref.contains::<TestComponent>());
+    assert!(restored_entity_ref.contains::<Signature>());
+    assert!(!restored_entity_ref.contains::<SignatureSnapshot>());
+
+    let mut mapped = restored_app
+        .world_mut()
+        .query_filtered::<&MappedComponent, With<Replicated>>();
+    let mapped_component = mapped.single(restored_app.world()).unwrap();
+    assert_eq!(mapped_component.0, restored_entity);
+
+    let mut restored_scene = DynamicScene::default();
+    scene::replicate_into(&mut restored_scene, restored_app.world());
+    assert_eq!(
+        find_snapshot(&restored_scene),
+        find_snapshot(&scene),
+        "signature hash should be preserved"
+    );
+}
+
+#[cfg(all(feature = "client", feature = "server"))]
+#[test]
+fn client_state() {
+    use bevy::ecs::entity::EntityHashMap;
+    use bevy_replicon::{
+        client::{ServerUpdateTick, confirm_history::ConfirmHistory},
+        scene::ServerEntitySnapshot,
+        test_app::ServerTestAppExt,
+    };
+
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .register_type::<TestComponent>()
+        .replicate::<TestComponent>()
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, TestComponent))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let mut scene = DynamicScene::default();
+    scene::replicate_client_into(&mut scene, client_app.world());
+
+    assert_eq!(scene.entities.len(), 1);
+    assert_eq!(scene.resources.len(), 1);
+
+    let update_tick = scene.resources[0]
+        .try_downcast_ref::<ServerUpdateTick>()
+        .unwrap();
+    assert_eq!(
+        **update_tick,
+        **client_app.world().resource::<ServerUpdateTick>()
+    );
+
+    let dyn_entity = &scene.entities[0];
+    assert_eq!(dyn_entity.components.len(), 3);
+    let snapshot = dyn_entity
+        .components
+        .iter()
+        .find_map(|component| component.try_downcast_ref::<ServerEntitySnapshot>())
+        .unwrap();
+    assert_eq!(snapshot.0, server_entity);
+
+    let history = dyn_entity
+        .components
+        .iter()
+        .find_map(|component| component.try_downcast_ref::<ConfirmHistory>())
+        .unwrap();
+    assert_eq!(history.last_tick(), **update_tick);
+
+    // Server entities shouldn't be mapped on loading.
+    let mut world = World::new();
+    world.insert_resource(client_app.world().resource::<AppTypeRegistry>().clone());
+    world.spawn_empty();
+    scene
+        .write_to_world(&mut world, &mut EntityHashMap::default())
+        .unwrap();
+    let mut snapshots = world.query::<&ServerEntitySnapshot>();
+    assert_eq!(snapshots.single(&world).unwrap().0, server_entity);
+}
+
+fn find_snapshot(scene: &DynamicScene) -> SignatureSnapshot {
+    scene
+        .entities
+        .iter()
+        .flat_map(|entity| &entity.components)
+        .find_map(|component| component.try_downcast_ref::<SignatureSnapshot>())
+        .copied()
+        .expect("scene should contain a signature snapshot")
+}
+
 #[derive(Component, Default, Deserialize, Reflect, Serialize)]
 #[reflect(Component)]
 struct TestComponent;
@@ -117,3 +274,7 @@ struct ReflectedComponent;
 /// Component that have `Reflect` derive, but without `#[reflect(Component)]`
 #[derive(Component, Default, Deserialize, Reflect, Serialize)]
 struct NonReflectedComponent;
+
+#[derive(Component, Deserialize, Reflect, Serialize)]
+#[reflect(Component)]
+struct MappedComponent(#[entities] Entity);
@@ -76,6 +76,58 @@ fn mapped() {
     assert_eq!(mapped_entities, [client_entity]);
 }
 
+#[test]
+fn entity() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .add_server_entity_event::<EntityTest>(Channel::Ordered)
+        .finish();
+    }
+    client_app.init_resource::<Targets>();
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app.world_mut().spawn(Replicated).id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let client_entity = *client_app
+        .world()
+        .resource::<ServerEntityMap>()
+        .to_client()
+        .get(&server_entity)
+        .unwrap();
+
+    client_app.world_mut().entity_mut(client_entity).observe(
+        |event: On<EntityTest>, mut targets: ResMut<Targets>| {
+            targets.push(event.entity);
+        },
+    );
+
+    server_app.world_mut().server_trigger(ToClients {
+        mode: SendMode::Broadcast,
+        message: EntityTest {
+            entity: server_entity,
+        },
+    });
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let targets = client_app.world().resource::<Targets>();
+    assert_eq!(**targets, [client_entity]);
+}
+
 #[test]
 fn without_plugins() {
     let mut server_app = App::new();
@@ -209,6 +261,14 @@ struct Independent;
 #[derive(Event, Serialize, Deserialize, MapEntities, Clone)]
 struct WithEntity(#[entities] Entity);
 
+#[derive(EntityEvent, Serialize, Deserialize)]
+struct EntityTest {
+    entity: Entity,
+}
+
+#[derive(Resource, Default, Deref, DerefMut)]
+struct Targets(Vec<Entity>);
+
 #[derive(Resource)]
 struct EventReader<E: Event> {
     events: Vec<E>,
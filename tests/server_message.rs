@@ -1,4 +1,9 @@
-use bevy::{ecs::entity::MapEntities, prelude::*, state::app::StatesPlugin, time::TimePlugin};
+use bevy::{
+    ecs::{entity::MapEntities, schedule::ScheduleLabel},
+    prelude::*,
+    state::app::StatesPlugin,
+    time::TimePlugin,
+};
 use bevy_replicon::{
     client::ServerUpdateTick,
     prelude::*,
@@ -26,15 +31,29 @@ fn regular() {
     server_app.connect_client(&mut client_app);
 
     let client = **client_app.world().resource::<TestClientEntity>();
+    let visible = server_app.world_mut().spawn(Replicated).id();
+    let hidden = server_app.world_mut().spawn(Replicated).id();
+    server_app
+        .world_mut()
+        .get_mut::<ClientVisibility>(client)
+        .unwrap()
+        .set_visibility(hidden, false);
+
     for (mode, messages_count) in [
         (SendMode::Broadcast, 1),
         (SendMode::Direct(ClientId::Server), 0),
         (SendMode::Direct(client.into()), 1),
         (SendMode::BroadcastExcept(ClientId::Server), 1),
         (SendMode::BroadcastExcept(client.into()), 0),
+        (SendMode::List(vec![ClientId::Server, client.into()]), 1),
+        (SendMode::List(vec![ClientId::Server]), 0),
+        (SendMode::Visible(visible), 1),
+        (SendMode::Visible(hidden), 0),
+        (SendMode::with_component::<AuthorizedClient>(), 1),
+        (SendMode::with_component::<Replicated>(), 0),
     ] {
         server_app.world_mut().write_message(ToClients {
-            mode,
+            mode: mode.clone(),
             message: Test,
         });
 
@@ -52,6 +71,137 @@ fn regular() {
     }
 }
 
+#[test]
+fn list() {
+    let mut server_app = App::new();
+    let mut client_app1 = App::new();
+    let mut client_app2 = App::new();
+    let mut client_app3 = App::new();
+    for app in [
+        &mut server_app,
+        &mut client_app1,
+        &mut client_app2,
+        &mut client_app3,
+    ] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .add_server_message::<Test>(Channel::Ordered)
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app1);
+    server_app.connect_client(&mut client_app2);
+    server_app.connect_client(&mut client_app3);
+
+    let client1 = **client_app1.world().resource::<TestClientEntity>();
+    let client2 = **client_app2.world().resource::<TestClientEntity>();
+    server_app.world_mut().write_message(ToClients {
+        mode: SendMode::List(vec![client1.into(), client2.into(), client1.into()]),
+        message: Test,
+    });
+
+    server_app.update();
+    for (client_app, messages_count) in [
+        (&mut client_app1, 1),
+        (&mut client_app2, 1),
+        (&mut client_app3, 0),
+    ] {
+        server_app.exchange_with_client(client_app);
+        client_app.update();
+
+        let messages = client_app.world().resource::<Messages<Test>>();
+        assert_eq!(messages.len(), messages_count);
+    }
+}
+
+#[test]
+fn shared_channel() {
+    const SHARED: SharedChannel = SharedChannel::new("shared", Channel::Ordered);
+
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .add_server_message::<Test>(SHARED)
+        .add_server_message::<Independent>(SHARED)
+        .make_message_independent::<Independent>()
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    server_app.world_mut().write_message(ToClients {
+        mode: SendMode::Broadcast,
+        message: Test,
+    });
+    server_app.world_mut().write_message(ToClients {
+        mode: SendMode::Broadcast,
+        message: Independent,
+    });
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let mut messages = client_app.world_mut().resource_mut::<Messages<Test>>();
+    assert_eq!(messages.drain().count(), 1);
+
+    let mut messages = client_app
+        .world_mut()
+        .resource_mut::<Messages<Independent>>();
+    assert_eq!(messages.drain().count(), 1);
+}
+
+#[test]
+fn ordered_shared_channel() {
+    const SHARED: SharedChannel = SharedChannel::new("shared", Channel::Ordered);
+
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .add_server_message::<Keyed>(SHARED)
+        .add_server_message::<Counter>(SHARED)
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    for value in 0..3 {
+        server_app.world_mut().write_message(ToClients {
+            mode: SendMode::Broadcast,
+            message: Keyed { key: 0, value },
+        });
+        server_app.world_mut().write_message(ToClients {
+            mode: SendMode::Broadcast,
+            message: Counter(value),
+        });
+    }
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let mut messages = client_app.world_mut().resource_mut::<Messages<Keyed>>();
+    let values: Vec<_> = messages.drain().map(|keyed| keyed.value).collect();
+    assert_eq!(values, [0, 1, 2]);
+
+    let mut messages = client_app.world_mut().resource_mut::<Messages<Counter>>();
+    let values: Vec<_> = messages.drain().map(|counter| counter.0).collect();
+    assert_eq!(values, [0, 1, 2]);
+}
+
 #[test]
 fn mapped() {
     let mut server_app = App::new();
@@ -134,7 +284,7 @@ fn without_plugins() {
         (SendMode::BroadcastExcept(client.into()), 0),
     ] {
         server_app.world_mut().write_message(ToClients {
-            mode,
+            mode: mode.clone(),
             message: Test,
         });
 
@@ -171,9 +321,16 @@ fn local_sending() {
         (SendMode::Direct(PLACEHOLDER_CLIENT_ID), 0),
         (SendMode::BroadcastExcept(ClientId::Server), 0),
         (SendMode::BroadcastExcept(PLACEHOLDER_CLIENT_ID), 1),
+        (
+            SendMode::List(vec![ClientId::Server, PLACEHOLDER_CLIENT_ID]),
+            1,
+        ),
+        (SendMode::List(vec![PLACEHOLDER_CLIENT_ID]), 0),
+        (SendMode::Visible(CLIENT_ENTITY), 1),
+        (SendMode::with_component::<AuthorizedClient>(), 0),
     ] {
         app.world_mut().write_message(ToClients {
-            mode,
+            mode: mode.clone(),
             message: Test,
         });
 
@@ -439,13 +596,15 @@ fn independent() {
         (SendMode::Direct(client.into()), 1),
         (SendMode::BroadcastExcept(ClientId::Server), 1),
         (SendMode::BroadcastExcept(client.into()), 0),
+        (SendMode::List(vec![client.into()]), 1),
+        (SendMode::with_component::<AuthorizedClient>(), 1),
     ] {
         server_app.world_mut().write_message(ToClients {
-            mode,
+            mode: mode.clone(),
             message: Test,
         });
         server_app.world_mut().write_message(ToClients {
-            mode,
+            mode: mode.clone(),
             message: Independent,
         });
 
@@ -470,6 +629,274 @@ fn independent() {
     }
 }
 
+#[test]
+fn budget_drop() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin {
+                message_budget: Some(1),
+                ..ServerPlugin::new(PostUpdate)
+            }),
+        ))
+        .add_server_message::<Test>(Channel::Unreliable)
+        .add_server_message::<Important>(Channel::Unreliable)
+        .set_message_priority::<Important>(1)
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    server_app.world_mut().write_message(ToClients {
+        mode: SendMode::Broadcast,
+        message: Test,
+    });
+    server_app.world_mut().write_message(ToClients {
+        mode: SendMode::Broadcast,
+        message: Important,
+    });
+
+    for _ in 0..2 {
+        server_app.update();
+        server_app.exchange_with_client(&mut client_app);
+        client_app.update();
+        server_app.exchange_with_client(&mut client_app);
+    }
+
+    let messages = client_app.world().resource::<Messages<Important>>();
+    assert_eq!(messages.len(), 1);
+
+    let messages = client_app.world().resource::<Messages<Test>>();
+    assert!(
+        messages.is_empty(),
+        "low-priority unreliable message should be dropped"
+    );
+}
+
+#[test]
+fn budget_defer() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin {
+                message_budget: Some(1),
+                ..ServerPlugin::new(PostUpdate)
+            }),
+        ))
+        .add_server_message::<Test>(Channel::Ordered)
+        .add_server_message::<Important>(Channel::Ordered)
+        .set_message_priority::<Important>(1)
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    server_app.world_mut().write_message(ToClients {
+        mode: SendMode::Broadcast,
+        message: Test,
+    });
+    server_app.world_mut().write_message(ToClients {
+        mode: SendMode::Broadcast,
+        message: Important,
+    });
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let mut messages = client_app.world_mut().resource_mut::<Messages<Important>>();
+    assert_eq!(messages.drain().count(), 1);
+
+    let mut messages = client_app.world_mut().resource_mut::<Messages<Test>>();
+    assert_eq!(
+        messages.drain().count(),
+        0,
+        "low-priority message should be deferred"
+    );
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let messages = client_app.world().resource::<Messages<Test>>();
+    assert_eq!(messages.len(), 1);
+}
+
+#[test]
+fn budget_shared_channel() {
+    const SHARED: SharedChannel = SharedChannel::new("shared", Channel::Unreliable);
+
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin {
+                message_budget: Some(1),
+                ..ServerPlugin::new(PostUpdate)
+            }),
+        ))
+        .add_server_message::<Test>(SHARED)
+        .add_server_message::<Important>(Channel::Ordered)
+        .add_server_message::<Independent>(SHARED)
+        .set_message_priority::<Important>(1)
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    server_app.world_mut().write_message(ToClients {
+        mode: SendMode::Broadcast,
+        message: Test,
+    });
+    server_app.world_mut().write_message(ToClients {
+        mode: SendMode::Broadcast,
+        message: Independent,
+    });
+    server_app.world_mut().write_message(ToClients {
+        mode: SendMode::Broadcast,
+        message: Important,
+    });
+
+    for _ in 0..2 {
+        server_app.update();
+        server_app.exchange_with_client(&mut client_app);
+        client_app.update();
+        server_app.exchange_with_client(&mut client_app);
+    }
+
+    let messages = client_app.world().resource::<Messages<Important>>();
+    assert_eq!(messages.len(), 1);
+
+    let messages = client_app.world().resource::<Messages<Test>>();
+    assert!(
+        messages.is_empty(),
+        "low-priority message on unreliable shared channel should be dropped"
+    );
+
+    let messages = client_app.world().resource::<Messages<Independent>>();
+    assert!(
+        messages.is_empty(),
+        "low-priority message on unreliable shared channel should be dropped"
+    );
+}
+
+#[test]
+fn coalesce() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .add_server_message::<Test>(Channel::Unreliable)
+        .coalesce_server_message::<Test>()
+        .add_server_message::<Keyed>(Channel::Unreliable)
+        .coalesce_server_message_by::<Keyed>(|keyed| keyed.key.into())
+        .add_server_message::<Independent>(Channel::Unreliable)
+        .make_message_independent::<Independent>()
+        .coalesce_server_message::<Independent>()
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    server_app.world_mut().write_message(ToClients {
+        mode: SendMode::Broadcast,
+        message: Test,
+    });
+    server_app.world_mut().write_message(ToClients {
+        mode: SendMode::Broadcast,
+        message: Test,
+    });
+    for _ in 0..2 {
+        server_app.world_mut().write_message(ToClients {
+            mode: SendMode::Broadcast,
+            message: Independent,
+        });
+    }
+    for (key, value, mode) in [
+        (0, 1, SendMode::Broadcast),
+        (1, 2, SendMode::Broadcast),
+        (0, 3, SendMode::Broadcast),
+        (1, 4, SendMode::Direct(ClientId::Server)),
+    ] {
+        server_app.world_mut().write_message(ToClients {
+            mode,
+            message: Keyed { key, value },
+        });
+    }
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let messages = client_app.world().resource::<Messages<Test>>();
+    assert_eq!(messages.len(), 1);
+
+    let messages = client_app.world().resource::<Messages<Independent>>();
+    assert_eq!(messages.len(), 1);
+
+    let mut messages = client_app.world_mut().resource_mut::<Messages<Keyed>>();
+    let values: Vec<_> = messages.drain().map(|keyed| keyed.value).collect();
+    assert_eq!(
+        values,
+        [2, 3],
+        "message directed to another client shouldn't replace the received one"
+    );
+}
+
+#[test]
+fn coalesce_buffered() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(ManualTick)),
+        ))
+        .add_server_message::<Keyed>(Channel::Unreliable)
+        .coalesce_server_message_by::<Keyed>(|keyed| keyed.key.into())
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    // Write messages over multiple updates within the same tick.
+    for (key, value) in [(0, 1), (1, 2), (0, 3)] {
+        server_app.world_mut().write_message(ToClients {
+            mode: SendMode::Broadcast,
+            message: Keyed { key, value },
+        });
+        server_app.update();
+    }
+
+    server_app
+        .world_mut()
+        .resource_mut::<ServerTick>()
+        .increment();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let mut messages = client_app.world_mut().resource_mut::<Messages<Keyed>>();
+    let values: Vec<_> = messages.drain().map(|keyed| keyed.value).collect();
+    assert_eq!(values, [2, 3]);
+}
+
 #[test]
 fn before_started_replication() {
     let mut server_app = App::new();
@@ -482,6 +909,7 @@ fn before_started_replication() {
                 .set(ServerPlugin::new(PostUpdate))
                 .set(RepliconSharedPlugin {
                     auth_method: AuthMethod::Custom,
+                    ..Default::default()
                 }),
         ))
         .add_server_message::<Test>(Channel::Ordered)
@@ -523,6 +951,7 @@ fn independent_before_started_replication() {
                 .set(ServerPlugin::new(PostUpdate))
                 .set(RepliconSharedPlugin {
                     auth_method: AuthMethod::Custom,
+                    ..Default::default()
                 }),
         ))
         .add_server_message::<Test>(Channel::Ordered)
@@ -608,11 +1037,26 @@ fn different_ticks() {
     assert_eq!(messages2.len(), 1);
 }
 
+#[derive(ScheduleLabel, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+struct ManualTick;
+
 #[derive(Message, Serialize, Deserialize)]
 struct Test;
 
 #[derive(Message, Serialize, Deserialize)]
 struct Independent;
 
+#[derive(Message, Serialize, Deserialize)]
+struct Important;
+
+#[derive(Message, Serialize, Deserialize)]
+struct Counter(u8);
+
+#[derive(Message, Serialize, Deserialize)]
+struct Keyed {
+    key: u8,
+    value: u8,
+}
+
 #[derive(Message, Serialize, Deserialize, MapEntities)]
 struct WithEntity(#[entities] Entity);
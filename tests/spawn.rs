@@ -232,6 +232,55 @@ fn before_connection() {
     assert_eq!(components.iter(client_app.world()).count(), 1);
 }
 
+#[test]
+fn streamed_before_connection() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin {
+                initial_sync: InitialSync::Streamed { max_bytes: 1 },
+                ..ServerPlugin::new(PostUpdate)
+            }),
+        ))
+        .replicate::<A>()
+        .finish();
+    }
+    client_app.init_resource::<SyncFinished>().add_observer(
+        |_on: On<InitialSyncFinished>, mut finished: ResMut<SyncFinished>| {
+            finished.0 += 1;
+        },
+    );
+
+    server_app.world_mut().spawn_batch([(Replicated, A); 3]);
+
+    server_app.update();
+
+    server_app.connect_client(&mut client_app);
+
+    let mut components = client_app.world_mut().query::<(&Replicated, &A)>();
+    for synced in 1..=3 {
+        assert_eq!(client_app.world().resource::<SyncFinished>().0, 0);
+
+        server_app.update();
+        server_app.exchange_with_client(&mut client_app);
+        client_app.update();
+
+        assert_eq!(
+            components.iter(client_app.world()).count(),
+            synced,
+            "only one entity should fit into a chunk"
+        );
+        let progress = client_app.world().resource::<InitialSyncProgress>();
+        assert_eq!(progress.synced, synced);
+        assert_eq!(progress.total, 3);
+    }
+
+    assert_eq!(client_app.world().resource::<SyncFinished>().0, 1);
+}
+
 #[test]
 fn signature() {
     let mut server_app = App::new();
@@ -369,6 +418,7 @@ fn before_started_replication() {
             RepliconPlugins
                 .set(RepliconSharedPlugin {
                     auth_method: AuthMethod::Custom,
+                    ..Default::default()
                 })
                 .set(ServerPlugin::new(PostUpdate)),
         ))
@@ -417,6 +467,7 @@ fn after_started_replication() {
             RepliconPlugins
                 .set(RepliconSharedPlugin {
                     auth_method: AuthMethod::Custom,
+                    ..Default::default()
                 })
                 .set(ServerPlugin::new(PostUpdate)),
         ))
@@ -448,8 +499,11 @@ fn after_started_replication() {
     assert_eq!(components.iter(client_app.world()).count(), 1);
 }
 
-#[derive(Component, Deserialize, Serialize)]
+#[derive(Component, Deserialize, Serialize, Clone, Copy)]
 struct A;
 
 #[derive(Component, Deserialize, Serialize)]
 struct B;
+
+#[derive(Resource, Default)]
+struct SyncFinished(usize);
@@ -53,5 +53,118 @@ fn client_stats() {
     assert_eq!(stats.bytes, 24);
 }
 
+#[test]
+fn server_stats() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin::new(PostUpdate)),
+        ))
+        .replicate::<TestComponent>()
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, TestComponent))
+        .id();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    server_app
+        .world_mut()
+        .get_mut::<TestComponent>(server_entity)
+        .unwrap()
+        .set_changed();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let stats = *server_app.world().resource::<ServerReplicationStats>();
+    assert_eq!(stats.update_entities, 1);
+    assert_eq!(stats.update_components, 1);
+    assert_eq!(stats.update_messages, 1);
+    assert_eq!(stats.mutate_entities, 1);
+    assert_eq!(stats.mutate_components, 1);
+    assert_eq!(stats.mutate_messages, 1);
+    assert_eq!(stats.resends, 0);
+    assert_eq!(stats.pending_acks, 1);
+
+    let client_replication_stats = client_app.world().resource::<ClientReplicationStats>();
+    assert_eq!(
+        stats.update_bytes + stats.mutate_bytes,
+        client_replication_stats.bytes
+    );
+
+    let mut clients = server_app.world_mut().query::<&ServerReplicationStats>();
+    let client_stats = *clients.single(server_app.world()).unwrap();
+    assert_eq!(client_stats.update_bytes, stats.update_bytes);
+    assert_eq!(client_stats.mutate_bytes, stats.mutate_bytes);
+    assert_eq!(client_stats.pending_acks, 1);
+}
+
+#[test]
+fn server_client_stats_without_resource() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    server_app
+        .add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins
+                .build()
+                .disable::<ServerDiagnosticsPlugin>()
+                .set(ServerPlugin::new(PostUpdate)),
+        ))
+        .replicate::<TestComponent>()
+        .finish();
+    client_app
+        .add_plugins((MinimalPlugins, StatesPlugin, RepliconPlugins))
+        .replicate::<TestComponent>()
+        .finish();
+
+    server_app.connect_client(&mut client_app);
+
+    assert!(
+        !server_app
+            .world()
+            .contains_resource::<ServerReplicationStats>()
+    );
+    let mut clients = server_app
+        .world_mut()
+        .query_filtered::<Entity, With<ConnectedClient>>();
+    let client = clients.single(server_app.world()).unwrap();
+    server_app
+        .world_mut()
+        .entity_mut(client)
+        .insert(ServerReplicationStats::default());
+
+    server_app.world_mut().spawn((Replicated, TestComponent));
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let client_stats = *server_app
+        .world()
+        .get::<ServerReplicationStats>(client)
+        .unwrap();
+    assert_eq!(client_stats.update_entities, 1);
+    assert_eq!(client_stats.update_components, 1);
+    assert_eq!(client_stats.update_messages, 1);
+
+    let client_replication_stats = client_app.world().resource::<ClientReplicationStats>();
+    assert_eq!(client_stats.update_bytes, client_replication_stats.bytes);
+}
+
 #[derive(Component, Deserialize, Serialize)]
 struct TestComponent;
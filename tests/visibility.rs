@@ -1,6 +1,10 @@
 use bevy::{prelude::*, state::app::StatesPlugin};
 use bevy_replicon::{
     prelude::*,
+    shared::{
+        replication::registry::{ReplicationRegistry, ctx::DespawnCtx},
+        server_entity_map::ServerEntityMap,
+    },
     test_app::{ServerTestAppExt, TestClientEntity},
 };
 use serde::{Deserialize, Serialize};
@@ -265,6 +269,388 @@ fn whitelist_with_despawn() {
     assert!(!visibility.is_visible(server_entity));
 }
 
+#[test]
+fn visibility_lost() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin {
+                visibility_policy: VisibilityPolicy::Whitelist,
+                ..ServerPlugin::new(PostUpdate)
+            }),
+        ))
+        .replicate::<TestComponent>()
+        .finish();
+    }
+
+    let mut registry = client_app.world_mut().resource_mut::<ReplicationRegistry>();
+    registry.visibility_lost = keep_ghost;
+    client_app.add_observer(|left: On<EntityLeftInterest>, mut commands: Commands| {
+        commands.entity(left.entity).insert(LeftInterest);
+    });
+
+    server_app.connect_client(&mut client_app);
+
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, TestComponent))
+        .id();
+
+    let client = **client_app.world().resource::<TestClientEntity>();
+    let mut visibility = server_app
+        .world_mut()
+        .get_mut::<ClientVisibility>(client)
+        .unwrap();
+    visibility.set_visibility(server_entity, true);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let client_entity = *client_app
+        .world()
+        .resource::<ServerEntityMap>()
+        .to_client()
+        .get(&server_entity)
+        .unwrap();
+
+    let mut visibility = server_app
+        .world_mut()
+        .get_mut::<ClientVisibility>(client)
+        .unwrap();
+    visibility.set_visibility(server_entity, false);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let entity_map = client_app.world().resource::<ServerEntityMap>();
+    assert!(entity_map.to_client().is_empty());
+
+    let ghost = client_app.world().entity(client_entity);
+    assert!(ghost.contains::<Ghost>());
+    assert!(ghost.contains::<LeftInterest>());
+    assert!(ghost.contains::<TestComponent>());
+    assert!(!ghost.contains::<Replicated>());
+
+    // Reverse visibility back.
+    let mut visibility = server_app
+        .world_mut()
+        .get_mut::<ClientVisibility>(client)
+        .unwrap();
+    visibility.set_visibility(server_entity, true);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let mut components = client_app
+        .world_mut()
+        .query_filtered::<Entity, (With<Replicated>, With<TestComponent>)>();
+    let new_entity = components
+        .single(client_app.world())
+        .expect("entity should be replicated as a new one");
+    assert_ne!(new_entity, client_entity);
+}
+
+#[test]
+fn propagation() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin {
+                visibility_policy: VisibilityPolicy::Whitelist,
+                ..ServerPlugin::new(PostUpdate)
+            }),
+        ))
+        .replicate::<ChildOf>()
+        .propagate_visibility::<ChildOf>()
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let root = server_app
+        .world_mut()
+        .spawn((Replicated, children![(Replicated, children![Replicated])]))
+        .id();
+
+    let client = **client_app.world().resource::<TestClientEntity>();
+    let mut visibility = server_app
+        .world_mut()
+        .get_mut::<ClientVisibility>(client)
+        .unwrap();
+    visibility.set_visibility(root, true);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let mut replicated = client_app.world_mut().query::<&Replicated>();
+    assert_eq!(replicated.iter(client_app.world()).len(), 3);
+
+    // Attach a new child.
+    server_app.world_mut().spawn((Replicated, ChildOf(root)));
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    assert_eq!(replicated.iter(client_app.world()).len(), 4);
+
+    // Visibility of non-root entities is overridden.
+    let child = server_app.world().get::<Children>(root).unwrap()[0];
+    let mut visibility = server_app
+        .world_mut()
+        .get_mut::<ClientVisibility>(client)
+        .unwrap();
+    visibility.set_visibility(child, false);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    assert_eq!(replicated.iter(client_app.world()).len(), 4);
+
+    let mut visibility = server_app
+        .world_mut()
+        .get_mut::<ClientVisibility>(client)
+        .unwrap();
+    visibility.set_visibility(root, false);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    assert_eq!(
+        replicated.iter(client_app.world()).len(),
+        0,
+        "all entities should be hidden with the root"
+    );
+}
+
+#[test]
+fn propagation_detach() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin {
+                visibility_policy: VisibilityPolicy::Whitelist,
+                ..ServerPlugin::new(PostUpdate)
+            }),
+        ))
+        .replicate::<ChildOf>()
+        .propagate_visibility::<ChildOf>()
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let root = server_app
+        .world_mut()
+        .spawn((Replicated, children![Replicated]))
+        .id();
+    let child = server_app.world().get::<Children>(root).unwrap()[0];
+
+    let client = **client_app.world().resource::<TestClientEntity>();
+    let mut visibility = server_app
+        .world_mut()
+        .get_mut::<ClientVisibility>(client)
+        .unwrap();
+    visibility.set_visibility(root, true);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let mut replicated = client_app.world_mut().query::<&Replicated>();
+    assert_eq!(replicated.iter(client_app.world()).len(), 2);
+
+    server_app.world_mut().entity_mut(child).remove::<ChildOf>();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    assert_eq!(
+        replicated.iter(client_app.world()).len(),
+        1,
+        "detached entity shouldn't keep the visibility of its old root"
+    );
+
+    let visibility = server_app.world().get::<ClientVisibility>(client).unwrap();
+    assert!(visibility.is_visible(root));
+    assert!(!visibility.is_visible(child));
+}
+
+#[test]
+fn dependencies() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin {
+                visibility_policy: VisibilityPolicy::Whitelist,
+                visibility_dependencies: true,
+                ..ServerPlugin::new(PostUpdate)
+            }),
+        ))
+        .replicate::<TestComponent>()
+        .replicate::<MappedComponent>()
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_dependency = server_app
+        .world_mut()
+        .spawn((Replicated, TestComponent))
+        .id();
+    let server_entity = server_app
+        .world_mut()
+        .spawn((Replicated, MappedComponent(server_dependency)))
+        .id();
+
+    let client = **client_app.world().resource::<TestClientEntity>();
+    let mut visibility = server_app
+        .world_mut()
+        .get_mut::<ClientVisibility>(client)
+        .unwrap();
+    visibility.set_visibility(server_entity, true);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let visibility = server_app.world().get::<ClientVisibility>(client).unwrap();
+    assert!(visibility.is_visible(server_dependency));
+
+    let mut mapped = client_app.world_mut().query::<&MappedComponent>();
+    let mapped_component = *mapped.single(client_app.world()).unwrap();
+    assert!(
+        client_app
+            .world()
+            .get::<TestComponent>(mapped_component.0)
+            .is_some(),
+        "dependency should be sent in the same tick"
+    );
+
+    server_app
+        .world_mut()
+        .entity_mut(server_entity)
+        .remove::<MappedComponent>();
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let visibility = server_app.world().get::<ClientVisibility>(client).unwrap();
+    assert!(
+        !visibility.is_visible(server_dependency),
+        "visibility should be revoked when no visible entity references the dependency"
+    );
+    assert!(visibility.is_visible(server_entity));
+    assert!(client_app.world().get_entity(mapped_component.0).is_err());
+
+    let mut components = client_app.world_mut().query::<&TestComponent>();
+    assert_eq!(components.iter(client_app.world()).len(), 0);
+}
+
+#[test]
+fn shared_dependency() {
+    let mut server_app = App::new();
+    let mut client_app = App::new();
+    for app in [&mut server_app, &mut client_app] {
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            RepliconPlugins.set(ServerPlugin {
+                visibility_policy: VisibilityPolicy::Whitelist,
+                visibility_dependencies: true,
+                ..ServerPlugin::new(PostUpdate)
+            }),
+        ))
+        .replicate::<TestComponent>()
+        .replicate::<MappedComponent>()
+        .finish();
+    }
+
+    server_app.connect_client(&mut client_app);
+
+    let server_dependency = server_app
+        .world_mut()
+        .spawn((Replicated, TestComponent))
+        .id();
+    let server_entity1 = server_app
+        .world_mut()
+        .spawn((Replicated, MappedComponent(server_dependency)))
+        .id();
+    let server_entity2 = server_app
+        .world_mut()
+        .spawn((Replicated, MappedComponent(server_dependency)))
+        .id();
+
+    let client = **client_app.world().resource::<TestClientEntity>();
+    let mut visibility = server_app
+        .world_mut()
+        .get_mut::<ClientVisibility>(client)
+        .unwrap();
+    visibility.set_visibility(server_entity1, true);
+    visibility.set_visibility(server_entity2, true);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let mut visibility = server_app
+        .world_mut()
+        .get_mut::<ClientVisibility>(client)
+        .unwrap();
+    visibility.set_visibility(server_entity1, false);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+    server_app.exchange_with_client(&mut client_app);
+
+    let visibility = server_app.world().get::<ClientVisibility>(client).unwrap();
+    assert!(
+        visibility.is_visible(server_dependency),
+        "dependency should stay visible while referenced by a visible entity"
+    );
+
+    server_app.world_mut().despawn(server_entity2);
+
+    server_app.update();
+    server_app.exchange_with_client(&mut client_app);
+    client_app.update();
+
+    let visibility = server_app.world().get::<ClientVisibility>(client).unwrap();
+    assert!(!visibility.is_visible(server_dependency));
+
+    let mut components = client_app.world_mut().query::<&TestComponent>();
+    assert_eq!(components.iter(client_app.world()).len(), 0);
+}
+
 #[test]
 fn signature() {
     let mut server_app = App::new();
@@ -328,3 +714,17 @@ fn signature() {
 
 #[derive(Component, Deserialize, Serialize)]
 struct TestComponent;
+
+#[derive(Component, Deserialize, Serialize, Clone, Copy)]
+struct MappedComponent(#[entities] Entity);
+
+#[derive(Component)]
+struct Ghost;
+
+#[derive(Component)]
+struct LeftInterest;
+
+fn keep_ghost(ctx: &DespawnCtx, mut entity: EntityWorldMut) {
+    assert!(ctx.visibility_lost);
+    entity.remove::<Replicated>().insert(Ghost);
+}